@@ -8,6 +8,11 @@ if [ ! -f common.toml ]; then
   crypto-election-node generate-template common.toml --validators-count $node_count
 fi
 
+# Key of pass code fingerprints, shared by all nodes keeping personal data.
+if [ ! -f pass-code.key ]; then
+  head -c 32 /dev/urandom | od -An -tx1 | tr -d ' \n' > pass-code.key
+fi
+
 for i in $(seq 0 $((node_count - 1)))
 do
   if [ ! -f $((i + 1))/sec.toml ] && [ ! -f $((i + 1))/pub.toml ]; then
@@ -29,7 +34,7 @@ for i in $(seq 0 $((node_count - 1)))
 do
   public_port=$((start_public_port + i))
   private_port=$((public_port + node_count))
-  crypto-election-node run --node-config $((i + 1))/node.toml --db-path $((i + 1))/db --public-api-address 0.0.0.0:${public_port} --master-key-pass pass &
+  PII_STORE_PATH=$((i + 1))/pii PASS_CODE_KEY=$(cat pass-code.key) CONTACT_SINK_PATH=$((i + 1))/contact-codes crypto-election-node run --node-config $((i + 1))/node.toml --db-path $((i + 1))/db --public-api-address 0.0.0.0:${public_port} --master-key-pass pass &
   echo "new node with ports: $public_port (public) and $private_port (private)"
   sleep 1
 done
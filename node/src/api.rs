@@ -1,11 +1,29 @@
 use std::{collections::HashMap, fmt::Debug, iter::FromIterator};
 
-use exonum::runtime::CallerAddress;
+use exonum::{
+    crypto::{self, Hash},
+    runtime::CallerAddress,
+};
 use exonum_rust_runtime::api::{self, ServiceApiBuilder, ServiceApiState};
+use geojson::{Feature, FeatureCollection, Geometry, JsonObject};
+use serde_json::json;
 
 use crate::{
-    model::{public_api::*, AdministrationAddress, Election, ElectionAddress},
+    contact_verifier::ContactVerifier,
+    model::{
+        contact,
+        encryption::ElectionEncryption,
+        pii::{PiiField, PiiOpening},
+        public_api::*,
+        ring_ballot::ElectionRing,
+        transactions::AttestPassCode,
+        voting_token::TokenIssuance,
+        AdministrationAddress, Election, ElectionAddress, LocationRecord, ParticipantAddress,
+        ResultsVisibility,
+    },
+    pii_store::PiiStore,
     schema::SchemaImpl,
+    tx_behavior::ElectionInterfaceMut,
 };
 use chrono::{DateTime, Utc};
 
@@ -21,11 +39,34 @@ impl PublicApi {
         builder
             .public_scope()
             .endpoint("v1/participants/info", Self::participant_info)
+            .endpoint(
+                "v1/participants/current-address",
+                Self::current_participant_address,
+            )
+            .endpoint("v1/participants/recovery", Self::participant_recovery)
+            .endpoint("v1/participants/locations", Self::participant_locations)
             .endpoint("v1/administrations/info", Self::administration_info)
+            .endpoint(
+                "v1/administrations/current-address",
+                Self::current_administration_address,
+            )
             .endpoint("v1/administration/tree", Self::administrations_tree)
+            .endpoint("v1/administrations/geojson", Self::administrations_geojson)
+            .endpoint("v1/administrations/invitations", Self::invitation_report)
+            .endpoint(
+                "v1/administrations/proposals",
+                Self::administration_proposals,
+            )
             .endpoint("v1/elections/info", Self::election_info)
             .endpoint("v1/elections/active", Self::active_elections)
             .endpoint("v1/elections/result", Self::election_results)
+            .endpoint("v1/elections/result-proof", Self::election_results_proof)
+            .endpoint("v1/elections/receipt", Self::vote_receipt)
+            .endpoint("v1/elections/unrevealed", Self::unrevealed_ballots)
+            .endpoint("v1/elections/encryption", Self::election_encryption)
+            .endpoint("v1/elections/voting-token", Self::voting_token)
+            .endpoint("v1/elections/ring", Self::election_ring)
+            .endpoint("v1/elections/mixnet-ballots", Self::mixnet_ballots)
             .endpoint("v1/elections/suggested-for", Self::elections_suggested_for);
     }
 
@@ -51,6 +92,366 @@ impl PublicApi {
         .map_err(api::Error::internal)
     }
 
+    /// Gets the current address of participant, following key rotations
+    ///
+    /// ## API address
+    /// `v1/participants/current-address`
+    pub async fn current_participant_address(
+        state: ServiceApiState,
+        query: PubKeyQuery,
+    ) -> api::Result<ParticipantAddress> {
+        SchemaImpl::new(state.service_data())
+            .public
+            .current_participant_address(&CallerAddress::from_key(query.key))
+            .ok_or_else(api::Error::not_found)
+    }
+
+    /// Gets the current address of administration, following successions
+    ///
+    /// ## API address
+    /// `v1/administrations/current-address`
+    pub async fn current_administration_address(
+        state: ServiceApiState,
+        query: PubKeyQuery,
+    ) -> api::Result<AdministrationAddress> {
+        SchemaImpl::new(state.service_data())
+            .public
+            .current_administration_address(&CallerAddress::from_key(query.key))
+            .ok_or_else(api::Error::not_found)
+    }
+
+    /// Gets locations submitted by participant, from the oldest to the newest
+    ///
+    /// ## API address
+    /// `v1/participants/locations`
+    pub async fn participant_locations(
+        state: ServiceApiState,
+        query: PubKeyQuery,
+    ) -> api::Result<Vec<LocationRecord>> {
+        let schema = SchemaImpl::new(state.service_data()).public;
+        let participant = CallerAddress::from_key(query.key);
+        if !schema.participants.contains(&participant) {
+            return Err(api::Error::not_found());
+        }
+
+        Ok(schema
+            .participant_location_history
+            .get(&participant)
+            .iter()
+            .collect())
+    }
+
+    /// Gets guardians of participant and pending recovery of its account
+    ///
+    /// ## API address
+    /// `v1/participants/recovery`
+    pub async fn participant_recovery(
+        state: ServiceApiState,
+        query: PubKeyQuery,
+    ) -> api::Result<RecoveryInfo> {
+        let schema = SchemaImpl::new(state.service_data()).public;
+        let participant = CallerAddress::from_key(query.key);
+        if !schema.participants.contains(&participant) {
+            return Err(api::Error::not_found());
+        }
+
+        Ok(RecoveryInfo {
+            guardians: schema.participant_guardians.get(&participant),
+            recovery: schema.participant_recoveries.get(&participant),
+        })
+    }
+
+    /// Gets signers of administration and its pending proposals
+    ///
+    /// ## API address
+    /// `v1/administrations/proposals`
+    pub async fn administration_proposals(
+        state: ServiceApiState,
+        query: PubKeyQuery,
+    ) -> api::Result<ProposalsInfo> {
+        let schema = SchemaImpl::new(state.service_data()).public;
+        let administration = CallerAddress::from_key(query.key);
+        if !schema.administrations.contains(&administration) {
+            return Err(api::Error::not_found());
+        }
+        let now = Self::get_time(&state)?;
+
+        let pending = schema
+            .administration_proposals
+            .get(&administration)
+            .iter()
+            .filter_map(|proposal_id| {
+                let proposal = schema.proposals.get(&proposal_id)?;
+                if proposal.is_pending(now) {
+                    Some(PendingProposal {
+                        proposal_id,
+                        proposal,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        Ok(ProposalsInfo {
+            signers: schema.administration_signers.get(&administration),
+            pending,
+        })
+    }
+
+    /// Gets registration invitations issued by administration, used and unused, without
+    /// their keys
+    ///
+    /// ## API address
+    /// `v1/administrations/invitations`
+    pub async fn invitation_report(
+        state: ServiceApiState,
+        query: PubKeyQuery,
+    ) -> api::Result<InvitationReport> {
+        let schema = SchemaImpl::new(state.service_data()).public;
+        let administration = CallerAddress::from_key(query.key);
+        if !schema.administrations.contains(&administration) {
+            return Err(api::Error::not_found());
+        }
+        let now = Self::get_time(&state)?;
+
+        let mut report = InvitationReport {
+            administration,
+            used: 0,
+            expired: 0,
+            unused: 0,
+            invitations: Vec::new(),
+        };
+        for key in schema
+            .administration_invitations
+            .get(&administration)
+            .iter()
+        {
+            let invitation = schema.invitations.get(&key).unwrap();
+            if invitation.is_used() {
+                report.used += 1;
+            } else if invitation.is_expired(now) {
+                report.expired += 1;
+            } else {
+                report.unused += 1;
+            }
+            report.invitations.push(invitation);
+        }
+
+        Ok(report)
+    }
+
+    /// Plugs in methods handling personal data of participants kept in `store`
+    pub fn wire_pii(builder: &mut ServiceApiBuilder, store: PiiStore) {
+        let private_store = store.clone();
+
+        builder
+            .public_scope()
+            .endpoint("v1/participants/pii/check", Self::check_pii)
+            .endpoint_mut("v1/participants/pii", move |state, query| {
+                Self::submit_pii(store.clone(), state, query)
+            });
+        let report_store = store.clone();
+        builder
+            .private_scope()
+            .endpoint("v1/participants/pii", move |state, query| {
+                Self::participant_pii(private_store.clone(), state, query)
+            })
+            .endpoint("v1/participants/dedup-report", move |state, _query: ()| {
+                Self::dedup_report(report_store.clone(), state)
+            });
+    }
+
+    /// Plugs in contact verification methods
+    pub fn wire_contacts(
+        builder: &mut ServiceApiBuilder,
+        store: PiiStore,
+        verifier: ContactVerifier,
+    ) {
+        builder
+            .public_scope()
+            .endpoint_mut("v1/participants/contacts/verify", move |state, query| {
+                Self::verify_contact(store.clone(), verifier.clone(), state, query)
+            });
+    }
+
+    /// Sends verification code to email or phone number of participant, stored on this
+    /// node, and publishes its challenge. Code is confirmed with `ConfirmContact`
+    /// transaction. Request is signed by participant and served by validator nodes only
+    ///
+    /// ## API address
+    /// `v1/participants/contacts/verify` (POST)
+    pub async fn verify_contact(
+        store: PiiStore,
+        verifier: ContactVerifier,
+        state: ServiceApiState,
+        query: ContactVerificationQuery,
+    ) -> api::Result<()> {
+        if !contact::is_contact(query.field) {
+            return Err(api::Error::bad_request());
+        }
+
+        // Challenge of the code is published by validator right away.
+        let broadcaster = state.broadcaster().ok_or_else(|| {
+            api::Error::new(api::HttpStatusCode::SERVICE_UNAVAILABLE)
+                .title("Contacts are verified by validator nodes only")
+        })?;
+
+        let message = contact::request_message(
+            &CallerAddress::from_key(query.participant),
+            query.field,
+            query.requested_at,
+        );
+        let now = Self::get_time(&state)?;
+        let lifetime = contact::request_lifetime();
+        if !crypto::verify(&query.signature, message.as_ref(), &query.participant)
+            || now - query.requested_at > lifetime
+            || query.requested_at - now > lifetime
+        {
+            return Err(api::Error::bad_request());
+        }
+
+        let participant = SchemaImpl::new(state.service_data())
+            .public
+            .participants
+            .get(&CallerAddress::from_key(query.participant))
+            .ok_or_else(api::Error::not_found)?;
+        let opening = store
+            .get(participant.pii_commitment(query.field))
+            .ok_or_else(api::Error::not_found)?;
+
+        if !verifier.register_request(&participant.addr) {
+            return Err(api::Error::new(api::HttpStatusCode::TOO_MANY_REQUESTS)
+                .title("Verification code has been requested recently"));
+        }
+        let challenge = verifier
+            .send_code(&participant.addr, query.field, &opening.value)
+            .await
+            .map_err(api::Error::internal)?;
+        broadcaster
+            .publish_contact_challenge((), challenge)
+            .await
+            .map_err(api::Error::internal)
+    }
+
+    /// Stores openings of personal data commitments of participant on this node. Validator
+    /// attests fingerprint of the submitted pass code, unless it is attested yet
+    ///
+    /// ## API address
+    /// `v1/participants/pii` (POST)
+    pub async fn submit_pii(
+        store: PiiStore,
+        state: ServiceApiState,
+        query: PiiSubmission,
+    ) -> api::Result<()> {
+        let participant = SchemaImpl::new(state.service_data())
+            .public
+            .participants
+            .get(&CallerAddress::from_key(query.participant))
+            .ok_or_else(api::Error::not_found)?;
+
+        // Only data committed on-chain is accepted.
+        if !query
+            .openings
+            .iter()
+            .all(|opening| opening.opens(&participant))
+        {
+            return Err(api::Error::bad_request());
+        }
+
+        let attestation = query
+            .openings
+            .iter()
+            .find(|opening| opening.field == PiiField::PassCode)
+            .filter(|_| !participant.is_pass_code_attested())
+            .map(|opening| AttestPassCode {
+                participant: participant.addr,
+                fingerprint: store.pass_code_fingerprint(&opening.value),
+            });
+        store.put(query.openings).map_err(api::Error::internal)?;
+
+        if let (Some(tx), Some(broadcaster)) = (attestation, state.broadcaster()) {
+            broadcaster
+                .attest_pass_code((), tx)
+                .await
+                .map_err(api::Error::internal)?;
+        }
+        Ok(())
+    }
+
+    /// Gets personal data of participant stored on this node
+    ///
+    /// ## API address
+    /// `v1/participants/pii` (private)
+    pub async fn participant_pii(
+        store: PiiStore,
+        state: ServiceApiState,
+        query: PubKeyQuery,
+    ) -> api::Result<Vec<PiiOpening>> {
+        let participant = SchemaImpl::new(state.service_data())
+            .public
+            .participants
+            .get(&CallerAddress::from_key(query.key))
+            .ok_or_else(api::Error::not_found)?;
+
+        Ok([PiiField::Email, PiiField::PhoneNumber, PiiField::PassCode]
+            .iter()
+            .filter_map(|&field| store.get(participant.pii_commitment(field)))
+            .collect())
+    }
+
+    /// Reports participants which have registered the same pass code under different
+    /// fingerprints, judging by pass codes stored on this node
+    ///
+    /// ## API address
+    /// `v1/participants/dedup-report` (private)
+    pub async fn dedup_report(store: PiiStore, state: ServiceApiState) -> api::Result<DedupReport> {
+        let schema = SchemaImpl::new(state.service_data());
+
+        let mut report = DedupReport::default();
+        let mut owners: HashMap<Hash, Vec<ParticipantAddress>> = HashMap::new();
+        for participant in schema.public.participants.values() {
+            let opening = match store.get(&participant.pass_code_commitment) {
+                Some(opening) => opening,
+                None => {
+                    report.unverified.push(participant.addr);
+                    continue;
+                }
+            };
+            let fingerprint = store.pass_code_fingerprint(&opening.value);
+            if participant.is_pass_code_attested()
+                && fingerprint != participant.pass_code_fingerprint
+            {
+                report.mismatched.push(participant.addr);
+            }
+            owners
+                .entry(fingerprint)
+                .or_default()
+                .push(participant.addr);
+        }
+        report.duplicates = owners
+            .into_iter()
+            .map(|(_, participants)| participants)
+            .filter(|participants| participants.len() > 1)
+            .collect();
+
+        Ok(report)
+    }
+
+    /// Checks claimed value of personal data field against the commitment of participant
+    ///
+    /// ## API address
+    /// `v1/participants/pii/check`
+    pub async fn check_pii(state: ServiceApiState, query: PiiCheckQuery) -> api::Result<bool> {
+        let participant = SchemaImpl::new(state.service_data())
+            .public
+            .participants
+            .get(&CallerAddress::from_key(query.participant))
+            .ok_or_else(api::Error::not_found)?;
+
+        Ok(PiiOpening::new(query.field, &query.value, query.salt).opens(&participant))
+    }
+
     /// Gets complete administration info
     ///
     /// ## API address
@@ -82,8 +483,14 @@ impl PublicApi {
             (schema.public.elections, schema.election_history)
         };
 
-        ProofedInfo::try_from_indexes(&state.data(), "elections", query.key, index_pair)
-            .map_err(api::Error::internal)
+        let mut info =
+            ProofedInfo::try_from_indexes(&state.data(), "elections", query.key, index_pair)
+                .map_err(api::Error::internal)?;
+        // History lists ballot transactions, which reveal hidden vote counts.
+        if Self::check_results_visible(&state, &query.key).is_err() {
+            info.history = None;
+        }
+        Ok(info)
     }
 
     #[doc(hidden)]
@@ -134,6 +541,31 @@ impl PublicApi {
             .map_err(api::Error::internal)
     }
 
+    /// Fails with `not found` while vote counts of election are hidden by its
+    /// results visibility policy.
+    fn check_results_visible(
+        state: &ServiceApiState,
+        election: &ElectionAddress,
+    ) -> api::Result<()> {
+        let election = SchemaImpl::new(state.service_data())
+            .public
+            .elections
+            .get(election);
+
+        let is_hidden = match election {
+            Some(election) if election.results_visibility != ResultsVisibility::Live => {
+                !election.are_results_visible(Self::get_time(state)?)
+            }
+            _ => false,
+        };
+
+        if is_hidden {
+            Err(api::Error::not_found())
+        } else {
+            Ok(())
+        }
+    }
+
     pub async fn elections_suggested_for(
         state: ServiceApiState,
         query: PubKeyQuery,
@@ -163,11 +595,17 @@ impl PublicApi {
                     .map(|it| Box::new(it) as Box<dyn Iterator<Item = _>>)
                     .unwrap_or_else(|| Box::new(std::iter::empty()))
                     .map(|election: Election| {
-                        if schema.public.voted_yet(&election.addr, &participant_addr) {
-                            let results = schema.public.election_results(&election.addr).unwrap();
-                            (election, true, &results).into()
+                        if !schema.public.voted_yet(&election.addr, &participant_addr) {
+                            return election.into();
+                        }
+                        let results = if election.are_results_visible(time) {
+                            schema.public.election_results(&election.addr)
                         } else {
-                            election.into()
+                            None
+                        };
+                        match results {
+                            Some(results) => (election, true, &results).into(),
+                            None => (election, true).into(),
                         }
                     })
                     .collect(),
@@ -235,13 +673,206 @@ impl PublicApi {
         )
     }
 
+    /// Returns areas of all administrations as GeoJSON features, principals before
+    /// their subordinates.
+    pub async fn administrations_geojson(
+        state: ServiceApiState,
+        _query: (),
+    ) -> api::Result<FeatureCollection> {
+        let schema = SchemaImpl::new(state.service_data());
+
+        let mut features = Vec::new();
+        let mut pending: Vec<_> = schema.public.subordinates(None);
+        pending.reverse();
+        while let Some(addr) = pending.pop() {
+            let administration = schema
+                .public
+                .administrations
+                .get(&addr)
+                .ok_or_else(|| api::Error::internal("Administration index is inconsistent"))?;
+
+            let mut properties = JsonObject::new();
+            properties.insert("address".to_owned(), json!(addr));
+            properties.insert("name".to_owned(), json!(administration.name));
+            properties.insert(
+                "level".to_owned(),
+                json!(administration.administration_level),
+            );
+            properties.insert(
+                "principal".to_owned(),
+                json!(administration.principal_key.0),
+            );
+            features.push(Feature {
+                bbox: None,
+                geometry: Some(Geometry::from(&administration.area)),
+                id: None,
+                properties: Some(properties),
+                foreign_members: None,
+            });
+
+            let mut children = schema.public.subordinates(Some(&addr));
+            children.reverse();
+            pending.extend(children);
+        }
+
+        Ok(FeatureCollection {
+            bbox: None,
+            features,
+            foreign_members: None,
+        })
+    }
+
     pub async fn election_results(
         state: ServiceApiState,
         query: KeyQuery<ElectionAddress>,
     ) -> api::Result<HashMap<i32, u32>> {
+        Self::check_results_visible(&state, &query.key)?;
+
         SchemaImpl::new(state.service_data())
             .public
             .election_results(&query.key)
             .ok_or_else(api::Error::not_found)
     }
+
+    /// Gets counted votes of election with the proof to the block
+    ///
+    /// ## API address
+    /// `v1/elections/result-proof`
+    pub async fn election_results_proof(
+        state: ServiceApiState,
+        query: KeyQuery<ElectionAddress>,
+    ) -> api::Result<TallyProof> {
+        Self::check_results_visible(&state, &query.key)?;
+
+        let schema = SchemaImpl::new(state.service_data()).public;
+
+        TallyProof::try_from_index(
+            &state.data(),
+            "election_tallies",
+            &schema.election_tallies,
+            query.key,
+        )
+        .map_err(api::Error::internal)
+    }
+
+    /// Gets receipt proving that vote of participant is recorded. Receipt holds the
+    /// selected option, so it is hidden together with vote counts.
+    ///
+    /// ## API address
+    /// `v1/elections/receipt`
+    pub async fn vote_receipt(
+        state: ServiceApiState,
+        query: ElectionParticipantQuery,
+    ) -> api::Result<VoteReceipt> {
+        Self::check_results_visible(&state, &query.election)?;
+
+        let schema = SchemaImpl::new(state.service_data()).public;
+        let votes = schema.election_votes.get(&query.election);
+
+        VoteReceipt::try_from_indexes(
+            &state.data(),
+            "election_vote_roots",
+            &schema.election_vote_roots,
+            &votes,
+            query.election,
+            CallerAddress::from_key(query.participant),
+        )
+        .map_err(api::Error::internal)
+    }
+
+    /// Gets participants which have committed ballots but have not revealed them
+    ///
+    /// ## API address
+    /// `v1/elections/unrevealed`
+    pub async fn unrevealed_ballots(
+        state: ServiceApiState,
+        query: KeyQuery<ElectionAddress>,
+    ) -> api::Result<UnrevealedBallots> {
+        let participants = SchemaImpl::new(state.service_data())
+            .public
+            .unrevealed_commitments(&query.key)
+            .ok_or_else(api::Error::not_found)?;
+
+        Ok(UnrevealedBallots {
+            election: query.key,
+            count: participants.len() as u32,
+            participants,
+        })
+    }
+
+    /// Gets joint encryption key and tally state of encrypted election
+    ///
+    /// ## API address
+    /// `v1/elections/encryption`
+    pub async fn election_encryption(
+        state: ServiceApiState,
+        query: KeyQuery<ElectionAddress>,
+    ) -> api::Result<ElectionEncryption> {
+        let mut encryption = SchemaImpl::new(state.service_data())
+            .public
+            .election_encryption
+            .get(&query.key)
+            .ok_or_else(api::Error::not_found)?;
+        // Voters need the key and encrypted tally while results are hidden, but not counts.
+        if Self::check_results_visible(&state, &query.key).is_err() {
+            encryption.ballots_count = 0;
+            encryption.results.clear();
+        }
+        Ok(encryption)
+    }
+
+    /// Gets voting token signing session of participant
+    ///
+    /// ## API address
+    /// `v1/elections/voting-token`
+    pub async fn voting_token(
+        state: ServiceApiState,
+        query: ElectionParticipantQuery,
+    ) -> api::Result<TokenIssuance> {
+        SchemaImpl::new(state.service_data())
+            .public
+            .token_issuance
+            .get(&query.election)
+            .get(&CallerAddress::from_key(query.participant))
+            .ok_or_else(api::Error::not_found)
+    }
+
+    /// Gets ring keys of election electorate
+    ///
+    /// ## API address
+    /// `v1/elections/ring`
+    pub async fn election_ring(
+        state: ServiceApiState,
+        query: KeyQuery<ElectionAddress>,
+    ) -> api::Result<ElectionRing> {
+        let schema = SchemaImpl::new(state.service_data());
+        if !schema.public.elections.contains(&query.key) {
+            return Err(api::Error::not_found());
+        }
+
+        Ok(ElectionRing {
+            keys: schema.public.election_ring(&query.key),
+        })
+    }
+
+    /// Gets decrypted ballots of mixnet election
+    ///
+    /// ## API address
+    /// `v1/elections/mixnet-ballots`
+    pub async fn mixnet_ballots(
+        state: ServiceApiState,
+        query: KeyQuery<ElectionAddress>,
+    ) -> api::Result<MixnetBallots> {
+        Self::check_results_visible(&state, &query.key)?;
+
+        let ballots = SchemaImpl::new(state.service_data())
+            .public
+            .mixnet_results(&query.key)
+            .ok_or_else(api::Error::not_found)?;
+
+        Ok(MixnetBallots {
+            election: query.key,
+            ballots,
+        })
+    }
 }
@@ -0,0 +1,165 @@
+//! Delivery of verification codes to participant contacts.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::OpenOptions,
+    io::Write,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use lettre::{transport::smtp::authentication::Credentials, Message, SmtpTransport, Transport};
+
+use crate::model::{
+    contact, pii::PiiField, transactions::PublishContactChallenge, ParticipantAddress,
+};
+
+/// Channel delivering text messages to contacts, such as mail server or SMS provider.
+pub trait ContactGateway: Send + Sync {
+    fn send(&self, address: &str, text: &str) -> anyhow::Result<()>;
+}
+
+/// Sends messages through SMTP relay.
+#[derive(Debug, Clone)]
+pub struct SmtpGateway {
+    pub relay: String,
+    pub username: String,
+    pub password: String,
+    /// Mailbox of sender, e.g. `Elections <noreply@example.com>`.
+    pub from: String,
+}
+
+impl ContactGateway for SmtpGateway {
+    fn send(&self, address: &str, text: &str) -> anyhow::Result<()> {
+        let email = Message::builder()
+            .from(self.from.parse()?)
+            .to(address.parse()?)
+            .subject("Verification code")
+            .body(text.to_owned())?;
+        let credentials = Credentials::new(self.username.clone(), self.password.clone());
+        SmtpTransport::relay(&self.relay)?
+            .credentials(credentials)
+            .build()
+            .send(&email)?;
+        Ok(())
+    }
+}
+
+/// Sends messages through HTTP API of SMS provider, which accepts form with `from`, `to`
+/// and `text` fields authorised by bearer token.
+#[derive(Debug, Clone)]
+pub struct HttpSmsGateway {
+    pub url: String,
+    pub token: String,
+    pub from: String,
+}
+
+impl ContactGateway for HttpSmsGateway {
+    fn send(&self, address: &str, text: &str) -> anyhow::Result<()> {
+        reqwest::blocking::Client::new()
+            .post(&self.url)
+            .bearer_auth(&self.token)
+            .form(&[
+                ("from", self.from.as_str()),
+                ("to", address),
+                ("text", text),
+            ])
+            .send()?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Appends messages to file as `address<TAB>text` lines, in place of real gateway.
+#[derive(Debug, Clone)]
+pub struct FileSinkGateway {
+    pub path: PathBuf,
+}
+
+impl ContactGateway for FileSinkGateway {
+    fn send(&self, address: &str, text: &str) -> anyhow::Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}\t{}", address, text)?;
+        Ok(())
+    }
+}
+
+/// Sends verification codes to contacts and limits how often participants request them.
+#[derive(Clone)]
+pub struct ContactVerifier {
+    email_gateway: Arc<dyn ContactGateway>,
+    sms_gateway: Arc<dyn ContactGateway>,
+    last_requests: Arc<Mutex<HashMap<ParticipantAddress, Instant>>>,
+}
+
+impl ContactVerifier {
+    /// Minimal interval between codes sent to the same participant.
+    pub const MIN_REQUEST_INTERVAL: Duration = Duration::from_secs(60);
+
+    pub fn new(
+        email_gateway: Arc<dyn ContactGateway>,
+        sms_gateway: Arc<dyn ContactGateway>,
+    ) -> Self {
+        Self {
+            email_gateway,
+            sms_gateway,
+            last_requests: Arc::default(),
+        }
+    }
+
+    /// Creates verifier which writes both emails and SMS to the file.
+    pub fn file_sink(path: impl Into<PathBuf>) -> Self {
+        let gateway = Arc::new(FileSinkGateway { path: path.into() });
+        Self::new(gateway.clone(), gateway)
+    }
+
+    /// Sends new code to contact `address` of participant and returns its challenge,
+    /// which should be published by validator.
+    pub async fn send_code(
+        &self,
+        participant: &ParticipantAddress,
+        field: PiiField,
+        address: &str,
+    ) -> anyhow::Result<PublishContactChallenge> {
+        let gateway = match field {
+            PiiField::Email => self.email_gateway.clone(),
+            PiiField::PhoneNumber => self.sms_gateway.clone(),
+            PiiField::PassCode => anyhow::bail!("Pass code is not a contact"),
+        };
+        let code = contact::random_code(&mut rand::thread_rng());
+        let address = address.to_owned();
+        let text = format!("Your verification code: {}", code);
+        // Gateways block on network, so they are kept off the API threads.
+        tokio::task::spawn_blocking(move || gateway.send(&address, &text)).await??;
+
+        Ok(PublishContactChallenge {
+            participant: *participant,
+            field,
+            challenge: contact::code_challenge(participant, field, &code),
+        })
+    }
+
+    /// Registers request of code by participant. Returns `false` if the participant has
+    /// requested a code less than `MIN_REQUEST_INTERVAL` ago.
+    pub fn register_request(&self, participant: &ParticipantAddress) -> bool {
+        let now = Instant::now();
+        let mut last_requests = self.last_requests.lock().unwrap();
+        last_requests.retain(|_, requested| now - *requested < Self::MIN_REQUEST_INTERVAL);
+        if last_requests.contains_key(participant) {
+            return false;
+        }
+        last_requests.insert(*participant, now);
+        true
+    }
+}
+
+impl fmt::Debug for ContactVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContactVerifier").finish()
+    }
+}
@@ -0,0 +1,210 @@
+//! Encrypted single-choice ballots and threshold decryption of their sum.
+
+use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
+use rand::{CryptoRng, RngCore};
+
+use super::{
+    decode_point, dkg,
+    elgamal::{self, Ciphertext, EncryptionKey},
+    proofs::{ChoiceProof, DleqProof},
+    G,
+};
+
+/// Ballot with one ciphertext per election option.
+///
+/// Every ciphertext is proven to encrypt 0 or 1 and their sum is proven to encrypt 1,
+/// so ballot selects exactly one option.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncryptedBallot {
+    pub choices: Vec<Ciphertext>,
+    pub choice_proofs: Vec<ChoiceProof>,
+    pub sum_proof: DleqProof,
+}
+
+impl EncryptedBallot {
+    /// Encrypts selection of option with index `selected` out of `options_count`.
+    ///
+    /// `context` must be unique for the pair of election and voter.
+    pub fn encrypt<R: RngCore + CryptoRng>(
+        context: &[u8],
+        key: &EncryptionKey,
+        options_count: usize,
+        selected: usize,
+        rng: &mut R,
+    ) -> Self {
+        assert!(selected < options_count, "Selected option is out of range");
+
+        let mut choices = Vec::with_capacity(options_count);
+        let mut choice_proofs = Vec::with_capacity(options_count);
+        let mut randomness_sum = Scalar::zero();
+
+        for index in 0..options_count {
+            let message = index == selected;
+            let randomness = Scalar::random(rng);
+            let ciphertext = Ciphertext::encrypt(key, message as u64, &randomness);
+            choice_proofs.push(ChoiceProof::prove(
+                context,
+                key,
+                &ciphertext,
+                message,
+                &randomness,
+                rng,
+            ));
+            choices.push(ciphertext);
+            randomness_sum += randomness;
+        }
+
+        let sum_proof = DleqProof::prove(context, (&G, &key.0), &randomness_sum, rng);
+
+        Self {
+            choices,
+            choice_proofs,
+            sum_proof,
+        }
+    }
+
+    pub fn verify(&self, context: &[u8], key: &EncryptionKey, options_count: usize) -> bool {
+        if self.choices.len() != options_count || self.choice_proofs.len() != options_count {
+            return false;
+        }
+
+        let choices_valid = self
+            .choices
+            .iter()
+            .zip(&self.choice_proofs)
+            .all(|(ciphertext, proof)| proof.verify(context, key, ciphertext));
+
+        let sum = self.sum();
+        choices_valid
+            && self
+                .sum_proof
+                .verify(context, (&G, &key.0), (&sum.a, &(sum.b - G)))
+    }
+
+    fn sum(&self) -> Ciphertext {
+        self.choices
+            .iter()
+            .fold(Ciphertext::zero(), |sum, ciphertext| sum + *ciphertext)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.choices.len() * 192 + 64);
+        for ciphertext in &self.choices {
+            bytes.extend_from_slice(&ciphertext.to_bytes());
+        }
+        for proof in &self.choice_proofs {
+            bytes.extend_from_slice(&proof.to_bytes());
+        }
+        bytes.extend_from_slice(&self.sum_proof.to_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 64 || (bytes.len() - 64) % 192 != 0 {
+            return None;
+        }
+        let count = (bytes.len() - 64) / 192;
+        let (choices, rest) = bytes.split_at(count * 64);
+        let (choice_proofs, sum_proof) = rest.split_at(count * 128);
+        Some(Self {
+            choices: choices
+                .chunks(64)
+                .map(Ciphertext::from_bytes)
+                .collect::<Option<_>>()?,
+            choice_proofs: choice_proofs
+                .chunks(128)
+                .map(ChoiceProof::from_bytes)
+                .collect::<Option<_>>()?,
+            sum_proof: DleqProof::from_bytes(sum_proof)?,
+        })
+    }
+}
+
+/// Decryption shares of a trustee for every ciphertext of a tally.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartialDecryption {
+    pub shares: Vec<RistrettoPoint>,
+    pub proofs: Vec<DleqProof>,
+}
+
+impl PartialDecryption {
+    pub fn create<R: RngCore + CryptoRng>(
+        context: &[u8],
+        secret: &Scalar,
+        tally: &[Ciphertext],
+        rng: &mut R,
+    ) -> Self {
+        let shares = tally
+            .iter()
+            .map(|ciphertext| elgamal::decryption_share(secret, ciphertext))
+            .collect();
+        let proofs = tally
+            .iter()
+            .map(|ciphertext| DleqProof::prove(context, (&G, &ciphertext.a), secret, rng))
+            .collect();
+        Self { shares, proofs }
+    }
+
+    /// Checks that shares were made with the secret of `key_share`.
+    pub fn verify(&self, context: &[u8], key_share: &EncryptionKey, tally: &[Ciphertext]) -> bool {
+        self.shares.len() == tally.len()
+            && self.proofs.len() == tally.len()
+            && tally.iter().zip(&self.shares).zip(&self.proofs).all(
+                |((ciphertext, share), proof)| {
+                    proof.verify(context, (&G, &ciphertext.a), (&key_share.0, share))
+                },
+            )
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.shares.len() * 96);
+        for share in &self.shares {
+            bytes.extend_from_slice(share.compress().as_bytes());
+        }
+        for proof in &self.proofs {
+            bytes.extend_from_slice(&proof.to_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() % 96 != 0 {
+            return None;
+        }
+        let (shares, proofs) = bytes.split_at(bytes.len() / 96 * 32);
+        Some(Self {
+            shares: shares.chunks(32).map(decode_point).collect::<Option<_>>()?,
+            proofs: proofs
+                .chunks(64)
+                .map(DleqProof::from_bytes)
+                .collect::<Option<_>>()?,
+        })
+    }
+}
+
+/// Decrypts tally with decryption shares of at least `threshold` trustees, given with
+/// positions of their key shares.
+///
+/// Every count is searched in `[0, max_count]`.
+pub fn decrypt_tally(
+    tally: &[Ciphertext],
+    decryptions: &[(usize, PartialDecryption)],
+    max_count: u64,
+) -> Option<Vec<u64>> {
+    let positions: Vec<usize> = decryptions.iter().map(|(position, _)| *position).collect();
+    let coefficients = dkg::lagrange_coefficients(&positions);
+    tally
+        .iter()
+        .enumerate()
+        .map(|(index, ciphertext)| {
+            let plain = elgamal::combine_decryption(
+                ciphertext,
+                coefficients
+                    .iter()
+                    .zip(decryptions)
+                    .map(|(coefficient, (_, decryption))| (*coefficient, decryption.shares[index])),
+            );
+            elgamal::discrete_log(&plain, max_count)
+        })
+        .collect()
+}
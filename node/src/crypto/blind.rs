@@ -0,0 +1,170 @@
+//! Blind Schnorr signatures.
+//!
+//! Signer with key `X = x * G` commits to nonce `R = k * G`. Requester blinds commitment as
+//! `R' = R + alpha * G + beta * X` and sends challenge `c = H(X, R', m) + beta`. Signer
+//! responds with `s = k + c * x`, which is unblinded to `s' = s + alpha`. Pair `(R', s')`
+//! is a Schnorr signature of `m` which signer can not link to the signing session.
+//!
+//! Signer must never answer two challenges with the same nonce, since it reveals `x`.
+//! Signer must not keep several sessions open at once either: requester choosing
+//! challenges after seeing many commitments can forge one signature more than it has got
+//! (ROS attack), so every session is answered or abandoned before the next one opens.
+
+use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
+use rand::{CryptoRng, RngCore};
+
+use super::{decode_point, decode_scalar, split_chunks, Transcript, G};
+
+/// Public key of signer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerificationKey(pub RistrettoPoint);
+
+impl VerificationKey {
+    pub fn from_secret(secret: &Scalar) -> Self {
+        Self(secret * G)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.compress().to_bytes().to_vec()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        decode_point(bytes).map(Self)
+    }
+}
+
+/// Commitment `R` to the nonce of a signing session.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NonceCommitment(pub RistrettoPoint);
+
+impl NonceCommitment {
+    pub fn from_nonce(nonce: &Scalar) -> Self {
+        Self(nonce * G)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.compress().to_bytes().to_vec()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        decode_point(bytes).map(Self)
+    }
+}
+
+/// Blinded challenge `c` sent by requester.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlindChallenge(pub Scalar);
+
+impl BlindChallenge {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.as_bytes().to_vec()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        decode_scalar(bytes).map(Self)
+    }
+}
+
+/// Response `s` of signer to blinded challenge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlindResponse(pub Scalar);
+
+impl BlindResponse {
+    /// Answers blinded challenge with secret key and nonce of the session.
+    pub fn sign(secret: &Scalar, nonce: &Scalar, challenge: &BlindChallenge) -> Self {
+        Self(nonce + challenge.0 * secret)
+    }
+
+    /// Checks response against public data of the signing session.
+    pub fn verify(
+        &self,
+        key: &VerificationKey,
+        commitment: &NonceCommitment,
+        challenge: &BlindChallenge,
+    ) -> bool {
+        self.0 * G == commitment.0 + challenge.0 * key.0
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.as_bytes().to_vec()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        decode_scalar(bytes).map(Self)
+    }
+}
+
+/// Unblinded signature `(R', s')`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlindSignature {
+    pub commitment: RistrettoPoint,
+    pub response: Scalar,
+}
+
+impl BlindSignature {
+    pub fn verify(&self, key: &VerificationKey, message: &[u8]) -> bool {
+        self.response * G == self.commitment + challenge(key, &self.commitment, message) * key.0
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(64);
+        bytes.extend_from_slice(self.commitment.compress().as_bytes());
+        bytes.extend_from_slice(self.response.as_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let chunks = split_chunks(bytes, 2)?;
+        Some(Self {
+            commitment: decode_point(chunks[0])?,
+            response: decode_scalar(chunks[1])?,
+        })
+    }
+}
+
+/// Secret blinding state kept by requester between challenge and response.
+#[derive(Clone, Debug)]
+pub struct Blinding {
+    alpha: Scalar,
+    commitment: RistrettoPoint,
+}
+
+impl Blinding {
+    /// Blinds nonce commitment of signer for `message`.
+    ///
+    /// Returns the state needed to unblind response and the challenge for signer.
+    pub fn new<R: RngCore + CryptoRng>(
+        key: &VerificationKey,
+        commitment: &NonceCommitment,
+        message: &[u8],
+        rng: &mut R,
+    ) -> (Self, BlindChallenge) {
+        let alpha = Scalar::random(rng);
+        let beta = Scalar::random(rng);
+        let blinded = commitment.0 + alpha * G + beta * key.0;
+        let challenge = challenge(key, &blinded, message) + beta;
+        (
+            Self {
+                alpha,
+                commitment: blinded,
+            },
+            BlindChallenge(challenge),
+        )
+    }
+
+    pub fn unblind(&self, response: &BlindResponse) -> BlindSignature {
+        BlindSignature {
+            commitment: self.commitment,
+            response: response.0 + self.alpha,
+        }
+    }
+}
+
+fn challenge(key: &VerificationKey, commitment: &RistrettoPoint, message: &[u8]) -> Scalar {
+    let mut transcript = Transcript::new(b"crypto-election/blind-schnorr");
+    transcript
+        .append_point(&key.0)
+        .append_point(commitment)
+        .append_bytes(message);
+    transcript.challenge()
+}
@@ -0,0 +1,262 @@
+//! Distributed key generation and threshold decryption.
+//!
+//! Joint-Feldman protocol: every dealer shares a random secret with a polynomial of
+//! degree `threshold - 1`, publishes commitments to its coefficients and sends every
+//! trustee its share encrypted with Diffie-Hellman key of their transport keys. Shares
+//! are checked against the commitments, so an invalid share is proven by revealing the
+//! Diffie-Hellman key. Joint key is the sum of the constant terms of qualified dealers,
+//! secret share of a trustee is the sum of shares dealt to it, and any `threshold`
+//! trustees decrypt together.
+
+use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar, traits::Identity};
+use rand::{CryptoRng, RngCore};
+
+use super::{
+    decode_point, decode_scalar, elgamal::EncryptionKey, proofs::DleqProof, Transcript, G,
+};
+
+/// Dealt secret: commitments to polynomial coefficients and encrypted shares.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dealing {
+    /// Commitments to coefficients, the first one is the key share of the dealer.
+    pub commitments: Vec<RistrettoPoint>,
+    /// Proof of knowledge of the constant term.
+    pub proof: DleqProof,
+    /// Shares encrypted to every trustee, in the order of trustees. Trustees without
+    /// transport key get no share.
+    pub shares: Vec<Option<Scalar>>,
+}
+
+impl Dealing {
+    /// Deals random secret to trustees with given transport keys.
+    pub fn deal<R: RngCore + CryptoRng>(
+        context: &[u8],
+        threshold: usize,
+        transport_secret: &Scalar,
+        transport_keys: &[Option<EncryptionKey>],
+        rng: &mut R,
+    ) -> Self {
+        assert!(threshold > 0, "Threshold must be positive");
+
+        let coefficients: Vec<Scalar> = (0..threshold).map(|_| Scalar::random(rng)).collect();
+        let commitments = coefficients.iter().map(|a| a * G).collect();
+        let proof = DleqProof::prove_key(context, &coefficients[0], rng);
+        let shares = transport_keys
+            .iter()
+            .enumerate()
+            .map(|(position, key)| {
+                key.map(|key| {
+                    let shared_key = transport_secret * key.0;
+                    evaluate(&coefficients, position) + share_mask(context, position, &shared_key)
+                })
+            })
+            .collect();
+
+        Self {
+            commitments,
+            proof,
+            shares,
+        }
+    }
+
+    /// Checks dimensions of dealing and the proof of knowledge of the dealt secret.
+    pub fn verify(&self, context: &[u8], threshold: usize, trustees_count: usize) -> bool {
+        self.commitments.len() == threshold
+            && self.shares.len() == trustees_count
+            && self
+                .proof
+                .verify_key(context, &EncryptionKey(self.commitments[0]))
+    }
+
+    pub fn key_share(&self) -> EncryptionKey {
+        EncryptionKey(self.commitments[0])
+    }
+
+    /// Public key of the share of trustee at `position`.
+    pub fn share_key(&self, position: usize) -> RistrettoPoint {
+        let x = share_index(position);
+        self.commitments
+            .iter()
+            .rev()
+            .fold(RistrettoPoint::identity(), |sum, commitment| {
+                sum * x + commitment
+            })
+    }
+
+    /// Decrypts share of trustee at `position` with Diffie-Hellman key of transport keys
+    /// of the trustee and the dealer. Returns `None` if there is no valid share.
+    pub fn open_share(
+        &self,
+        context: &[u8],
+        position: usize,
+        shared_key: &RistrettoPoint,
+    ) -> Option<Scalar> {
+        let encrypted = (*self.shares.get(position)?)?;
+        let share = encrypted - share_mask(context, position, shared_key);
+        if share * G == self.share_key(position) {
+            Some(share)
+        } else {
+            None
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + self.commitments.len() * 32 + 64);
+        bytes.extend_from_slice(&(self.commitments.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.shares.len() as u32).to_le_bytes());
+        for commitment in &self.commitments {
+            bytes.extend_from_slice(commitment.compress().as_bytes());
+        }
+        bytes.extend_from_slice(&self.proof.to_bytes());
+        for share in &self.shares {
+            match share {
+                Some(share) => {
+                    bytes.push(1);
+                    bytes.extend_from_slice(share.as_bytes());
+                }
+                None => bytes.push(0),
+            }
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let number = |bytes: &[u8]| {
+            let mut buffer = [0_u8; 4];
+            buffer.copy_from_slice(bytes);
+            u32::from_le_bytes(buffer) as usize
+        };
+        if bytes.len() < 8 {
+            return None;
+        }
+        let (threshold, count) = (number(&bytes[..4]), number(&bytes[4..8]));
+        if threshold == 0 {
+            return None;
+        }
+        let mut rest = &bytes[8..];
+        if rest.len() < threshold.checked_mul(32)? + 64 {
+            return None;
+        }
+        let (commitments, tail) = rest.split_at(threshold * 32);
+        let (proof, tail) = tail.split_at(64);
+        rest = tail;
+
+        let mut shares = Vec::with_capacity(count.min(rest.len()));
+        for _ in 0..count {
+            let (&flag, tail) = rest.split_first()?;
+            rest = tail;
+            shares.push(match flag {
+                0 => None,
+                1 if rest.len() >= 32 => {
+                    let (share, tail) = rest.split_at(32);
+                    rest = tail;
+                    Some(decode_scalar(share)?)
+                }
+                _ => return None,
+            });
+        }
+        if !rest.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            commitments: commitments
+                .chunks(32)
+                .map(decode_point)
+                .collect::<Option<_>>()?,
+            proof: DleqProof::from_bytes(proof)?,
+            shares,
+        })
+    }
+}
+
+/// Diffie-Hellman key of the trustee and the dealer with proof of its correctness, which
+/// reveals share dealt to the trustee.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SharedKey {
+    pub key: RistrettoPoint,
+    pub proof: DleqProof,
+}
+
+impl SharedKey {
+    /// Computes key of trustee with `transport_secret` and dealer with `dealer_key`.
+    pub fn reveal<R: RngCore + CryptoRng>(
+        context: &[u8],
+        transport_secret: &Scalar,
+        dealer_key: &EncryptionKey,
+        rng: &mut R,
+    ) -> Self {
+        Self {
+            key: transport_secret * dealer_key.0,
+            proof: DleqProof::prove(context, (&G, &dealer_key.0), transport_secret, rng),
+        }
+    }
+
+    pub fn verify(
+        &self,
+        context: &[u8],
+        trustee_key: &EncryptionKey,
+        dealer_key: &EncryptionKey,
+    ) -> bool {
+        self.proof
+            .verify(context, (&G, &dealer_key.0), (&trustee_key.0, &self.key))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(96);
+        bytes.extend_from_slice(self.key.compress().as_bytes());
+        bytes.extend_from_slice(&self.proof.to_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != 96 {
+            return None;
+        }
+        Some(Self {
+            key: decode_point(&bytes[..32])?,
+            proof: DleqProof::from_bytes(&bytes[32..])?,
+        })
+    }
+}
+
+/// Point at which share of trustee at `position` is evaluated.
+fn share_index(position: usize) -> Scalar {
+    Scalar::from(position as u64 + 1)
+}
+
+fn evaluate(coefficients: &[Scalar], position: usize) -> Scalar {
+    let x = share_index(position);
+    coefficients
+        .iter()
+        .rev()
+        .fold(Scalar::zero(), |sum, coefficient| sum * x + coefficient)
+}
+
+fn share_mask(context: &[u8], position: usize, shared_key: &RistrettoPoint) -> Scalar {
+    let mut transcript = Transcript::new(b"crypto-election/dealt-share");
+    transcript
+        .append_bytes(context)
+        .append_bytes(&(position as u64).to_le_bytes())
+        .append_point(shared_key);
+    transcript.challenge()
+}
+
+/// Lagrange coefficients for interpolation at zero over shares of trustees at `positions`.
+///
+/// Positions must be distinct.
+pub fn lagrange_coefficients(positions: &[usize]) -> Vec<Scalar> {
+    positions
+        .iter()
+        .map(|&position| {
+            let x = share_index(position);
+            positions.iter().filter(|&&other| other != position).fold(
+                Scalar::one(),
+                |product, &other| {
+                    let other = share_index(other);
+                    product * other * (other - x).invert()
+                },
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,134 @@
+//! Exponential ElGamal encryption.
+//!
+//! Message `m` is encrypted with key `Y` as `(r * G, m * G + r * Y)`, so ciphertexts
+//! can be summed component-wise and the sum decrypts to the sum of messages.
+
+use std::{collections::HashMap, ops::Add};
+
+use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar, traits::Identity};
+
+use super::{decode_point, split_chunks, G};
+
+/// Public encryption key (or a trustee share of it).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncryptionKey(pub RistrettoPoint);
+
+impl EncryptionKey {
+    pub fn from_secret(secret: &Scalar) -> Self {
+        Self(secret * G)
+    }
+
+    /// Joins key shares of trustees into the key of the whole trustee group.
+    pub fn combine<'a>(shares: impl IntoIterator<Item = &'a EncryptionKey>) -> Self {
+        Self(shares.into_iter().map(|share| share.0).sum())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.compress().to_bytes().to_vec()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        decode_point(bytes).map(Self)
+    }
+}
+
+/// ElGamal ciphertext.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ciphertext {
+    pub a: RistrettoPoint,
+    pub b: RistrettoPoint,
+}
+
+impl Ciphertext {
+    /// Ciphertext of zero with zero randomness, neutral element of addition.
+    pub fn zero() -> Self {
+        Self {
+            a: RistrettoPoint::identity(),
+            b: RistrettoPoint::identity(),
+        }
+    }
+
+    pub fn encrypt(key: &EncryptionKey, message: u64, randomness: &Scalar) -> Self {
+        Self {
+            a: randomness * G,
+            b: Scalar::from(message) * G + randomness * key.0,
+        }
+    }
+
+    /// Re-randomizes ciphertext without changing the encrypted message.
+    pub fn rerandomize(&self, key: &EncryptionKey, randomness: &Scalar) -> Self {
+        Self {
+            a: self.a + randomness * G,
+            b: self.b + randomness * key.0,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(64);
+        bytes.extend_from_slice(self.a.compress().as_bytes());
+        bytes.extend_from_slice(self.b.compress().as_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let chunks = split_chunks(bytes, 2)?;
+        Some(Self {
+            a: decode_point(chunks[0])?,
+            b: decode_point(chunks[1])?,
+        })
+    }
+}
+
+impl Add for Ciphertext {
+    type Output = Ciphertext;
+
+    fn add(self, other: Ciphertext) -> Ciphertext {
+        Ciphertext {
+            a: self.a + other.a,
+            b: self.b + other.b,
+        }
+    }
+}
+
+/// Decryption share of a trustee: `x_i * A`.
+pub fn decryption_share(secret: &Scalar, ciphertext: &Ciphertext) -> RistrettoPoint {
+    secret * ciphertext.a
+}
+
+/// Removes trustee decryption shares weighted with their Lagrange coefficients, leaving
+/// `m * G`.
+pub fn combine_decryption(
+    ciphertext: &Ciphertext,
+    shares: impl IntoIterator<Item = (Scalar, RistrettoPoint)>,
+) -> RistrettoPoint {
+    ciphertext.b
+        - shares
+            .into_iter()
+            .map(|(coefficient, share)| coefficient * share)
+            .sum::<RistrettoPoint>()
+}
+
+/// Finds `m` in `[0, max]` such that `m * G == point` using baby-step giant-step search.
+pub fn discrete_log(point: &RistrettoPoint, max: u64) -> Option<u64> {
+    let step = (max as f64).sqrt() as u64 + 1;
+
+    let mut baby_steps = HashMap::with_capacity(step as usize);
+    let mut current = RistrettoPoint::identity();
+    for j in 0..step {
+        baby_steps.insert(current.compress().to_bytes(), j);
+        current += G;
+    }
+
+    let giant_step = Scalar::from(step) * G;
+    let mut current = *point;
+    let mut i = 0;
+    while i * step <= max {
+        if let Some(j) = baby_steps.get(current.compress().as_bytes()) {
+            let message = i * step + j;
+            return if message <= max { Some(message) } else { None };
+        }
+        current -= giant_step;
+        i += 1;
+    }
+    None
+}
@@ -0,0 +1,658 @@
+//! Verifiable re-encryption mix-net.
+//!
+//! Mixer permutes list of ballots and re-randomizes every ciphertext. Correctness of the
+//! shuffle is proven with the Terelius-Wikström argument, which size is linear in the
+//! number of ballots.
+
+use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
+use rand::{seq::SliceRandom, CryptoRng, RngCore};
+use sha2::Sha512;
+
+use super::{
+    ballot::PartialDecryption,
+    decode_point, decode_scalar, dkg,
+    elgamal::{self, Ciphertext, EncryptionKey},
+    proofs::DleqProof,
+    Transcript, G,
+};
+
+/// Ballot of free-form values, each encrypted separately.
+///
+/// Every ciphertext is accompanied with proof of knowledge of its randomness, so ballot
+/// can not be copied by another voter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MixnetBallot {
+    pub ciphertexts: Vec<Ciphertext>,
+    pub proofs: Vec<DleqProof>,
+}
+
+impl MixnetBallot {
+    /// Encrypts `values`. `context` must be unique for the pair of election and voter.
+    pub fn encrypt<R: RngCore + CryptoRng>(
+        context: &[u8],
+        key: &EncryptionKey,
+        values: &[u64],
+        rng: &mut R,
+    ) -> Self {
+        let mut ciphertexts = Vec::with_capacity(values.len());
+        let mut proofs = Vec::with_capacity(values.len());
+        for value in values {
+            let randomness = Scalar::random(rng);
+            ciphertexts.push(Ciphertext::encrypt(key, *value, &randomness));
+            proofs.push(DleqProof::prove(context, (&G, &G), &randomness, rng));
+        }
+        Self {
+            ciphertexts,
+            proofs,
+        }
+    }
+
+    pub fn verify(&self, context: &[u8], width: usize) -> bool {
+        self.ciphertexts.len() == width
+            && self.proofs.len() == width
+            && self
+                .ciphertexts
+                .iter()
+                .zip(&self.proofs)
+                .all(|(ciphertext, proof)| {
+                    proof.verify(context, (&G, &G), (&ciphertext.a, &ciphertext.a))
+                })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.ciphertexts.len() * 128);
+        for ciphertext in &self.ciphertexts {
+            bytes.extend_from_slice(&ciphertext.to_bytes());
+        }
+        for proof in &self.proofs {
+            bytes.extend_from_slice(&proof.to_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() % 128 != 0 {
+            return None;
+        }
+        let (ciphertexts, proofs) = bytes.split_at(bytes.len() / 2);
+        Some(Self {
+            ciphertexts: ciphertexts
+                .chunks(64)
+                .map(Ciphertext::from_bytes)
+                .collect::<Option<_>>()?,
+            proofs: proofs
+                .chunks(64)
+                .map(DleqProof::from_bytes)
+                .collect::<Option<_>>()?,
+        })
+    }
+}
+
+/// List of ballots, each made of `width` ciphertexts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BallotList {
+    pub width: usize,
+    pub ballots: Vec<Vec<Ciphertext>>,
+}
+
+impl BallotList {
+    pub fn len(&self) -> usize {
+        self.ballots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ballots.is_empty()
+    }
+
+    /// All ciphertexts of the list, ballot by ballot.
+    pub fn ciphertexts(&self) -> Vec<Ciphertext> {
+        self.ballots.iter().flatten().copied().collect()
+    }
+
+    /// Components of ciphertexts in the column of the list.
+    fn column(&self, column: usize) -> (Vec<RistrettoPoint>, Vec<RistrettoPoint>) {
+        self.ballots
+            .iter()
+            .map(|ballot| (ballot[column].a, ballot[column].b))
+            .unzip()
+    }
+
+    /// Makes list with `permutation[i]`-th ballot re-randomized with `randomness[i]` at
+    /// position `i`.
+    pub fn shuffle(
+        &self,
+        key: &EncryptionKey,
+        permutation: &[usize],
+        randomness: &[Vec<Scalar>],
+    ) -> Self {
+        Self {
+            width: self.width,
+            ballots: permutation
+                .iter()
+                .zip(randomness)
+                .map(|(&source, randomness)| {
+                    self.ballots[source]
+                        .iter()
+                        .zip(randomness)
+                        .map(|(ciphertext, randomness)| ciphertext.rerandomize(key, randomness))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /// Shuffles list with random permutation and randomness, which are returned as well.
+    pub fn random_shuffle<R: RngCore + CryptoRng>(
+        &self,
+        key: &EncryptionKey,
+        rng: &mut R,
+    ) -> (Self, ShuffleOpening) {
+        let mut permutation: Vec<usize> = (0..self.len()).collect();
+        permutation.shuffle(rng);
+
+        let mut randomness = Vec::with_capacity(self.len());
+        for _ in 0..self.len() {
+            let mut ballot_randomness = Vec::with_capacity(self.width);
+            for _ in 0..self.width {
+                ballot_randomness.push(Scalar::random(rng));
+            }
+            randomness.push(ballot_randomness);
+        }
+
+        let shuffled = self.shuffle(key, &permutation, &randomness);
+        (
+            shuffled,
+            ShuffleOpening {
+                permutation,
+                randomness,
+            },
+        )
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + self.len() * self.width * 64);
+        bytes.extend_from_slice(&(self.width as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.len() as u32).to_le_bytes());
+        for ciphertext in self.ballots.iter().flatten() {
+            bytes.extend_from_slice(&ciphertext.to_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader(bytes);
+        let list = reader.ballot_list()?;
+        if reader.0.is_empty() {
+            Some(list)
+        } else {
+            None
+        }
+    }
+}
+
+/// Permutation and randomness of a shuffle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShuffleOpening {
+    pub permutation: Vec<usize>,
+    pub randomness: Vec<Vec<Scalar>>,
+}
+
+/// Terelius-Wikström proof that output list is a shuffle of input list.
+///
+/// Mixer commits to the permutation matrix and proves that the committed matrix is a
+/// permutation one and that the output, weighted with random challenges, is the
+/// re-encryption of the input weighted with the permuted challenges. Proof holds a
+/// constant number of elements per ballot and one response per ballot column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShuffleProof {
+    /// Commitments to columns of permutation matrix.
+    pub commitments: Vec<RistrettoPoint>,
+    /// Chain of commitments to products of permuted challenges.
+    pub chain: Vec<RistrettoPoint>,
+    pub challenge: Scalar,
+    /// Responses for the sums of commitment randomness.
+    pub responses: [Scalar; 3],
+    /// Responses for the re-encryption randomness of every column.
+    pub column_responses: Vec<Scalar>,
+    /// Responses for the randomness of chained commitments.
+    pub chain_responses: Vec<Scalar>,
+    /// Responses for the permuted challenges.
+    pub permuted_responses: Vec<Scalar>,
+}
+
+/// Commitments of the shuffle proof, made by prover and restored by verifier.
+struct ShuffleCommitments {
+    permutation: RistrettoPoint,
+    chain: RistrettoPoint,
+    weights: RistrettoPoint,
+    columns: Vec<(RistrettoPoint, RistrettoPoint)>,
+    links: Vec<RistrettoPoint>,
+}
+
+impl ShuffleProof {
+    /// Proves that `output` was made from `input` with `opening`.
+    pub fn prove<R: RngCore + CryptoRng>(
+        context: &[u8],
+        key: &EncryptionKey,
+        input: &BallotList,
+        output: &BallotList,
+        opening: &ShuffleOpening,
+        rng: &mut R,
+    ) -> Self {
+        let len = input.len();
+        let bases = commitment_bases(len);
+        let chain_base = chain_base();
+
+        // Column `j` of permutation matrix has one at row `i` where `permutation[i] == j`.
+        let commitment_randomness: Vec<Scalar> = (0..len).map(|_| Scalar::random(rng)).collect();
+        let mut commitments = vec![RistrettoPoint::default(); len];
+        for (row, &column) in opening.permutation.iter().enumerate() {
+            commitments[column] = commitment_randomness[column] * G + bases[row];
+        }
+
+        let weights = Self::weights(context, key, input, output, &commitments);
+        let permuted_weights: Vec<Scalar> = opening
+            .permutation
+            .iter()
+            .map(|&source| weights[source])
+            .collect();
+
+        let chain_randomness: Vec<Scalar> = (0..len).map(|_| Scalar::random(rng)).collect();
+        let mut chain = Vec::with_capacity(len);
+        let mut previous = chain_base;
+        for (weight, randomness) in permuted_weights.iter().zip(&chain_randomness) {
+            previous = randomness * G + weight * previous;
+            chain.push(previous);
+        }
+
+        let nonces: [Scalar; 3] = [
+            Scalar::random(rng),
+            Scalar::random(rng),
+            Scalar::random(rng),
+        ];
+        let column_nonces: Vec<Scalar> = (0..input.width).map(|_| Scalar::random(rng)).collect();
+        let chain_nonces: Vec<Scalar> = (0..len).map(|_| Scalar::random(rng)).collect();
+        let permuted_nonces: Vec<Scalar> = (0..len).map(|_| Scalar::random(rng)).collect();
+
+        let previous_links = std::iter::once(&chain_base).chain(&chain);
+        let proof_commitments = ShuffleCommitments {
+            permutation: nonces[0] * G,
+            chain: nonces[1] * G,
+            weights: nonces[2] * G + weighted_sum(&permuted_nonces, &bases),
+            columns: column_nonces
+                .iter()
+                .enumerate()
+                .map(|(column, nonce)| {
+                    let (a, b) = output.column(column);
+                    (
+                        weighted_sum(&permuted_nonces, &a) - nonce * G,
+                        weighted_sum(&permuted_nonces, &b) - nonce * key.0,
+                    )
+                })
+                .collect(),
+            links: chain_nonces
+                .iter()
+                .zip(&permuted_nonces)
+                .zip(previous_links)
+                .map(|((chain_nonce, nonce), previous)| chain_nonce * G + nonce * previous)
+                .collect(),
+        };
+        let challenge = Self::challenge(
+            context,
+            key,
+            input,
+            output,
+            &commitments,
+            &chain,
+            &proof_commitments,
+        );
+
+        // Chain randomness is accumulated with products of the later permuted weights.
+        let mut chain_sum = Scalar::zero();
+        let mut later_product = Scalar::one();
+        for (randomness, weight) in chain_randomness.iter().zip(&permuted_weights).rev() {
+            chain_sum += randomness * later_product;
+            later_product *= weight;
+        }
+        let sums = [
+            commitment_randomness.iter().sum::<Scalar>(),
+            chain_sum,
+            weighted_sum_scalars(&weights, &commitment_randomness),
+        ];
+
+        Self {
+            commitments,
+            chain,
+            challenge,
+            responses: [
+                nonces[0] + challenge * sums[0],
+                nonces[1] + challenge * sums[1],
+                nonces[2] + challenge * sums[2],
+            ],
+            column_responses: column_nonces
+                .iter()
+                .enumerate()
+                .map(|(column, nonce)| {
+                    let randomness: Vec<Scalar> = opening
+                        .randomness
+                        .iter()
+                        .map(|ballot| ballot[column])
+                        .collect();
+                    nonce + challenge * weighted_sum_scalars(&permuted_weights, &randomness)
+                })
+                .collect(),
+            chain_responses: chain_nonces
+                .iter()
+                .zip(&chain_randomness)
+                .map(|(nonce, randomness)| nonce + challenge * randomness)
+                .collect(),
+            permuted_responses: permuted_nonces
+                .iter()
+                .zip(&permuted_weights)
+                .map(|(nonce, weight)| nonce + challenge * weight)
+                .collect(),
+        }
+    }
+
+    pub fn verify(
+        &self,
+        context: &[u8],
+        key: &EncryptionKey,
+        input: &BallotList,
+        output: &BallotList,
+    ) -> bool {
+        let len = input.len();
+        if output.width != input.width
+            || output.len() != len
+            || self.commitments.len() != len
+            || self.chain.len() != len
+            || self.column_responses.len() != input.width
+            || self.chain_responses.len() != len
+            || self.permuted_responses.len() != len
+        {
+            return false;
+        }
+
+        let bases = commitment_bases(len);
+        let chain_base = chain_base();
+        let weights = Self::weights(context, key, input, output, &self.commitments);
+        let challenge = self.challenge;
+
+        let permutation_sum =
+            self.commitments.iter().sum::<RistrettoPoint>() - bases.iter().sum::<RistrettoPoint>();
+        let weights_product = weights.iter().product::<Scalar>();
+        let chain_end = self.chain.last().unwrap_or(&chain_base) - weights_product * chain_base;
+        let weighted_commitments = weighted_sum(&weights, &self.commitments);
+
+        let previous_links = std::iter::once(&chain_base).chain(&self.chain);
+        let restored = ShuffleCommitments {
+            permutation: self.responses[0] * G - challenge * permutation_sum,
+            chain: self.responses[1] * G - challenge * chain_end,
+            weights: self.responses[2] * G + weighted_sum(&self.permuted_responses, &bases)
+                - challenge * weighted_commitments,
+            columns: self
+                .column_responses
+                .iter()
+                .enumerate()
+                .map(|(column, response)| {
+                    let (input_a, input_b) = input.column(column);
+                    let (output_a, output_b) = output.column(column);
+                    (
+                        weighted_sum(&self.permuted_responses, &output_a)
+                            - response * G
+                            - challenge * weighted_sum(&weights, &input_a),
+                        weighted_sum(&self.permuted_responses, &output_b)
+                            - response * key.0
+                            - challenge * weighted_sum(&weights, &input_b),
+                    )
+                })
+                .collect(),
+            links: self
+                .chain_responses
+                .iter()
+                .zip(&self.permuted_responses)
+                .zip(previous_links.zip(&self.chain))
+                .map(|((chain_response, response), (previous, link))| {
+                    chain_response * G + response * previous - challenge * link
+                })
+                .collect(),
+        };
+
+        Self::challenge(
+            context,
+            key,
+            input,
+            output,
+            &self.commitments,
+            &self.chain,
+            &restored,
+        ) == challenge
+    }
+
+    /// Derives weights of input ballots, which bind the proof to the committed permutation.
+    fn weights(
+        context: &[u8],
+        key: &EncryptionKey,
+        input: &BallotList,
+        output: &BallotList,
+        commitments: &[RistrettoPoint],
+    ) -> Vec<Scalar> {
+        let mut transcript = Transcript::new(b"crypto-election/shuffle-weights");
+        transcript
+            .append_bytes(context)
+            .append_point(&key.0)
+            .append_bytes(&input.to_bytes())
+            .append_bytes(&output.to_bytes());
+        for commitment in commitments {
+            transcript.append_point(commitment);
+        }
+        (0..commitments.len())
+            .map(|index| {
+                let mut transcript = transcript.clone();
+                transcript.append_bytes(&(index as u64).to_le_bytes());
+                transcript.challenge()
+            })
+            .collect()
+    }
+
+    fn challenge(
+        context: &[u8],
+        key: &EncryptionKey,
+        input: &BallotList,
+        output: &BallotList,
+        commitments: &[RistrettoPoint],
+        chain: &[RistrettoPoint],
+        proof_commitments: &ShuffleCommitments,
+    ) -> Scalar {
+        let mut transcript = Transcript::new(b"crypto-election/shuffle");
+        transcript
+            .append_bytes(context)
+            .append_point(&key.0)
+            .append_bytes(&input.to_bytes())
+            .append_bytes(&output.to_bytes());
+        for point in commitments.iter().chain(chain) {
+            transcript.append_point(point);
+        }
+        transcript
+            .append_point(&proof_commitments.permutation)
+            .append_point(&proof_commitments.chain)
+            .append_point(&proof_commitments.weights);
+        for (a, b) in &proof_commitments.columns {
+            transcript.append_point(a).append_point(b);
+        }
+        for link in &proof_commitments.links {
+            transcript.append_point(link);
+        }
+        transcript.challenge()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let len = self.commitments.len();
+        let width = self.column_responses.len();
+        let mut bytes = Vec::with_capacity(8 + (len * 4 + width + 4) * 32);
+        bytes.extend_from_slice(&(width as u32).to_le_bytes());
+        bytes.extend_from_slice(&(len as u32).to_le_bytes());
+        for point in self.commitments.iter().chain(&self.chain) {
+            bytes.extend_from_slice(point.compress().as_bytes());
+        }
+        let scalars = std::iter::once(&self.challenge)
+            .chain(&self.responses)
+            .chain(&self.column_responses)
+            .chain(&self.chain_responses)
+            .chain(&self.permuted_responses);
+        for scalar in scalars {
+            bytes.extend_from_slice(scalar.as_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader(bytes);
+        let width = reader.number()?;
+        let len = reader.number()?;
+        let points = reader
+            .take(len.checked_mul(64)?)?
+            .chunks(32)
+            .map(decode_point)
+            .collect::<Option<Vec<_>>>()?;
+        let scalars = reader
+            .take(
+                len.checked_mul(2)?
+                    .checked_add(width)?
+                    .checked_add(4)?
+                    .checked_mul(32)?,
+            )?
+            .chunks(32)
+            .map(decode_scalar)
+            .collect::<Option<Vec<_>>>()?;
+        if !reader.0.is_empty() {
+            return None;
+        }
+
+        let (commitments, chain) = points.split_at(len);
+        let (head, tail) = scalars.split_at(4);
+        let (column_responses, tail) = tail.split_at(width);
+        let (chain_responses, permuted_responses) = tail.split_at(len);
+        Some(Self {
+            commitments: commitments.to_vec(),
+            chain: chain.to_vec(),
+            challenge: head[0],
+            responses: [head[1], head[2], head[3]],
+            column_responses: column_responses.to_vec(),
+            chain_responses: chain_responses.to_vec(),
+            permuted_responses: permuted_responses.to_vec(),
+        })
+    }
+}
+
+/// Independent generators for commitments to permutation matrix rows.
+fn commitment_bases(len: usize) -> Vec<RistrettoPoint> {
+    (0..len)
+        .map(|index| {
+            let label = [
+                b"crypto-election/shuffle-base".as_ref(),
+                &(index as u64).to_le_bytes(),
+            ]
+            .concat();
+            RistrettoPoint::hash_from_bytes::<Sha512>(&label)
+        })
+        .collect()
+}
+
+fn chain_base() -> RistrettoPoint {
+    RistrettoPoint::hash_from_bytes::<Sha512>(b"crypto-election/shuffle-chain")
+}
+
+fn weighted_sum(weights: &[Scalar], points: &[RistrettoPoint]) -> RistrettoPoint {
+    weights
+        .iter()
+        .zip(points)
+        .map(|(weight, point)| weight * point)
+        .sum()
+}
+
+fn weighted_sum_scalars(weights: &[Scalar], scalars: &[Scalar]) -> Scalar {
+    weights
+        .iter()
+        .zip(scalars)
+        .map(|(weight, scalar)| weight * scalar)
+        .sum()
+}
+
+/// Decrypts every ciphertext with decryption shares of at least `threshold` trustees,
+/// given with positions of their key shares.
+///
+/// Values are searched in `[0, max_value]`, values out of range are decrypted to `None`.
+pub fn decrypt_ciphertexts(
+    ciphertexts: &[Ciphertext],
+    decryptions: &[(usize, PartialDecryption)],
+    max_value: u64,
+) -> Vec<Option<u64>> {
+    let positions: Vec<usize> = decryptions.iter().map(|(position, _)| *position).collect();
+    let coefficients = dkg::lagrange_coefficients(&positions);
+    ciphertexts
+        .iter()
+        .enumerate()
+        .map(|(index, ciphertext)| {
+            let plain = elgamal::combine_decryption(
+                ciphertext,
+                coefficients
+                    .iter()
+                    .zip(decryptions)
+                    .map(|(coefficient, (_, decryption))| (*coefficient, decryption.shares[index])),
+            );
+            elgamal::discrete_log(&plain, max_value)
+        })
+        .collect()
+}
+
+/// Sequential reader of length-prefixed structures.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Some(head)
+    }
+
+    fn number(&mut self) -> Option<usize> {
+        let mut buffer = [0_u8; 4];
+        buffer.copy_from_slice(self.take(4)?);
+        Some(u32::from_le_bytes(buffer) as usize)
+    }
+
+    /// Reads numbers of columns and rows. Rows can not be empty.
+    fn dimensions(&mut self) -> Option<(usize, usize)> {
+        let width = self.number()?;
+        let count = self.number()?;
+        if width == 0 && count != 0 {
+            return None;
+        }
+        Some((width, count))
+    }
+
+    fn ballot_list(&mut self) -> Option<BallotList> {
+        let (width, count) = self.dimensions()?;
+        let bytes = self.take(count.checked_mul(width)?.checked_mul(64)?)?;
+        let ciphertexts = bytes
+            .chunks(64)
+            .map(Ciphertext::from_bytes)
+            .collect::<Option<Vec<_>>>()?;
+        Some(BallotList {
+            width,
+            ballots: rows(&ciphertexts, width),
+        })
+    }
+}
+
+fn rows<T: Clone>(items: &[T], width: usize) -> Vec<Vec<T>> {
+    if items.is_empty() {
+        Vec::new()
+    } else {
+        items.chunks(width).map(<[_]>::to_vec).collect()
+    }
+}
@@ -0,0 +1,100 @@
+//! Cryptographic primitives used by secret ballot modes.
+//!
+//! All primitives work in the Ristretto prime-order group. Non-interactive proofs use
+//! Fiat-Shamir transform over SHA-512 with explicit domain separation, so proofs made for
+//! one election (or one voter) can not be replayed in another context.
+
+use curve25519_dalek::{
+    constants::RISTRETTO_BASEPOINT_POINT,
+    ristretto::{CompressedRistretto, RistrettoPoint},
+};
+use sha2::{Digest, Sha512};
+
+pub use curve25519_dalek::scalar::Scalar;
+
+pub mod ballot;
+
+pub mod blind;
+
+pub mod dkg;
+
+pub mod elgamal;
+
+pub mod mixnet;
+
+pub mod proofs;
+
+pub mod ring;
+
+/// Group generator.
+pub const G: RistrettoPoint = RISTRETTO_BASEPOINT_POINT;
+
+/// Fiat-Shamir transcript.
+#[derive(Clone, Debug)]
+pub struct Transcript {
+    hasher: Sha512,
+}
+
+impl Transcript {
+    /// Creates a new transcript for given protocol domain.
+    pub fn new(domain: &[u8]) -> Self {
+        let mut transcript = Self {
+            hasher: Sha512::new(),
+        };
+        transcript.append_bytes(domain);
+        transcript
+    }
+
+    /// Appends length-prefixed bytes.
+    pub fn append_bytes(&mut self, bytes: &[u8]) -> &mut Self {
+        self.hasher.input((bytes.len() as u64).to_le_bytes());
+        self.hasher.input(bytes);
+        self
+    }
+
+    pub fn append_point(&mut self, point: &RistrettoPoint) -> &mut Self {
+        self.hasher.input(point.compress().as_bytes());
+        self
+    }
+
+    pub fn append_scalar(&mut self, scalar: &Scalar) -> &mut Self {
+        self.hasher.input(scalar.as_bytes());
+        self
+    }
+
+    /// Produces a challenge scalar.
+    pub fn challenge(self) -> Scalar {
+        Scalar::from_hash(self.hasher)
+    }
+}
+
+/// Derives a secret scalar from node secret material and a context.
+pub fn derive_scalar(domain: &[u8], secret: &[u8], context: &[u8]) -> Scalar {
+    let mut transcript = Transcript::new(domain);
+    transcript.append_bytes(secret).append_bytes(context);
+    transcript.challenge()
+}
+
+pub(crate) fn decode_point(bytes: &[u8]) -> Option<RistrettoPoint> {
+    if bytes.len() != 32 {
+        return None;
+    }
+    CompressedRistretto::from_slice(bytes).decompress()
+}
+
+pub(crate) fn decode_scalar(bytes: &[u8]) -> Option<Scalar> {
+    if bytes.len() != 32 {
+        return None;
+    }
+    let mut buffer = [0_u8; 32];
+    buffer.copy_from_slice(bytes);
+    Scalar::from_canonical_bytes(buffer)
+}
+
+/// Splits bytes into `count` chunks of 32 bytes.
+pub(crate) fn split_chunks(bytes: &[u8], count: usize) -> Option<Vec<&[u8]>> {
+    if bytes.len() != count * 32 {
+        return None;
+    }
+    Some(bytes.chunks(32).collect())
+}
@@ -0,0 +1,194 @@
+//! Non-interactive zero-knowledge proofs.
+
+use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
+use rand::{CryptoRng, RngCore};
+
+use super::{
+    decode_scalar,
+    elgamal::{Ciphertext, EncryptionKey},
+    split_chunks, Transcript, G,
+};
+
+/// Chaum-Pedersen proof of discrete logarithm equality: `log_g1(h1) == log_g2(h2)`.
+///
+/// With `(g2, h2) == (g1, h1)` it is a plain Schnorr proof of knowledge of `log_g1(h1)`,
+/// as used by `prove_key`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DleqProof {
+    pub challenge: Scalar,
+    pub response: Scalar,
+}
+
+impl DleqProof {
+    fn transcript(
+        context: &[u8],
+        bases: (&RistrettoPoint, &RistrettoPoint),
+        images: (&RistrettoPoint, &RistrettoPoint),
+        commitments: (&RistrettoPoint, &RistrettoPoint),
+    ) -> Scalar {
+        let mut transcript = Transcript::new(b"crypto-election/dleq");
+        transcript
+            .append_bytes(context)
+            .append_point(bases.0)
+            .append_point(images.0)
+            .append_point(bases.1)
+            .append_point(images.1)
+            .append_point(commitments.0)
+            .append_point(commitments.1);
+        transcript.challenge()
+    }
+
+    pub fn prove<R: RngCore + CryptoRng>(
+        context: &[u8],
+        bases: (&RistrettoPoint, &RistrettoPoint),
+        secret: &Scalar,
+        rng: &mut R,
+    ) -> Self {
+        let images = (secret * bases.0, secret * bases.1);
+        let nonce = Scalar::random(rng);
+        let commitments = (nonce * bases.0, nonce * bases.1);
+        let challenge = Self::transcript(
+            context,
+            bases,
+            (&images.0, &images.1),
+            (&commitments.0, &commitments.1),
+        );
+        Self {
+            challenge,
+            response: nonce + challenge * secret,
+        }
+    }
+
+    pub fn verify(
+        &self,
+        context: &[u8],
+        bases: (&RistrettoPoint, &RistrettoPoint),
+        images: (&RistrettoPoint, &RistrettoPoint),
+    ) -> bool {
+        let commitments = (
+            self.response * bases.0 - self.challenge * images.0,
+            self.response * bases.1 - self.challenge * images.1,
+        );
+        Self::transcript(context, bases, images, (&commitments.0, &commitments.1)) == self.challenge
+    }
+
+    /// Proves knowledge of secret key for `EncryptionKey::from_secret(secret)`.
+    pub fn prove_key<R: RngCore + CryptoRng>(context: &[u8], secret: &Scalar, rng: &mut R) -> Self {
+        Self::prove(context, (&G, &G), secret, rng)
+    }
+
+    pub fn verify_key(&self, context: &[u8], key: &EncryptionKey) -> bool {
+        self.verify(context, (&G, &G), (&key.0, &key.0))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(64);
+        bytes.extend_from_slice(self.challenge.as_bytes());
+        bytes.extend_from_slice(self.response.as_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let chunks = split_chunks(bytes, 2)?;
+        Some(Self {
+            challenge: decode_scalar(chunks[0])?,
+            response: decode_scalar(chunks[1])?,
+        })
+    }
+}
+
+/// Disjunctive Chaum-Pedersen proof that ciphertext encrypts either 0 or 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChoiceProof {
+    pub challenges: [Scalar; 2],
+    pub responses: [Scalar; 2],
+}
+
+impl ChoiceProof {
+    fn transcript(
+        context: &[u8],
+        key: &EncryptionKey,
+        ciphertext: &Ciphertext,
+        commitments: &[(RistrettoPoint, RistrettoPoint); 2],
+    ) -> Scalar {
+        let mut transcript = Transcript::new(b"crypto-election/choice");
+        transcript
+            .append_bytes(context)
+            .append_point(&key.0)
+            .append_point(&ciphertext.a)
+            .append_point(&ciphertext.b);
+        for (first, second) in commitments {
+            transcript.append_point(first).append_point(second);
+        }
+        transcript.challenge()
+    }
+
+    /// `B - m * G` for each of possible messages.
+    fn shifted(ciphertext: &Ciphertext) -> [RistrettoPoint; 2] {
+        [ciphertext.b, ciphertext.b - G]
+    }
+
+    pub fn prove<R: RngCore + CryptoRng>(
+        context: &[u8],
+        key: &EncryptionKey,
+        ciphertext: &Ciphertext,
+        message: bool,
+        randomness: &Scalar,
+        rng: &mut R,
+    ) -> Self {
+        let real = message as usize;
+        let fake = 1 - real;
+        let shifted = Self::shifted(ciphertext);
+
+        let mut challenges = [Scalar::zero(); 2];
+        let mut responses = [Scalar::zero(); 2];
+        challenges[fake] = Scalar::random(rng);
+        responses[fake] = Scalar::random(rng);
+
+        let nonce = Scalar::random(rng);
+        let mut commitments = [(G, G); 2];
+        commitments[real] = (nonce * G, nonce * key.0);
+        commitments[fake] = (
+            responses[fake] * G - challenges[fake] * ciphertext.a,
+            responses[fake] * key.0 - challenges[fake] * shifted[fake],
+        );
+
+        let challenge = Self::transcript(context, key, ciphertext, &commitments);
+        challenges[real] = challenge - challenges[fake];
+        responses[real] = nonce + challenges[real] * randomness;
+
+        Self {
+            challenges,
+            responses,
+        }
+    }
+
+    pub fn verify(&self, context: &[u8], key: &EncryptionKey, ciphertext: &Ciphertext) -> bool {
+        let shifted = Self::shifted(ciphertext);
+        let mut commitments = [(G, G); 2];
+        for (i, commitment) in commitments.iter_mut().enumerate() {
+            *commitment = (
+                self.responses[i] * G - self.challenges[i] * ciphertext.a,
+                self.responses[i] * key.0 - self.challenges[i] * shifted[i],
+            );
+        }
+        Self::transcript(context, key, ciphertext, &commitments)
+            == self.challenges[0] + self.challenges[1]
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(128);
+        for scalar in self.challenges.iter().chain(self.responses.iter()) {
+            bytes.extend_from_slice(scalar.as_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let chunks = split_chunks(bytes, 4)?;
+        Some(Self {
+            challenges: [decode_scalar(chunks[0])?, decode_scalar(chunks[1])?],
+            responses: [decode_scalar(chunks[2])?, decode_scalar(chunks[3])?],
+        })
+    }
+}
@@ -0,0 +1,173 @@
+//! Linkable spontaneous anonymous group (LSAG) signatures.
+//!
+//! Signature proves that signer knows secret of one of ring keys without revealing which
+//! one. Every signature carries key image `I = x * Hp(P)`, which is the same for all
+//! signatures made with the same key, so repeated signing can be detected.
+
+use curve25519_dalek::{ristretto::RistrettoPoint, scalar::Scalar};
+use rand::{CryptoRng, RngCore};
+use sha2::Sha512;
+
+use super::{decode_point, decode_scalar, Transcript, G};
+
+/// Public key of ring member.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RingKey(pub RistrettoPoint);
+
+impl RingKey {
+    pub fn from_secret(secret: &Scalar) -> Self {
+        Self(secret * G)
+    }
+
+    /// Base point of key image for this key.
+    fn image_base(&self) -> RistrettoPoint {
+        RistrettoPoint::hash_from_bytes::<Sha512>(
+            &[
+                b"crypto-election/key-image".as_ref(),
+                self.0.compress().as_bytes(),
+            ]
+            .concat(),
+        )
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.compress().to_bytes().to_vec()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        decode_point(bytes).map(Self)
+    }
+}
+
+/// Key image linking signatures made with the same secret.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyImage(pub RistrettoPoint);
+
+impl KeyImage {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.compress().to_bytes().to_vec()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        decode_point(bytes).map(Self)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RingSignature {
+    pub key_image: KeyImage,
+    /// Challenge for the first ring member.
+    pub challenge: Scalar,
+    /// Response for every ring member.
+    pub responses: Vec<Scalar>,
+}
+
+impl RingSignature {
+    /// Signs `context` as ring member with position `index`.
+    pub fn sign<R: RngCore + CryptoRng>(
+        context: &[u8],
+        ring: &[RingKey],
+        index: usize,
+        secret: &Scalar,
+        rng: &mut R,
+    ) -> Self {
+        assert!(index < ring.len(), "Signer is out of ring");
+
+        let size = ring.len();
+        let image_base = ring[index].image_base();
+        let key_image = KeyImage(secret * image_base);
+        let transcript = Self::transcript(context, ring, &key_image);
+
+        let mut challenges = vec![Scalar::zero(); size];
+        let mut responses = vec![Scalar::zero(); size];
+
+        let nonce = Scalar::random(rng);
+        challenges[(index + 1) % size] =
+            Self::challenge(&transcript, &(nonce * G), &(nonce * image_base));
+        for offset in 1..size {
+            let i = (index + offset) % size;
+            responses[i] = Scalar::random(rng);
+            let (left, right) =
+                Self::commitments(&ring[i], &key_image, &responses[i], &challenges[i]);
+            challenges[(i + 1) % size] = Self::challenge(&transcript, &left, &right);
+        }
+        responses[index] = nonce - challenges[index] * secret;
+
+        Self {
+            key_image,
+            challenge: challenges[0],
+            responses,
+        }
+    }
+
+    pub fn verify(&self, context: &[u8], ring: &[RingKey]) -> bool {
+        if ring.is_empty() || self.responses.len() != ring.len() {
+            return false;
+        }
+
+        let transcript = Self::transcript(context, ring, &self.key_image);
+        let challenge =
+            ring.iter()
+                .zip(&self.responses)
+                .fold(self.challenge, |challenge, (key, response)| {
+                    let (left, right) =
+                        Self::commitments(key, &self.key_image, response, &challenge);
+                    Self::challenge(&transcript, &left, &right)
+                });
+        challenge == self.challenge
+    }
+
+    fn transcript(context: &[u8], ring: &[RingKey], key_image: &KeyImage) -> Transcript {
+        let mut transcript = Transcript::new(b"crypto-election/lsag");
+        transcript.append_bytes(context);
+        for key in ring {
+            transcript.append_point(&key.0);
+        }
+        transcript.append_point(&key_image.0);
+        transcript
+    }
+
+    fn challenge(transcript: &Transcript, left: &RistrettoPoint, right: &RistrettoPoint) -> Scalar {
+        let mut transcript = transcript.clone();
+        transcript.append_point(left).append_point(right);
+        transcript.challenge()
+    }
+
+    /// `(s * G + c * P, s * Hp(P) + c * I)` for ring member `P`.
+    fn commitments(
+        key: &RingKey,
+        key_image: &KeyImage,
+        response: &Scalar,
+        challenge: &Scalar,
+    ) -> (RistrettoPoint, RistrettoPoint) {
+        (
+            response * G + challenge * key.0,
+            response * key.image_base() + challenge * key_image.0,
+        )
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(64 + self.responses.len() * 32);
+        bytes.extend_from_slice(self.key_image.0.compress().as_bytes());
+        bytes.extend_from_slice(self.challenge.as_bytes());
+        for response in &self.responses {
+            bytes.extend_from_slice(response.as_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 64 || bytes.len() % 32 != 0 {
+            return None;
+        }
+        let (header, responses) = bytes.split_at(64);
+        Some(Self {
+            key_image: KeyImage::from_bytes(&header[..32])?,
+            challenge: decode_scalar(&header[32..])?,
+            responses: responses
+                .chunks(32)
+                .map(decode_scalar)
+                .collect::<Option<_>>()?,
+        })
+    }
+}
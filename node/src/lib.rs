@@ -23,6 +23,14 @@ mod proto;
 
 pub mod constant;
 
+pub mod crypto;
+
 pub mod schema;
 
+pub mod pii_store;
+
+pub mod contact_verifier;
+
+mod migrations;
+
 mod tx_behavior;
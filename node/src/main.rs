@@ -1,13 +1,65 @@
+use std::{env, sync::Arc};
+
 use crypto_election_node as election;
+use election::contact_verifier::{
+    ContactGateway, ContactVerifier, FileSinkGateway, HttpSmsGateway, SmtpGateway,
+};
 use exonum_cli::{NodeBuilder, Spec};
 
+/// Builds contact verifier from `CONTACT_SMTP_*` and `CONTACT_SMS_*` variables. Messages
+/// of unconfigured gateways are written to `CONTACT_SINK_PATH` file, which is only used if
+/// set explicitly, so that codes are never silently dropped into a local file.
+fn contact_verifier() -> anyhow::Result<ContactVerifier> {
+    let sink = env::var("CONTACT_SINK_PATH")
+        .ok()
+        .map(|path| Arc::new(FileSinkGateway { path: path.into() }) as Arc<dyn ContactGateway>);
+    let sink_or = |gateway: &str, variable: &str| {
+        sink.clone().ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} gateway is not configured by {} and CONTACT_SINK_PATH is not set",
+                gateway,
+                variable
+            )
+        })
+    };
+
+    let email_gateway: Arc<dyn ContactGateway> = match env::var("CONTACT_SMTP_RELAY") {
+        Ok(relay) => Arc::new(SmtpGateway {
+            relay,
+            username: env::var("CONTACT_SMTP_USERNAME").unwrap_or_default(),
+            password: env::var("CONTACT_SMTP_PASSWORD").unwrap_or_default(),
+            from: env::var("CONTACT_SMTP_FROM").unwrap_or_default(),
+        }),
+        Err(_) => sink_or("Email", "CONTACT_SMTP_RELAY")?,
+    };
+    let sms_gateway: Arc<dyn ContactGateway> = match env::var("CONTACT_SMS_URL") {
+        Ok(url) => Arc::new(HttpSmsGateway {
+            url,
+            token: env::var("CONTACT_SMS_TOKEN").unwrap_or_default(),
+            from: env::var("CONTACT_SMS_FROM").unwrap_or_default(),
+        }),
+        Err(_) => sink_or("SMS", "CONTACT_SMS_URL")?,
+    };
+
+    Ok(ContactVerifier::new(email_gateway, sms_gateway))
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     exonum::helpers::init_logger()?;
 
+    let pii_store_path = env::var("PII_STORE_PATH").unwrap_or_else(|_| "pii".to_owned());
+    let pass_code_key = env::var("PASS_CODE_KEY")
+        .map_err(|_| anyhow::anyhow!("PASS_CODE_KEY of pass code fingerprints is not set"))?;
+    let pii_store =
+        election::pii_store::PiiStore::open(pii_store_path, pass_code_key.into_bytes())?;
+
     NodeBuilder::new()
         .with(Spec::new(exonum_time::TimeServiceFactory::default()))
-        .with(Spec::new(election::service::ElectionService))
+        .with(Spec::migrating(election::service::ElectionService::new(
+            pii_store,
+            contact_verifier()?,
+        )))
         .run()
         .await
 }
@@ -0,0 +1,462 @@
+//! Data migrations between versions of the service.
+
+use exonum::{
+    crypto::{self, Hash},
+    merkledb::{
+        access::{Access, RawAccessMut},
+        ObjectHash,
+    },
+    runtime::migrations::{MigrationContext, MigrationError},
+};
+
+use crate::{
+    model::{
+        geo,
+        pii::{PiiField, PiiOpening},
+        Administration, BallotMode, ElectionTally, LocationRecord, Participant, ParticipantAddress,
+        ParticipantStatus,
+    },
+    pii_store::PiiStore,
+    schema::{Schema, SchemaImpl},
+};
+
+/// Converts administration areas to micro-degree coordinates and builds the index of
+/// administration tree used by location lookup.
+pub(crate) fn migrate_to_fixed_point(context: &mut MigrationContext) -> Result<(), MigrationError> {
+    let old_schema = v03::Schema::new(context.helper.old_data());
+    let mut new_schema = v04::Schema::new(context.helper.new_data());
+
+    for (addr, old) in old_schema.administrations.iter() {
+        let area: geo::Polygon = old.area.into();
+
+        match old.principal_key.0 {
+            Some(principal) => new_schema
+                .administration_children
+                .get(&principal)
+                .insert(addr),
+            None => new_schema.root_administrations.insert(addr),
+        }
+        // Empty areas contain no locations and have no bounds.
+        if !area.exterior.items.is_empty() {
+            new_schema
+                .administration_bounds
+                .put(&addr, area.bounding_box());
+        }
+
+        let administration = v04::Administration {
+            addr: old.addr,
+            name: old.name,
+            principal_key: old.principal_key,
+            area,
+            administration_level: old.administration_level,
+            history_len: old.history_len,
+            history_hash: old.history_hash,
+        };
+        new_schema.administrations.put(&addr, administration);
+    }
+
+    Ok(())
+}
+
+/// Wraps single polygon areas of administrations into multi-polygons.
+pub(crate) fn migrate_to_multi_polygon(
+    context: &mut MigrationContext,
+) -> Result<(), MigrationError> {
+    let old_schema = v04::Schema::new(context.helper.old_data());
+    let mut schema = SchemaImpl::new(context.helper.new_data());
+
+    for (addr, old) in old_schema.administrations.iter() {
+        // Empty areas become multi-polygons without polygons.
+        let area = if old.area.exterior.items.is_empty() {
+            geo::MultiPolygon { polygons: vec![] }
+        } else {
+            old.area.into()
+        };
+
+        let administration = Administration {
+            addr: old.addr,
+            name: old.name,
+            principal_key: old.principal_key,
+            area,
+            administration_level: old.administration_level,
+            history_len: old.history_len,
+            history_hash: old.history_hash,
+        };
+        schema.public.administrations.put(&addr, administration);
+    }
+
+    Ok(())
+}
+
+/// Replaces personal data of participants stored in clear with salted commitments and
+/// moves the data itself to the node-local `PiiStore`.
+///
+/// Salts are derived from participant addresses, so every node makes the same commitments.
+/// They do not hide the data, which has been public on-chain anyway. Pass codes are left
+/// without fingerprints until participants submit them to a validator.
+pub(crate) fn migrate_to_pii_commitments(
+    context: &mut MigrationContext,
+    pii_store: &PiiStore,
+) -> Result<(), MigrationError> {
+    let old_schema = v03::Schema::new(context.helper.old_data());
+    let mut schema = SchemaImpl::new(context.helper.new_data());
+
+    let mut openings = Vec::new();
+    for (addr, old) in old_schema.participants.iter() {
+        let email = PiiOpening::new(
+            PiiField::Email,
+            &old.email,
+            legacy_salt(&addr, PiiField::Email),
+        );
+        let phone_number = PiiOpening::new(
+            PiiField::PhoneNumber,
+            &old.phone_number,
+            legacy_salt(&addr, PiiField::PhoneNumber),
+        );
+        let pass_code = PiiOpening::new(
+            PiiField::PassCode,
+            &old.pass_code,
+            legacy_salt(&addr, PiiField::PassCode),
+        );
+
+        let participant = Participant {
+            addr: old.addr,
+            name: old.name,
+            email_commitment: email.commitment(),
+            phone_number_commitment: phone_number.commitment(),
+            pass_code_commitment: pass_code.commitment(),
+            pass_code_fingerprint: Hash::zero(),
+            residence: old.residence,
+            history_len: old.history_len,
+            history_hash: old.history_hash,
+            attributes: Vec::new(),
+            // Legacy participants have voted without verification, so they keep the right.
+            status: ParticipantStatus::Verified,
+            is_email_verified: false,
+            is_phone_number_verified: false,
+        };
+        schema.public.participants.put(&addr, participant);
+        openings.extend(vec![email, phone_number, pass_code]);
+    }
+
+    pii_store.put(openings).map_err(MigrationError::new)
+}
+
+/// Converts location history of participants to records with containing administrations.
+/// Legacy records keep the only administration, which was resolved at submission.
+pub(crate) fn migrate_location_records(
+    context: &mut MigrationContext,
+) -> Result<(), MigrationError> {
+    let participants = SchemaImpl::new(context.helper.old_data())
+        .public
+        .participants;
+    let old_schema = v05::Schema::new(context.helper.old_data());
+    let mut schema = SchemaImpl::new(context.helper.new_data());
+
+    for addr in participants.keys() {
+        let mut locations = schema.public.participant_location_history.get(&addr);
+        for location in old_schema.participant_location_history.get(&addr).iter() {
+            let (date, administration) = location.0;
+            locations.push(LocationRecord {
+                date,
+                administrations: vec![administration],
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn legacy_salt(addr: &ParticipantAddress, field: PiiField) -> Hash {
+    let mut buffer = b"crypto-election/legacy-pii".to_vec();
+    buffer.extend_from_slice(addr.as_ref());
+    buffer.push(field.tag());
+    crypto::hash(&buffer)
+}
+
+/// Fills indexes which were introduced in version 0.6 for elections and participants created
+/// before it.
+///
+/// Migrated indexes replace the old ones as a whole, so they are built from the old data
+/// for every election.
+pub(crate) fn backfill_election_indexes(
+    context: &mut MigrationContext,
+) -> Result<(), MigrationError> {
+    let old_schema = SchemaImpl::new(context.helper.old_data()).public;
+    let mut schema = SchemaImpl::new(context.helper.new_data());
+
+    backfill_vote_roots(&old_schema, &mut schema);
+    backfill_tallies(&old_schema, &mut schema);
+    backfill_participant_indexes(&old_schema, &mut schema);
+    Ok(())
+}
+
+/// Proof roots of votes, by which vote receipts are proven.
+fn backfill_vote_roots<T, U>(old_schema: &Schema<T>, schema: &mut SchemaImpl<U>)
+where
+    T: Access,
+    U: Access,
+    U::Base: RawAccessMut,
+{
+    for election_id in old_schema.elections.keys() {
+        let votes = old_schema.election_votes.get(&election_id);
+        if votes.iter().next().is_some() {
+            schema
+                .public
+                .election_vote_roots
+                .put(&election_id, votes.object_hash());
+        }
+    }
+}
+
+/// Tallies of elections, which are updated on every ballot counted in clear. Tallies of
+/// decrypted elections are kept.
+fn backfill_tallies<T, U>(old_schema: &Schema<T>, schema: &mut SchemaImpl<U>)
+where
+    T: Access,
+    U: Access,
+    U::Base: RawAccessMut,
+{
+    for (election_id, election) in old_schema.elections.iter() {
+        if let Some(tally) = old_schema.election_tallies.get(&election_id) {
+            schema.public.election_tallies.put(&election_id, tally);
+            continue;
+        }
+        if election.ballot_mode == BallotMode::Encrypted
+            || election.ballot_mode == BallotMode::Mixnet
+        {
+            continue;
+        }
+
+        let mut tally = ElectionTally::new(&election.options);
+        let votes = old_schema.election_votes.get(&election_id);
+        let token_votes = old_schema.spent_voting_tokens.get(&election_id);
+        let ring_votes = old_schema.spent_key_images.get(&election_id);
+        let ballots = votes
+            .values()
+            .chain(token_votes.values())
+            .chain(ring_votes.values());
+        for option_id in ballots {
+            tally.count(option_id);
+        }
+        schema.public.election_tallies.put(&election_id, tally);
+    }
+}
+
+/// Elections and voter rolls of specific participants, which are moved on key rotation.
+fn backfill_participant_indexes<T, U>(old_schema: &Schema<T>, schema: &mut SchemaImpl<U>)
+where
+    T: Access,
+    U: Access,
+    U::Base: RawAccessMut,
+{
+    for election_id in old_schema.elections.keys() {
+        let votes = old_schema.election_votes.get(&election_id);
+        let commitments = old_schema.election_commitments.get(&election_id);
+        let encrypted_ballots = old_schema.encrypted_ballots.get(&election_id);
+        let mixnet_ballots = old_schema.mixnet_ballots.get(&election_id);
+        let token_sessions = old_schema.token_issuance.get(&election_id);
+        let ring_keys = old_schema.ring_keys.get(&election_id);
+        let participants = votes
+            .keys()
+            .chain(commitments.keys())
+            .chain(encrypted_ballots.keys())
+            .chain(mixnet_ballots.keys())
+            .chain(token_sessions.keys())
+            .chain(ring_keys.keys());
+        for participant in participants {
+            schema
+                .public
+                .participant_elections
+                .get(&participant)
+                .insert(election_id);
+        }
+    }
+
+    for roll_id in old_schema.voter_roll_owners.keys() {
+        for participant in old_schema.voter_rolls.get(&roll_id).iter() {
+            schema
+                .public
+                .participant_voter_rolls
+                .get(&participant)
+                .insert(roll_id);
+        }
+    }
+}
+
+/// Data layout of service version 0.3, which stored coordinates as floating point degrees
+/// and personal data of participants in clear.
+mod v03 {
+    use exonum::crypto::Hash;
+    use exonum_merkledb::{
+        access::{Access, FromAccess},
+        RawProofMapIndex,
+    };
+
+    use anyhow as failure;
+    use exonum_derive::{BinaryValue, FromAccess, ObjectHash};
+    use exonum_proto::ProtobufConvert;
+
+    use crate::{
+        model::{geo, wrappers::OptionalContainer, AdministrationAddress, ParticipantAddress},
+        proto,
+    };
+
+    #[derive(Debug, FromAccess)]
+    pub struct Schema<T: Access> {
+        pub participants: RawProofMapIndex<T::Base, ParticipantAddress, Participant>,
+        pub administrations: RawProofMapIndex<T::Base, AdministrationAddress, Administration>,
+    }
+
+    impl<T: Access> Schema<T> {
+        pub fn new(access: T) -> Self {
+            Self::from_root(access).unwrap()
+        }
+    }
+
+    #[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+    #[protobuf_convert(source = "proto::LegacyParticipant")]
+    pub struct Participant {
+        pub addr: ParticipantAddress,
+        pub name: String,
+        pub email: String,
+        pub phone_number: String,
+        pub pass_code: String,
+        pub residence: OptionalContainer<AdministrationAddress>,
+        pub history_len: u64,
+        pub history_hash: Hash,
+    }
+
+    #[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+    #[protobuf_convert(source = "proto::LegacyAdministration")]
+    pub struct Administration {
+        pub addr: AdministrationAddress,
+        pub name: String,
+        pub principal_key: OptionalContainer<AdministrationAddress>,
+        pub area: Polygon,
+        pub administration_level: u32,
+        pub history_len: u64,
+        pub history_hash: Hash,
+    }
+
+    #[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+    #[protobuf_convert(source = "proto::LegacyPolygon")]
+    pub struct Polygon {
+        pub exterior: LineString,
+        pub interiors: Vec<LineString>,
+    }
+
+    #[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+    #[protobuf_convert(source = "proto::LegacyLineString")]
+    pub struct LineString {
+        pub items: Vec<Coordinate>,
+    }
+
+    #[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+    #[protobuf_convert(source = "proto::LegacyCoordinate")]
+    pub struct Coordinate {
+        pub x: f64,
+        pub y: f64,
+    }
+
+    impl From<Polygon> for geo::Polygon {
+        fn from(polygon: Polygon) -> Self {
+            Self {
+                exterior: polygon.exterior.into(),
+                interiors: polygon.interiors.into_iter().map(Into::into).collect(),
+            }
+        }
+    }
+
+    impl From<LineString> for geo::LineString {
+        fn from(line_string: LineString) -> Self {
+            line_string
+                .items
+                .into_iter()
+                .map(|coordinate| geo::Coordinate::from_degrees(coordinate.x, coordinate.y))
+                .collect()
+        }
+    }
+}
+
+/// Data layout of service version 0.4, which stored area of administration as single
+/// polygon.
+mod v04 {
+    use exonum::crypto::Hash;
+    use exonum_merkledb::{
+        access::{Access, FromAccess},
+        Group, KeySetIndex, RawProofMapIndex,
+    };
+
+    use anyhow as failure;
+    use exonum_derive::{BinaryValue, FromAccess, ObjectHash};
+    use exonum_proto::ProtobufConvert;
+
+    use crate::{
+        model::{geo, wrappers::OptionalContainer, AdministrationAddress},
+        proto,
+    };
+
+    #[derive(Debug, FromAccess)]
+    pub struct Schema<T: Access> {
+        pub administrations: RawProofMapIndex<T::Base, AdministrationAddress, Administration>,
+        pub root_administrations: KeySetIndex<T::Base, AdministrationAddress>,
+        pub administration_children:
+            Group<T, AdministrationAddress, KeySetIndex<T::Base, AdministrationAddress>>,
+        pub administration_bounds:
+            RawProofMapIndex<T::Base, AdministrationAddress, geo::BoundingBox>,
+    }
+
+    impl<T: Access> Schema<T> {
+        pub fn new(access: T) -> Self {
+            Self::from_root(access).unwrap()
+        }
+    }
+
+    #[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+    #[protobuf_convert(source = "proto::SinglePolygonAdministration")]
+    pub struct Administration {
+        pub addr: AdministrationAddress,
+        pub name: String,
+        pub principal_key: OptionalContainer<AdministrationAddress>,
+        pub area: geo::Polygon,
+        pub administration_level: u32,
+        pub history_len: u64,
+        pub history_hash: Hash,
+    }
+}
+
+/// Data layout of service version 0.5, which stored the only administration containing
+/// submitted location.
+mod v05 {
+    use chrono::{DateTime, Utc};
+    use exonum_merkledb::{
+        access::{Access, FromAccess},
+        Group, ProofListIndex,
+    };
+
+    use exonum_derive::FromAccess;
+
+    use crate::model::{AdministrationAddress, ParticipantAddress};
+
+    binary_value_tuple_impls! {
+        #[derive(Debug)]
+        pub TupleContainer {
+            (DateTime<Utc>, AdministrationAddress),
+        }
+    }
+
+    pub type TimePositionInfo = TupleContainer<(DateTime<Utc>, AdministrationAddress)>;
+
+    #[derive(Debug, FromAccess)]
+    pub struct Schema<T: Access> {
+        pub participant_location_history:
+            Group<T, ParticipantAddress, ProofListIndex<T::Base, TimePositionInfo>>,
+    }
+
+    impl<T: Access> Schema<T> {
+        pub fn new(access: T) -> Self {
+            Self::from_root(access).unwrap()
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Approval of new administrations.
+//!
+//! Creation request of administration carries counter-signatures over the creation
+//! message. Child administration is approved by its principal, or by enough signers of
+//! multi-signature principal. Root administration is approved by a supermajority of
+//! validators. Creation message includes the expiration time, so the collected approvals
+//! can not be replayed later.
+
+use chrono::{DateTime, Duration, Utc};
+use exonum::{
+    crypto::{self, Hash, KeyPair, PublicKey, Signature},
+    runtime::CallerAddress as Address,
+};
+
+use anyhow as failure;
+use exonum_derive::{BinaryValue, ObjectHash};
+use exonum_merkledb::ObjectHash;
+use exonum_proto::ProtobufConvert;
+
+use super::{geo::MultiPolygon, AdministrationAddress};
+use crate::proto;
+
+const CREATION_DOMAIN: &[u8] = b"crypto-election/administration-creation";
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::CounterSignature", serde_pb_convert)]
+pub struct CounterSignature {
+    pub key: PublicKey,
+    pub signature: Signature,
+}
+
+impl CounterSignature {
+    pub fn verify(&self, message: &Hash) -> bool {
+        crypto::verify(&self.signature, message.as_ref(), &self.key)
+    }
+
+    pub fn signer(&self) -> Address {
+        Address::from_key(self.key)
+    }
+}
+
+/// Maximal time between blockchain time and expiration of creation request.
+pub fn approval_lifetime() -> Duration {
+    Duration::days(1)
+}
+
+/// Calculates message approving creation of administration `addr`.
+pub fn creation_message(
+    addr: &AdministrationAddress,
+    name: &str,
+    principal: Option<&AdministrationAddress>,
+    area: &MultiPolygon,
+    expires_at: DateTime<Utc>,
+) -> Hash {
+    let principal: &[u8] = principal.map_or(&[], |addr| addr.as_ref());
+    crypto::hash(
+        &[
+            CREATION_DOMAIN,
+            addr.as_ref(),
+            &(name.len() as u64).to_le_bytes(),
+            name.as_bytes(),
+            principal,
+            area.object_hash().as_ref(),
+            &expires_at.timestamp_millis().to_le_bytes(),
+        ]
+        .concat(),
+    )
+}
+
+/// Counter-signs creation of administration `addr`.
+pub fn sign_creation(
+    addr: &AdministrationAddress,
+    name: &str,
+    principal: Option<&AdministrationAddress>,
+    area: &MultiPolygon,
+    expires_at: DateTime<Utc>,
+    keys: &KeyPair,
+) -> CounterSignature {
+    let message = creation_message(addr, name, principal, area, expires_at);
+    CounterSignature {
+        key: keys.public_key(),
+        signature: crypto::sign(message.as_ref(), keys.secret_key()),
+    }
+}
+
+/// Number of validators required to approve root administration.
+pub fn validator_supermajority(validators: usize) -> usize {
+    validators * 2 / 3 + 1
+}
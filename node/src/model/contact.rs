@@ -0,0 +1,90 @@
+//! Verification of participant contacts.
+//!
+//! Validator node sends a random code to email or phone number of participant and
+//! publishes the challenge, which is the hash of the keyed code hash. Participant proves
+//! that the code is received by publishing the keyed code hash before the challenge
+//! expires. Keyed hash binds the code to participant and contact, so the code can not be
+//! confirmed on behalf of anyone else.
+
+use chrono::{DateTime, Duration, Utc};
+use exonum::crypto::{self, Hash};
+use rand::{CryptoRng, Rng};
+
+use anyhow as failure;
+use exonum_derive::{BinaryValue, ObjectHash};
+use exonum_proto::ProtobufConvert;
+
+use super::{pii::PiiField, ParticipantAddress};
+use crate::proto;
+
+/// Number of characters in verification code.
+pub const CODE_LENGTH: usize = 12;
+
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const REQUEST_DOMAIN: &[u8] = b"crypto-election/contact-verification-request";
+
+/// Time during which code can be confirmed.
+pub fn code_lifetime() -> Duration {
+    Duration::hours(1)
+}
+
+/// Maximal difference between the time of code request and blockchain time.
+pub fn request_lifetime() -> Duration {
+    Duration::minutes(5)
+}
+
+/// Published challenge of contact verification.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::ContactChallenge", serde_pb_convert)]
+pub struct ContactChallenge {
+    pub challenge: Hash,
+    pub expires_at: DateTime<Utc>,
+    /// Participant address which the code is keyed by. It is kept when participant rotates
+    /// its key, so the code sent before rotation is confirmed with the same `code_mac`.
+    pub participant: ParticipantAddress,
+}
+
+/// Checks whether field is a contact which can be verified.
+pub fn is_contact(field: PiiField) -> bool {
+    field == PiiField::Email || field == PiiField::PhoneNumber
+}
+
+/// Calculates message signed by participant to request verification code at `requested_at`.
+pub fn request_message(
+    participant: &ParticipantAddress,
+    field: PiiField,
+    requested_at: DateTime<Utc>,
+) -> Hash {
+    crypto::hash(
+        &[
+            REQUEST_DOMAIN,
+            participant.as_ref(),
+            &[field.tag()],
+            &requested_at.timestamp_millis().to_le_bytes(),
+        ]
+        .concat(),
+    )
+}
+
+/// Generates random verification code.
+pub fn random_code<R: Rng + CryptoRng>(rng: &mut R) -> String {
+    (0..CODE_LENGTH)
+        .map(|_| CODE_ALPHABET[rng.gen_range(0, CODE_ALPHABET.len())] as char)
+        .collect()
+}
+
+/// Calculates hash of code keyed by participant and contact, which confirms the contact.
+pub fn code_mac(participant: &ParticipantAddress, field: PiiField, code: &str) -> Hash {
+    let code = code.trim().to_uppercase();
+    crypto::hash(&[participant.as_ref(), &[field.tag()], code.as_bytes()].concat())
+}
+
+/// Calculates challenge published for the code.
+pub fn code_challenge(participant: &ParticipantAddress, field: PiiField, code: &str) -> Hash {
+    crypto::hash(code_mac(participant, field, code).as_ref())
+}
+
+/// Key of contact challenge in the index.
+pub fn challenge_id(participant: &ParticipantAddress, field: PiiField) -> Hash {
+    crypto::hash(&[participant.as_ref(), &[field.tag()]].concat())
+}
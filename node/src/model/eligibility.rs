@@ -0,0 +1,224 @@
+//! Declarative eligibility expressions attached to elections.
+//!
+//! Expression is stored as a flat list of terms in prefix (Polish) notation, e.g.
+//! `[AND, ATTRIBUTE_IN("citizen", ["yes"]), RESIDES_WITHIN(<district>)]`.
+//! Such representation has a canonical binary form and is evaluated without any
+//! floating or platform dependent behavior, so all validators come to the same result.
+
+use exonum::crypto::Hash;
+
+use anyhow as failure;
+use exonum_derive::{BinaryValue, ObjectHash};
+use exonum_proto::ProtobufConvert;
+
+use super::{wrappers::OptionalContainer, AdministrationAddress};
+use crate::proto;
+
+/// Maximal count of terms in a single expression.
+pub const MAX_TERMS: usize = 64;
+/// Maximal nesting depth of an expression.
+pub const MAX_DEPTH: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EligibilityOperator {
+    /// Both of two following sub-expressions are satisfied.
+    And,
+    /// Any of two following sub-expressions is satisfied.
+    Or,
+    /// Following sub-expression is not satisfied.
+    Not,
+    /// Participant attribute `attribute` has one of `values`.
+    AttributeIn,
+    /// Participant residence is `target` administration or any of its subordinates.
+    ResidesWithin,
+    /// Participant is on voter roll with `target` id.
+    OnRoll,
+}
+
+impl ProtobufConvert for EligibilityOperator {
+    type ProtoStruct = proto::EligibilityOperator;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        match self {
+            Self::And => proto::EligibilityOperator::AND,
+            Self::Or => proto::EligibilityOperator::OR,
+            Self::Not => proto::EligibilityOperator::NOT,
+            Self::AttributeIn => proto::EligibilityOperator::ATTRIBUTE_IN,
+            Self::ResidesWithin => proto::EligibilityOperator::RESIDES_WITHIN,
+            Self::OnRoll => proto::EligibilityOperator::ON_ROLL,
+        }
+    }
+
+    fn from_pb(pb: Self::ProtoStruct) -> anyhow::Result<Self> {
+        Ok(match pb {
+            proto::EligibilityOperator::AND => Self::And,
+            proto::EligibilityOperator::OR => Self::Or,
+            proto::EligibilityOperator::NOT => Self::Not,
+            proto::EligibilityOperator::ATTRIBUTE_IN => Self::AttributeIn,
+            proto::EligibilityOperator::RESIDES_WITHIN => Self::ResidesWithin,
+            proto::EligibilityOperator::ON_ROLL => Self::OnRoll,
+        })
+    }
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::EligibilityTerm", serde_pb_convert)]
+pub struct EligibilityTerm {
+    pub operator: EligibilityOperator,
+    /// Attribute key. Used by `AttributeIn` only.
+    pub attribute: String,
+    /// Accepted attribute values. Used by `AttributeIn` only.
+    pub values: Vec<String>,
+    /// Administration address or voter roll id. Used by `ResidesWithin` and `OnRoll`.
+    pub target: OptionalContainer<Hash>,
+}
+
+impl EligibilityTerm {
+    fn operator(operator: EligibilityOperator) -> Self {
+        Self {
+            operator,
+            attribute: String::new(),
+            values: Vec::new(),
+            target: None.into(),
+        }
+    }
+
+    pub fn and() -> Self {
+        Self::operator(EligibilityOperator::And)
+    }
+
+    pub fn or() -> Self {
+        Self::operator(EligibilityOperator::Or)
+    }
+
+    pub fn not() -> Self {
+        Self::operator(EligibilityOperator::Not)
+    }
+
+    pub fn attribute_in<S: ToString>(attribute: &str, values: &[S]) -> Self {
+        Self {
+            attribute: attribute.to_owned(),
+            values: values.iter().map(ToString::to_string).collect(),
+            ..Self::operator(EligibilityOperator::AttributeIn)
+        }
+    }
+
+    pub fn resides_within(administration: &AdministrationAddress) -> Self {
+        Self {
+            target: Some(Hash::from_slice(administration.as_ref()).unwrap()).into(),
+            ..Self::operator(EligibilityOperator::ResidesWithin)
+        }
+    }
+
+    pub fn on_roll(roll_id: &Hash) -> Self {
+        Self {
+            target: Some(*roll_id).into(),
+            ..Self::operator(EligibilityOperator::OnRoll)
+        }
+    }
+}
+
+/// Facts about participant needed to evaluate an eligibility expression.
+pub trait EligibilityContext {
+    /// Value of participant attribute with given key.
+    fn attribute(&self, key: &str) -> Option<&str>;
+
+    /// Whether participant residence is `administration` or any of its subordinates.
+    fn resides_within(&self, administration: &Hash) -> bool;
+
+    /// Whether participant is on voter roll with given id.
+    fn is_on_roll(&self, roll_id: &Hash) -> bool;
+}
+
+/// Reference to an eligibility expression.
+#[derive(Debug, Clone, Copy)]
+pub struct EligibilityRule<'a> {
+    terms: &'a [EligibilityTerm],
+}
+
+impl<'a> EligibilityRule<'a> {
+    pub fn new(terms: &'a [EligibilityTerm]) -> Self {
+        Self { terms }
+    }
+
+    /// Iterates over `(operator, target)` pairs of terms referring to chain objects.
+    pub fn targets(&self) -> impl Iterator<Item = (EligibilityOperator, Hash)> + 'a {
+        self.terms
+            .iter()
+            .filter_map(|term| term.target.0.map(|target| (term.operator, target)))
+    }
+
+    /// Checks that expression is well-formed: every operator has all of its operands,
+    /// there are no trailing terms and size limits are respected.
+    pub fn is_well_formed(&self) -> bool {
+        if self.terms.is_empty() {
+            return true;
+        }
+        if self.terms.len() > MAX_TERMS {
+            return false;
+        }
+        let mut position = 0;
+        self.check_at(&mut position, 0) && position == self.terms.len()
+    }
+
+    fn check_at(&self, position: &mut usize, depth: usize) -> bool {
+        if depth >= MAX_DEPTH {
+            return false;
+        }
+        let term = match self.terms.get(*position) {
+            Some(term) => term,
+            None => return false,
+        };
+        *position += 1;
+
+        match term.operator {
+            EligibilityOperator::And | EligibilityOperator::Or => {
+                self.check_at(position, depth + 1) && self.check_at(position, depth + 1)
+            }
+            EligibilityOperator::Not => self.check_at(position, depth + 1),
+            EligibilityOperator::AttributeIn => {
+                !term.attribute.is_empty() && !term.values.is_empty()
+            }
+            EligibilityOperator::ResidesWithin | EligibilityOperator::OnRoll => {
+                term.target.0.is_some()
+            }
+        }
+    }
+
+    /// Evaluates expression. Empty expression allows everyone.
+    ///
+    /// Expression must be well-formed, see [`is_well_formed`](#method.is_well_formed).
+    pub fn evaluate(&self, context: &impl EligibilityContext) -> bool {
+        if self.terms.is_empty() {
+            return true;
+        }
+        let mut position = 0;
+        self.evaluate_at(&mut position, context)
+    }
+
+    fn evaluate_at(&self, position: &mut usize, context: &impl EligibilityContext) -> bool {
+        let term = &self.terms[*position];
+        *position += 1;
+
+        match term.operator {
+            EligibilityOperator::And => {
+                let left = self.evaluate_at(position, context);
+                let right = self.evaluate_at(position, context);
+                left && right
+            }
+            EligibilityOperator::Or => {
+                let left = self.evaluate_at(position, context);
+                let right = self.evaluate_at(position, context);
+                left || right
+            }
+            EligibilityOperator::Not => !self.evaluate_at(position, context),
+            EligibilityOperator::AttributeIn => context
+                .attribute(&term.attribute)
+                .map_or(false, |value| term.values.iter().any(|v| v == value)),
+            EligibilityOperator::ResidesWithin => {
+                context.resides_within(term.target.0.as_ref().unwrap())
+            }
+            EligibilityOperator::OnRoll => context.is_on_roll(term.target.0.as_ref().unwrap()),
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//! Data models of `BallotMode::Encrypted` elections.
+//!
+//! Election key is generated by trustees in three phases, each closed once every trustee
+//! has taken part or on timeout: trustees publish transport keys, deal shares of their
+//! secrets to each other and acknowledge dealings, complaining about invalid shares.
+//! Any `threshold` trustees holding valid shares decrypt the tally together.
+
+use std::borrow::Cow;
+
+use chrono::{DateTime, Duration, Utc};
+use exonum::crypto::{PublicKey, SecretKey};
+use exonum_merkledb::BinaryValue;
+use rand::{CryptoRng, RngCore};
+
+use anyhow as failure;
+use exonum_derive::{BinaryValue, ObjectHash};
+use exonum_proto::ProtobufConvert;
+
+use super::{ElectionAddress, ParticipantAddress};
+use crate::{
+    crypto::{
+        self,
+        ballot::{EncryptedBallot, PartialDecryption},
+        dkg::{Dealing, SharedKey},
+        elgamal::{Ciphertext, EncryptionKey},
+        proofs::DleqProof,
+    },
+    proto,
+};
+
+/// Implements protobuf (as `bytes`) and database conversions for crypto types.
+macro_rules! impl_bytes_convert {
+    ($($name:ty),*) => {
+        $(
+            impl ProtobufConvert for $name {
+                type ProtoStruct = Vec<u8>;
+
+                fn to_pb(&self) -> Self::ProtoStruct {
+                    self.to_bytes()
+                }
+
+                fn from_pb(pb: Self::ProtoStruct) -> anyhow::Result<Self> {
+                    Self::from_bytes(&pb).ok_or_else(|| {
+                        anyhow::format_err!("Malformed {}", stringify!($name))
+                    })
+                }
+            }
+
+            impl BinaryValue for $name {
+                fn to_bytes(&self) -> Vec<u8> {
+                    <$name>::to_bytes(self)
+                }
+
+                fn from_bytes(bytes: Cow<'_, [u8]>) -> anyhow::Result<Self> {
+                    <$name as ProtobufConvert>::from_pb(bytes.into_owned())
+                }
+            }
+
+            impl_object_hash_for_binary_value! { $name }
+        )*
+    };
+}
+
+impl_bytes_convert!(
+    EncryptionKey,
+    Ciphertext,
+    DleqProof,
+    EncryptedBallot,
+    PartialDecryption,
+    Dealing,
+    SharedKey
+);
+
+/// Maximal duration of one phase of key generation.
+pub fn key_generation_phase() -> Duration {
+    Duration::minutes(10)
+}
+
+/// Number of trustees required to decrypt: more than the third of trustees, which may be
+/// faulty, while the rest can decrypt without them.
+pub fn decryption_threshold(trustees_count: usize) -> usize {
+    (trustees_count.max(1) - 1) / 3 + 1
+}
+
+/// Phase of election key generation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyGenerationPhase {
+    /// Trustees publish transport keys.
+    Transport,
+    /// Trustees with transport keys deal shares of their secrets.
+    Dealing,
+    /// Trustees with transport keys acknowledge dealings or complain about them.
+    Acknowledgement,
+    /// Key generation is over and the key is waiting to be combined.
+    Finished,
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::ElectionEncryption", serde_pb_convert)]
+pub struct ElectionEncryption {
+    /// Service keys of validators holding shares of the election key.
+    pub trustees: Vec<PublicKey>,
+    /// Sum of key shares of all trustees. Valid when `is_key_ready` is set.
+    pub joint_key: EncryptionKey,
+    pub is_key_ready: bool,
+    /// Homomorphic sum of ballots, one ciphertext per election option.
+    pub tally: Vec<Ciphertext>,
+    pub ballots_count: u32,
+    /// Decrypted tally. Valid when `is_decrypted` is set.
+    pub results: Vec<u32>,
+    pub is_decrypted: bool,
+    /// Number of trustees required to decrypt.
+    pub threshold: u32,
+    /// Time by which trustees publish transport keys. Every next phase lasts at most
+    /// `key_generation_phase()`.
+    pub key_deadline: DateTime<Utc>,
+    /// Trustees whose dealings make up the election key. Valid when `is_key_ready` is set.
+    pub qualified: Vec<PublicKey>,
+    /// Public keys of secret shares of trustees, in the order of trustees. Valid when
+    /// `is_key_ready` is set.
+    pub share_keys: Vec<EncryptionKey>,
+}
+
+impl ElectionEncryption {
+    pub fn new(
+        trustees: Vec<PublicKey>,
+        options_count: usize,
+        key_deadline: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            threshold: decryption_threshold(trustees.len()) as u32,
+            trustees,
+            joint_key: EncryptionKey::combine(std::iter::empty()),
+            is_key_ready: false,
+            tally: vec![Ciphertext::zero(); options_count],
+            ballots_count: 0,
+            results: Vec::new(),
+            is_decrypted: false,
+            key_deadline,
+            qualified: Vec::new(),
+            share_keys: Vec::new(),
+        }
+    }
+
+    pub fn is_trustee(&self, key: &PublicKey) -> bool {
+        self.trustees.contains(key)
+    }
+
+    pub fn trustee_position(&self, key: &PublicKey) -> Option<usize> {
+        self.trustees.iter().position(|trustee| trustee == key)
+    }
+
+    /// Returns the current phase of key generation given the numbers of published
+    /// transport keys, dealings and acknowledgements.
+    pub fn key_generation_phase(
+        &self,
+        now: DateTime<Utc>,
+        transport_keys: usize,
+        dealings: usize,
+        acknowledgements: usize,
+    ) -> KeyGenerationPhase {
+        let phase = key_generation_phase();
+        if transport_keys < self.trustees.len() && now < self.key_deadline {
+            KeyGenerationPhase::Transport
+        } else if dealings < transport_keys && now < self.key_deadline + phase {
+            KeyGenerationPhase::Dealing
+        } else if acknowledgements < transport_keys && now < self.key_deadline + phase + phase {
+            KeyGenerationPhase::Acknowledgement
+        } else {
+            KeyGenerationPhase::Finished
+        }
+    }
+}
+
+/// Transport key of a trustee with the proof of knowledge of its secret. Shares dealt to
+/// the trustee are encrypted with Diffie-Hellman keys of transport keys.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::TransportKey", serde_pb_convert)]
+pub struct TransportKey {
+    pub key: EncryptionKey,
+    pub proof: DleqProof,
+}
+
+impl TransportKey {
+    pub fn create<R: RngCore + CryptoRng>(
+        election: &ElectionAddress,
+        trustee: &PublicKey,
+        secret: &crypto::Scalar,
+        rng: &mut R,
+    ) -> Self {
+        Self {
+            key: EncryptionKey::from_secret(secret),
+            proof: DleqProof::prove_key(&trustee_context(election, trustee), secret, rng),
+        }
+    }
+
+    pub fn verify(&self, election: &ElectionAddress, trustee: &PublicKey) -> bool {
+        self.proof
+            .verify_key(&trustee_context(election, trustee), &self.key)
+    }
+}
+
+/// Complaint of a trustee about invalid share dealt to it.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::Complaint", serde_pb_convert)]
+pub struct Complaint {
+    pub dealer: PublicKey,
+    /// Diffie-Hellman key of transport keys of the trustee and the dealer.
+    pub shared_key: SharedKey,
+}
+
+/// Derives secret of the transport key of the trustee for given election.
+///
+/// Secret is derived from the service secret key and shares dealt to the trustee are
+/// published encrypted, so node does not need to keep any additional state.
+pub fn transport_secret(service_key: &SecretKey, election: &ElectionAddress) -> crypto::Scalar {
+    crypto::derive_scalar(
+        b"crypto-election/transport-secret",
+        &service_key[..],
+        election.as_ref(),
+    )
+}
+
+/// Combines secret share of the trustee at `position` from dealings of qualified dealers.
+///
+/// Returns `None` if any of the shares is invalid; such trustee can not decrypt.
+pub fn secret_share<'a>(
+    election: &ElectionAddress,
+    position: usize,
+    transport_secret: &crypto::Scalar,
+    dealings: impl IntoIterator<Item = (&'a PublicKey, &'a TransportKey, &'a Dealing)>,
+) -> Option<crypto::Scalar> {
+    dealings
+        .into_iter()
+        .map(|(dealer, dealer_key, dealing)| {
+            dealing.open_share(
+                &trustee_context(election, dealer),
+                position,
+                &(transport_secret * dealer_key.key.0),
+            )
+        })
+        .sum()
+}
+
+/// Proof context for ballot of `voter` in `election`.
+pub fn ballot_context(election: &ElectionAddress, voter: &ParticipantAddress) -> Vec<u8> {
+    [b"ballot".as_ref(), election.as_ref(), voter.as_ref()].concat()
+}
+
+/// Proof context for messages of `trustee` in `election`.
+pub fn trustee_context(election: &ElectionAddress, trustee: &PublicKey) -> Vec<u8> {
+    [b"trustee".as_ref(), election.as_ref(), trustee.as_ref()].concat()
+}
+
+/// Encrypts selection of option with index `selected` for `voter`.
+pub fn encrypt_ballot<R: RngCore + CryptoRng>(
+    election: &ElectionAddress,
+    voter: &ParticipantAddress,
+    key: &EncryptionKey,
+    options_count: usize,
+    selected: usize,
+    rng: &mut R,
+) -> EncryptedBallot {
+    EncryptedBallot::encrypt(
+        &ballot_context(election, voter),
+        key,
+        options_count,
+        selected,
+        rng,
+    )
+}
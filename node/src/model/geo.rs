@@ -1,6 +1,10 @@
-use std::iter::FromIterator;
+//! Areas of administrations and locations of participants.
+//!
+//! Coordinates are integer micro-degrees of longitude (`x`) and latitude (`y`), and all
+//! predicates are evaluated with exact integer arithmetic, so every node resolves
+//! locations identically.
 
-use geo;
+use std::iter::FromIterator;
 
 use anyhow as failure;
 use exonum_derive::{BinaryValue, ObjectHash};
@@ -8,6 +12,13 @@ use exonum_proto::ProtobufConvert;
 
 use crate::proto;
 
+/// Area consisting of several polygons, such as district with islands or exclaves.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::MultiPolygon", serde_pb_convert)]
+pub struct MultiPolygon {
+    pub polygons: Vec<Polygon>,
+}
+
 #[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
 #[protobuf_convert(source = "proto::Polygon", serde_pb_convert)]
 pub struct Polygon {
@@ -21,94 +32,449 @@ pub struct LineString {
     pub items: Vec<Coordinate>,
 }
 
-#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+/// Longitude (`x`) and latitude (`y`) in micro-degrees.
+#[derive(Clone, Debug, PartialEq, Eq, ProtobufConvert, BinaryValue, ObjectHash)]
 #[protobuf_convert(source = "proto::Coordinate", serde_pb_convert)]
 pub struct Coordinate {
-    pub x: f64,
-    pub y: f64,
+    pub x: i64,
+    pub y: i64,
 }
 
-impl From<geo::Polygon<f64>> for Polygon {
-    fn from(polygon: geo::Polygon<f64>) -> Self {
-        let (exterior, interiors) = polygon.into_inner();
-        Polygon {
-            exterior: exterior.into(),
-            interiors: interiors.into_iter().map(Into::into).collect(),
+/// Number of coordinate units in one degree.
+pub const UNITS_PER_DEGREE: i64 = 1_000_000;
+
+const MAX_X: i64 = 180 * UNITS_PER_DEGREE;
+const MAX_Y: i64 = 90 * UNITS_PER_DEGREE;
+
+impl Coordinate {
+    /// Rounds coordinate given in degrees to the nearest micro-degree.
+    pub fn from_degrees(x: f64, y: f64) -> Self {
+        let units = UNITS_PER_DEGREE as f64;
+        Self {
+            x: (x * units).round() as i64,
+            y: (y * units).round() as i64,
         }
     }
+
+    pub fn to_degrees(&self) -> (f64, f64) {
+        let units = UNITS_PER_DEGREE as f64;
+        (self.x as f64 / units, self.y as f64 / units)
+    }
+
+    pub fn is_in_range(&self) -> bool {
+        self.x.abs() <= MAX_X && self.y.abs() <= MAX_Y
+    }
+
+    fn scaled(&self, scale: i128) -> Point {
+        (i128::from(self.x) * scale, i128::from(self.y) * scale)
+    }
 }
 
-impl From<Polygon> for geo::Polygon<f64> {
-    fn from(def: Polygon) -> Self {
-        Self::new(
-            def.exterior.into(),
-            def.interiors.into_iter().map(Into::into).collect(),
-        )
+/// Axis-aligned rectangle enclosing area.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::BoundingBox", serde_pb_convert)]
+pub struct BoundingBox {
+    pub min: Coordinate,
+    pub max: Coordinate,
+}
+
+impl BoundingBox {
+    /// Checks whether point lies within the box, including its edges.
+    pub fn contains(&self, point: &Coordinate) -> bool {
+        point.x >= self.min.x
+            && point.x <= self.max.x
+            && point.y >= self.min.y
+            && point.y <= self.max.y
+    }
+
+    /// Smallest box enclosing both boxes.
+    pub fn union(&self, other: &BoundingBox) -> BoundingBox {
+        BoundingBox {
+            min: Coordinate {
+                x: self.min.x.min(other.min.x),
+                y: self.min.y.min(other.min.y),
+            },
+            max: Coordinate {
+                x: self.max.x.max(other.max.x),
+                y: self.max.y.max(other.max.y),
+            },
+        }
     }
 }
 
-impl From<geo::LineString<f64>> for LineString {
-    fn from(l_str: geo::LineString<f64>) -> Self {
-        Self {
-            items: l_str.0.into_iter().map(Into::into).collect(),
+/// Location of point relative to ring or polygon.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointLocation {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+impl MultiPolygon {
+    /// Checks that there is at least one polygon, every polygon is valid and interiors of
+    /// polygons do not overlap.
+    pub fn is_valid(&self) -> bool {
+        !self.polygons.is_empty()
+            && self.polygons.iter().all(Polygon::is_valid)
+            && self.polygons.iter().enumerate().all(|(i, polygon)| {
+                self.polygons[i + 1..]
+                    .iter()
+                    .all(|other| !polygon.overlaps(other))
+            })
+    }
+
+    /// Bounding box of all polygons. There must be at least one polygon.
+    pub fn bounding_box(&self) -> BoundingBox {
+        let mut boxes = self.polygons.iter().map(Polygon::bounding_box);
+        let first = boxes.next().expect("Multi-polygon has no polygons");
+        boxes.fold(first, |bounds, other| bounds.union(&other))
+    }
+
+    /// Point is inside if it is inside of any polygon, even if it lies on boundary of
+    /// another one.
+    pub fn locate(&self, point: &Coordinate) -> PointLocation {
+        let locations: Vec<_> = self
+            .polygons
+            .iter()
+            .map(|polygon| polygon.locate(point))
+            .collect();
+        if locations.contains(&PointLocation::Inside) {
+            PointLocation::Inside
+        } else if locations.contains(&PointLocation::Boundary) {
+            PointLocation::Boundary
+        } else {
+            PointLocation::Outside
         }
     }
+
+    /// Checks that every polygon of `other` lies within one of the polygons.
+    pub fn contains_multi_polygon(&self, other: &MultiPolygon) -> bool {
+        other.polygons.iter().all(|polygon| {
+            self.polygons
+                .iter()
+                .any(|own| own.contains_polygon(polygon))
+        })
+    }
+
+    /// Checks whether interiors of any polygons intersect.
+    pub fn overlaps(&self, other: &MultiPolygon) -> bool {
+        self.polygons
+            .iter()
+            .any(|polygon| other.polygons.iter().any(|their| polygon.overlaps(their)))
+    }
 }
 
-impl From<LineString> for geo::LineString<f64> {
-    fn from(l_str: LineString) -> Self {
-        l_str.items.into_iter().collect()
+impl Polygon {
+    /// Checks that coordinates are in range, rings are closed and simple, do not touch
+    /// each other, exterior is counter-clockwise, interiors are clockwise and lie inside
+    /// exterior, and the area is not zero.
+    pub fn is_valid(&self) -> bool {
+        let rings: Vec<_> = self.rings().collect();
+        if !rings.iter().all(|ring| ring.is_simple_ring()) {
+            return false;
+        }
+        if self.exterior.doubled_signed_area() <= 0
+            || self
+                .interiors
+                .iter()
+                .any(|ring| ring.doubled_signed_area() >= 0)
+        {
+            return false;
+        }
+
+        for (i, ring) in rings.iter().enumerate() {
+            for other in &rings[i + 1..] {
+                let touches = ring
+                    .segments(1)
+                    .any(|a| other.segments(1).any(|b| segments_intersect(a, b)));
+                if touches {
+                    return false;
+                }
+            }
+        }
+
+        let holes_inside = self.interiors.iter().enumerate().all(|(i, hole)| {
+            let vertex = hole.items[0].scaled(1);
+            self.exterior.locate_scaled(vertex, 1) == PointLocation::Inside
+                && self.interiors.iter().enumerate().all(|(j, other)| {
+                    i == j || other.locate_scaled(vertex, 1) == PointLocation::Outside
+                })
+        });
+
+        holes_inside && self.doubled_area() > 0
+    }
+
+    /// Bounding box of exterior ring. Ring must not be empty.
+    pub fn bounding_box(&self) -> BoundingBox {
+        let first = &self.exterior.items[0];
+        self.exterior.items.iter().fold(
+            BoundingBox {
+                min: first.clone(),
+                max: first.clone(),
+            },
+            |bounds, point| BoundingBox {
+                min: Coordinate {
+                    x: bounds.min.x.min(point.x),
+                    y: bounds.min.y.min(point.y),
+                },
+                max: Coordinate {
+                    x: bounds.max.x.max(point.x),
+                    y: bounds.max.y.max(point.y),
+                },
+            },
+        )
+    }
+
+    pub fn locate(&self, point: &Coordinate) -> PointLocation {
+        self.locate_scaled(point.scaled(1), 1)
+    }
+
+    /// Checks that `other` lies within the polygon, boundaries may touch.
+    pub fn contains_polygon(&self, other: &Polygon) -> bool {
+        let outside = other
+            .exterior
+            .probe_points()
+            .any(|point| self.locate_scaled(point, 2) == PointLocation::Outside);
+        if outside || self.crosses(other) {
+            return false;
+        }
+        // Holes of the polygon must not be covered by `other`.
+        self.interiors.iter().all(|hole| {
+            hole.probe_points()
+                .all(|point| other.locate_scaled(point, 2) != PointLocation::Inside)
+        })
+    }
+
+    /// Checks whether interiors of polygons intersect. Touching boundaries do not overlap.
+    pub fn overlaps(&self, other: &Polygon) -> bool {
+        if self.crosses(other) || self.shares_edge_side(other) {
+            return true;
+        }
+        other
+            .exterior
+            .probe_points()
+            .any(|point| self.locate_scaled(point, 2) == PointLocation::Inside)
+            || self
+                .exterior
+                .probe_points()
+                .any(|point| other.locate_scaled(point, 2) == PointLocation::Inside)
+    }
+
+    pub(crate) fn rings(&self) -> impl Iterator<Item = &LineString> {
+        std::iter::once(&self.exterior).chain(&self.interiors)
+    }
+
+    /// Area of polygon excluding holes, doubled.
+    fn doubled_area(&self) -> i128 {
+        self.exterior.doubled_signed_area().abs()
+            - self
+                .interiors
+                .iter()
+                .map(|ring| ring.doubled_signed_area().abs())
+                .sum::<i128>()
+    }
+
+    /// Locates point with coordinates multiplied by `scale`.
+    fn locate_scaled(&self, point: Point, scale: i128) -> PointLocation {
+        match self.exterior.locate_scaled(point, scale) {
+            PointLocation::Inside => {}
+            location => return location,
+        }
+        for hole in &self.interiors {
+            match hole.locate_scaled(point, scale) {
+                PointLocation::Outside => {}
+                PointLocation::Inside => return PointLocation::Outside,
+                PointLocation::Boundary => return PointLocation::Boundary,
+            }
+        }
+        PointLocation::Inside
+    }
+
+    /// Checks whether some edges of polygons properly cross each other.
+    fn crosses(&self, other: &Polygon) -> bool {
+        self.rings().any(|ring| {
+            other.rings().any(|other_ring| {
+                ring.segments(1)
+                    .any(|a| other_ring.segments(1).any(|b| segments_cross(a, b)))
+            })
+        })
+    }
+
+    /// Checks whether polygons have common piece of boundary with both interiors on the
+    /// same side of it. Interior of valid polygon lies to the left of every ring edge.
+    fn shares_edge_side(&self, other: &Polygon) -> bool {
+        self.rings().any(|ring| {
+            other.rings().any(|other_ring| {
+                ring.segments(1).any(|a| {
+                    other_ring
+                        .segments(1)
+                        .any(|b| segments_overlap_in_same_direction(a, b))
+                })
+            })
+        })
     }
 }
 
-impl<IC: Into<Coordinate>> From<Vec<IC>> for LineString {
-    fn from(line_string: Vec<IC>) -> Self {
-        Self {
-            items: line_string.into_iter().map(Into::into).collect(),
+impl LineString {
+    fn segments(&self, scale: i128) -> impl Iterator<Item = Segment> + '_ {
+        self.items
+            .windows(2)
+            .map(move |pair| (pair[0].scaled(scale), pair[1].scaled(scale)))
+    }
+
+    /// Vertices and midpoints of edges, with coordinates doubled to keep them integer.
+    fn probe_points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.segments(1)
+            .flat_map(|(a, b)| vec![(a.0 * 2, a.1 * 2), (a.0 + b.0, a.1 + b.1)])
+    }
+
+    /// Checks that ring is closed, has at least three distinct vertices, coordinates in
+    /// range and does not intersect itself.
+    fn is_simple_ring(&self) -> bool {
+        let items = &self.items;
+        if items.len() < 4
+            || !items.iter().all(Coordinate::is_in_range)
+            || items[0] != items[items.len() - 1]
+        {
+            return false;
+        }
+
+        let segments: Vec<_> = self.segments(1).collect();
+        let count = segments.len();
+        for (i, &(a, b)) in segments.iter().enumerate() {
+            if a == b {
+                return false;
+            }
+            // Adjacent edges share vertex only and must not turn back.
+            let (_, c) = segments[(i + 1) % count];
+            let backward = (b.0 - a.0) * (c.0 - b.0) + (b.1 - a.1) * (c.1 - b.1) < 0;
+            if orientation(a, b, c) == 0 && backward {
+                return false;
+            }
+            for (j, &other) in segments.iter().enumerate().skip(i + 2) {
+                if i == 0 && j == count - 1 {
+                    continue;
+                }
+                if segments_intersect((a, b), other) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Signed area of closed ring, doubled. Positive for counter-clockwise rings.
+    fn doubled_signed_area(&self) -> i128 {
+        self.segments(1).map(|(a, b)| a.0 * b.1 - b.0 * a.1).sum()
+    }
+
+    /// Locates point relative to ring which coordinates are multiplied by `scale`.
+    fn locate_scaled(&self, point: Point, scale: i128) -> PointLocation {
+        if self
+            .segments(scale)
+            .any(|(a, b)| orientation(a, b, point) == 0 && is_between(a, b, point))
+        {
+            return PointLocation::Boundary;
+        }
+
+        // Counts edges crossing the ray from the point in the direction of increasing `x`.
+        let mut is_inside = false;
+        for (a, b) in self.segments(scale) {
+            if (a.1 > point.1) != (b.1 > point.1) {
+                let side = orientation(a, b, point);
+                if (b.1 > a.1 && side > 0) || (b.1 < a.1 && side < 0) {
+                    is_inside = !is_inside;
+                }
+            }
+        }
+        if is_inside {
+            PointLocation::Inside
+        } else {
+            PointLocation::Outside
         }
     }
 }
 
-impl<IC: Into<Coordinate>> FromIterator<IC> for LineString {
-    fn from_iter<I: IntoIterator<Item = IC>>(iter: I) -> Self {
+type Point = (i128, i128);
+type Segment = (Point, Point);
+
+/// Positive if `c` lies to the left of line `a`-`b`, negative if to the right.
+fn orientation(a: Point, b: Point, c: Point) -> i128 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+/// Checks whether point `c` collinear with `a`-`b` lies on the segment.
+fn is_between(a: Point, b: Point, c: Point) -> bool {
+    c.0 >= a.0.min(b.0) && c.0 <= a.0.max(b.0) && c.1 >= a.1.min(b.1) && c.1 <= a.1.max(b.1)
+}
+
+/// Checks whether segments have common points, including touching ends.
+fn segments_intersect((a, b): Segment, (c, d): Segment) -> bool {
+    let (o1, o2) = (orientation(a, b, c).signum(), orientation(a, b, d).signum());
+    let (o3, o4) = (orientation(c, d, a).signum(), orientation(c, d, b).signum());
+
+    if o1 * o2 < 0 && o3 * o4 < 0 {
+        return true;
+    }
+    (o1 == 0 && is_between(a, b, c))
+        || (o2 == 0 && is_between(a, b, d))
+        || (o3 == 0 && is_between(c, d, a))
+        || (o4 == 0 && is_between(c, d, b))
+}
+
+/// Checks whether segments cross at a single point inside both of them.
+fn segments_cross((a, b): Segment, (c, d): Segment) -> bool {
+    orientation(a, b, c).signum() * orientation(a, b, d).signum() < 0
+        && orientation(c, d, a).signum() * orientation(c, d, b).signum() < 0
+}
+
+/// Checks whether collinear segments have common piece of positive length and point in
+/// the same direction.
+fn segments_overlap_in_same_direction((a, b): Segment, (c, d): Segment) -> bool {
+    if orientation(a, b, c) != 0 || orientation(a, b, d) != 0 {
+        return false;
+    }
+    let direction = (b.0 - a.0, b.1 - a.1);
+    let project = |p: Point| (p.0 - a.0) * direction.0 + (p.1 - a.1) * direction.1;
+    let (tc, td) = (project(c), project(d));
+    let length = project(b);
+    td > tc && tc.max(0) < td.min(length)
+}
+
+impl From<Polygon> for MultiPolygon {
+    fn from(polygon: Polygon) -> Self {
         Self {
-            items: iter.into_iter().map(Into::into).collect(),
+            polygons: vec![polygon],
         }
     }
 }
 
-impl From<geo::Coordinate<f64>> for Coordinate {
-    fn from(coord: geo::Coordinate<f64>) -> Self {
+impl<IC: Into<Coordinate>> From<Vec<IC>> for LineString {
+    fn from(line_string: Vec<IC>) -> Self {
         Self {
-            x: coord.x,
-            y: coord.y,
+            items: line_string.into_iter().map(Into::into).collect(),
         }
     }
 }
 
-impl From<Coordinate> for geo::Coordinate<f64> {
-    fn from(coord: Coordinate) -> Self {
+impl<IC: Into<Coordinate>> FromIterator<IC> for LineString {
+    fn from_iter<I: IntoIterator<Item = IC>>(iter: I) -> Self {
         Self {
-            x: coord.x,
-            y: coord.y,
+            items: iter.into_iter().map(Into::into).collect(),
         }
     }
 }
 
+/// Converts coordinate given in degrees, see `Coordinate::from_degrees`.
 impl From<[f64; 2]> for Coordinate {
     fn from(coordinate: [f64; 2]) -> Self {
-        Coordinate {
-            x: coordinate[0],
-            y: coordinate[1],
-        }
+        Coordinate::from_degrees(coordinate[0], coordinate[1])
     }
 }
 
+/// Converts coordinate given in degrees, see `Coordinate::from_degrees`.
 impl From<(f64, f64)> for Coordinate {
     fn from(coordinate: (f64, f64)) -> Self {
-        Coordinate {
-            x: coordinate.0,
-            y: coordinate.1,
-        }
+        Coordinate::from_degrees(coordinate.0, coordinate.1)
     }
 }
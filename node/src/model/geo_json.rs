@@ -0,0 +1,108 @@
+//! Conversion of administration areas to and from GeoJSON geometries.
+//!
+//! Positions are longitude and latitude in degrees, rounded to micro-degrees on import.
+//! Area of one polygon is exported as GeoJSON Polygon, area of several polygons as
+//! MultiPolygon. Ring orientation of GeoJSON (counter-clockwise exterior, clockwise
+//! holes) is the one required by `Polygon::is_valid`, so rings are kept as is.
+
+use std::convert::TryFrom;
+
+use geojson::{Geometry, PolygonType, Position, Value};
+
+use super::geo::{Coordinate, LineString, MultiPolygon, Polygon};
+
+/// Reason of GeoJSON geometry rejection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Fail)]
+pub enum GeoJsonError {
+    #[fail(display = "Geometry is neither Polygon nor MultiPolygon")]
+    UnsupportedGeometry,
+    #[fail(display = "MultiPolygon must contain exactly one polygon")]
+    MultiplePolygons,
+    #[fail(display = "Polygon has no exterior ring")]
+    MissingExterior,
+    #[fail(display = "Position must contain finite longitude and latitude")]
+    InvalidPosition,
+}
+
+impl From<&Polygon> for Geometry {
+    fn from(polygon: &Polygon) -> Self {
+        Geometry::new(Value::Polygon(polygon_to_rings(polygon)))
+    }
+}
+
+impl TryFrom<&Geometry> for Polygon {
+    type Error = GeoJsonError;
+
+    fn try_from(geometry: &Geometry) -> Result<Self, Self::Error> {
+        match &geometry.value {
+            Value::Polygon(rings) => polygon_from_rings(rings),
+            Value::MultiPolygon(polygons) => match polygons.as_slice() {
+                [rings] => polygon_from_rings(rings),
+                _ => Err(GeoJsonError::MultiplePolygons),
+            },
+            _ => Err(GeoJsonError::UnsupportedGeometry),
+        }
+    }
+}
+
+impl From<&MultiPolygon> for Geometry {
+    fn from(area: &MultiPolygon) -> Self {
+        match area.polygons.as_slice() {
+            [polygon] => Geometry::from(polygon),
+            polygons => Geometry::new(Value::MultiPolygon(
+                polygons.iter().map(polygon_to_rings).collect(),
+            )),
+        }
+    }
+}
+
+impl TryFrom<&Geometry> for MultiPolygon {
+    type Error = GeoJsonError;
+
+    fn try_from(geometry: &Geometry) -> Result<Self, Self::Error> {
+        let polygons = match &geometry.value {
+            Value::Polygon(rings) => vec![polygon_from_rings(rings)?],
+            Value::MultiPolygon(polygons) => polygons
+                .iter()
+                .map(|rings| polygon_from_rings(rings))
+                .collect::<Result<_, _>>()?,
+            _ => return Err(GeoJsonError::UnsupportedGeometry),
+        };
+        Ok(MultiPolygon { polygons })
+    }
+}
+
+fn polygon_to_rings(polygon: &Polygon) -> PolygonType {
+    polygon
+        .rings()
+        .map(|ring| ring.items.iter().map(coordinate_to_position).collect())
+        .collect()
+}
+
+fn polygon_from_rings(rings: &[Vec<Position>]) -> Result<Polygon, GeoJsonError> {
+    let (exterior, interiors) = rings.split_first().ok_or(GeoJsonError::MissingExterior)?;
+    Ok(Polygon {
+        exterior: ring_from_positions(exterior)?,
+        interiors: interiors
+            .iter()
+            .map(|ring| ring_from_positions(ring))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn ring_from_positions(positions: &[Position]) -> Result<LineString, GeoJsonError> {
+    let items = positions
+        .iter()
+        .map(|position| match position.as_slice() {
+            // Altitude, if any, is ignored.
+            [x, y, ..] if x.is_finite() && y.is_finite() => Ok(Coordinate::from_degrees(*x, *y)),
+            _ => Err(GeoJsonError::InvalidPosition),
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(LineString { items })
+}
+
+fn coordinate_to_position(coordinate: &Coordinate) -> Position {
+    let (x, y) = coordinate.to_degrees();
+    vec![x, y]
+}
@@ -0,0 +1,76 @@
+//! One-time registration invitations.
+//!
+//! Administration issues public keys derived from random codes, for example printed on
+//! letters to residents. Participant redeems a code by signing its own address with the
+//! derived key, so the code never appears on-chain and the redemption can not be replayed
+//! for another key. Participant registered with a code resides in the issuing administration
+//! and is verified as soon as a validator attests that its pass code is unique.
+
+use chrono::{DateTime, Utc};
+use exonum::crypto::{self, Hash, KeyPair, Seed};
+
+use anyhow as failure;
+use exonum_derive::{BinaryValue, ObjectHash};
+use exonum_proto::ProtobufConvert;
+
+use super::{
+    approval::CounterSignature, wrappers::OptionalContainer, AdministrationAddress,
+    ParticipantAddress,
+};
+use crate::proto;
+
+const CODE_DOMAIN: &[u8] = b"crypto-election/invitation-code";
+const REDEMPTION_DOMAIN: &[u8] = b"crypto-election/invitation-redemption";
+
+/// Maximal number of invitations issued by one transaction.
+pub const MAX_INVITATIONS_PER_TX: usize = 1000;
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::Invitation", serde_pb_convert)]
+pub struct Invitation {
+    pub issuer: AdministrationAddress,
+    pub expires_at: DateTime<Utc>,
+    /// Participant registered with the invitation.
+    pub used_by: OptionalContainer<ParticipantAddress>,
+}
+
+impl Invitation {
+    pub fn is_used(&self) -> bool {
+        self.used_by.0.is_some()
+    }
+
+    pub fn is_expired(&self, moment: DateTime<Utc>) -> bool {
+        self.expires_at <= moment
+    }
+}
+
+/// Derives keys of invitation code issued by administration `issuer`. Code is salted with
+/// the issuer address, so keys of short codes are not matched against a single table.
+pub fn invitation_keys(issuer: &AdministrationAddress, code: &str) -> KeyPair {
+    let mut buffer = CODE_DOMAIN.to_vec();
+    buffer.extend_from_slice(issuer.as_ref());
+    buffer.extend_from_slice(code.as_bytes());
+    let seed = crypto::hash(&buffer);
+    KeyPair::from_seed(&Seed::from_slice(seed.as_ref()).expect("Hash is a valid seed"))
+}
+
+/// Calculates message which is signed by invitation key to register `participant`.
+pub fn redemption_message(participant: &ParticipantAddress) -> Hash {
+    let mut buffer = REDEMPTION_DOMAIN.to_vec();
+    buffer.extend_from_slice(participant.as_ref());
+    crypto::hash(&buffer)
+}
+
+/// Redeems invitation code issued by `issuer` for registration of `participant`.
+pub fn redeem(
+    issuer: &AdministrationAddress,
+    code: &str,
+    participant: &ParticipantAddress,
+) -> CounterSignature {
+    let keys = invitation_keys(issuer, code);
+    let message = redemption_message(participant);
+    CounterSignature {
+        key: keys.public_key(),
+        signature: crypto::sign(message.as_ref(), keys.secret_key()),
+    }
+}
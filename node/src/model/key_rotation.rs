@@ -0,0 +1,28 @@
+//! Rotation of keys identifying participants and administrations.
+//!
+//! Rotation transaction is signed by the old key as usual and carries signature of the
+//! new key over the rotation message, so the new key holder agrees to take over.
+
+use exonum::{
+    crypto::{self, Hash, KeyPair, PublicKey, Signature},
+    runtime::CallerAddress as Address,
+};
+
+const ROTATION_DOMAIN: &[u8] = b"crypto-election/key-rotation";
+
+/// Calculates message signed by the new key to take over identity of `old` address.
+pub fn rotation_message(old: &Address, new_key: &PublicKey) -> Hash {
+    crypto::hash(&[ROTATION_DOMAIN, old.as_ref(), new_key.as_ref()].concat())
+}
+
+/// Signs rotation from `old` address with the new key pair.
+pub fn sign_rotation(old: &Address, new_keys: &KeyPair) -> Signature {
+    let message = rotation_message(old, &new_keys.public_key());
+    crypto::sign(message.as_ref(), new_keys.secret_key())
+}
+
+/// Checks signature of the new key over rotation from `old` address.
+pub fn verify_rotation(old: &Address, new_key: &PublicKey, signature: &Signature) -> bool {
+    let message = rotation_message(old, new_key);
+    crypto::verify(signature, message.as_ref(), new_key)
+}
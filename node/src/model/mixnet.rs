@@ -0,0 +1,159 @@
+//! Data models of `BallotMode::Mixnet` elections.
+//!
+//! Ballot is a fixed-length list of small values, each encrypted with joint key of
+//! trustees. After election finish every trustee in turn shuffles the ballots, and the
+//! output of the last shuffle is decrypted ballot by ballot. Trustee which does not
+//! shuffle in `mix_step_timeout()` is skipped. Meaning of values is up to the election:
+//! ranked ballots list option ids, free-text ballots hold encoded text.
+
+use std::borrow::Cow;
+
+use chrono::{DateTime, Duration, Utc};
+use exonum::crypto::PublicKey;
+use exonum_merkledb::BinaryValue;
+use rand::{CryptoRng, RngCore};
+
+use anyhow as failure;
+use exonum_derive::{BinaryValue, ObjectHash};
+use exonum_proto::ProtobufConvert;
+
+use super::{encryption, ElectionAddress, ElectionOptionAddress, ParticipantAddress};
+use crate::{
+    crypto::{
+        elgamal::EncryptionKey,
+        mixnet::{BallotList, MixnetBallot, ShuffleProof},
+    },
+    proto,
+};
+
+impl_bytes_convert!(MixnetBallot, BallotList, ShuffleProof);
+
+/// Maximal number of values in ballot.
+pub const MAX_BALLOT_LENGTH: u32 = 64;
+
+/// Maximal value which can be decrypted.
+pub const MAX_BALLOT_VALUE: u64 = 1 << 16;
+
+/// Decrypted value of ciphertext which does not encrypt value in `[0, MAX_BALLOT_VALUE]`.
+pub const INVALID_VALUE: u32 = u32::max_value();
+
+/// Time given to every trustee to publish its shuffle.
+pub fn mix_step_timeout() -> Duration {
+    Duration::minutes(10)
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::ElectionMixing", serde_pb_convert)]
+pub struct ElectionMixing {
+    /// Number of passed shuffle steps. Step `i` is made or skipped by trustee `i` of
+    /// election.
+    pub mix_steps: u32,
+    /// Values of decrypted ballots, ballot by ballot. Valid when election encryption
+    /// is decrypted.
+    pub results: Vec<u32>,
+    /// Time after which the current mixer is skipped.
+    pub step_deadline: DateTime<Utc>,
+    /// Trustees skipped for not publishing their shuffles in time.
+    pub skipped: Vec<PublicKey>,
+}
+
+impl ElectionMixing {
+    pub fn new(step_deadline: DateTime<Utc>) -> Self {
+        Self {
+            mix_steps: 0,
+            results: Vec::new(),
+            step_deadline,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Checks whether every trustee has shuffled or been skipped.
+    pub fn is_finished(&self, trustees_count: usize) -> bool {
+        self.mix_steps as usize >= trustees_count
+    }
+
+    /// Checks whether ballots can be decrypted: mixing is finished and at least one
+    /// shuffle is published, so decrypted ballots are not linked to voters.
+    pub fn is_mixed(&self, trustees_count: usize) -> bool {
+        self.is_finished(trustees_count) && self.skipped.len() < self.mix_steps as usize
+    }
+}
+
+/// Encrypts ballot `values` of `voter`.
+pub fn encrypt_ballot<R: RngCore + CryptoRng>(
+    election: &ElectionAddress,
+    voter: &ParticipantAddress,
+    key: &EncryptionKey,
+    values: &[u64],
+    rng: &mut R,
+) -> MixnetBallot {
+    MixnetBallot::encrypt(
+        &encryption::ballot_context(election, voter),
+        key,
+        values,
+        rng,
+    )
+}
+
+/// Shuffles ballots on behalf of `mixer` and proves the shuffle.
+pub fn shuffle_ballots<R: RngCore + CryptoRng>(
+    election: &ElectionAddress,
+    mixer: &PublicKey,
+    key: &EncryptionKey,
+    input: &BallotList,
+    rng: &mut R,
+) -> (BallotList, ShuffleProof) {
+    let (output, opening) = input.random_shuffle(key, rng);
+    let proof = ShuffleProof::prove(
+        &encryption::trustee_context(election, mixer),
+        key,
+        input,
+        &output,
+        &opening,
+        rng,
+    );
+    (output, proof)
+}
+
+/// Encodes ranking of options, most preferred first, padded with zeros to `length`.
+pub fn encode_ranking(ranking: &[ElectionOptionAddress], length: usize) -> Option<Vec<u64>> {
+    if ranking.len() > length || ranking.iter().any(|&option| option <= 0) {
+        return None;
+    }
+    let mut values: Vec<u64> = ranking.iter().map(|&option| option as u64).collect();
+    values.resize(length, 0);
+    Some(values)
+}
+
+/// Encodes text as two bytes per value, padded with zeros to `length`.
+pub fn encode_text(text: &str, length: usize) -> Option<Vec<u64>> {
+    let bytes = text.as_bytes();
+    if bytes.len() > length * 2 {
+        return None;
+    }
+    let mut values: Vec<u64> = bytes
+        .chunks(2)
+        .map(|chunk| {
+            let low = u64::from(chunk[0]);
+            let high = chunk.get(1).map_or(0, |&byte| u64::from(byte));
+            (high << 8 | low) + 1
+        })
+        .collect();
+    values.resize(length, 0);
+    Some(values)
+}
+
+/// Decodes text made with `encode_text`.
+pub fn decode_text(values: &[u32]) -> String {
+    let bytes: Vec<u8> = values
+        .iter()
+        .filter(|&&value| value != 0 && u64::from(value) <= MAX_BALLOT_VALUE)
+        .flat_map(|&value| {
+            let chunk = value - 1;
+            vec![chunk as u8, (chunk >> 8) as u8]
+        })
+        .collect();
+    String::from_utf8_lossy(&bytes)
+        .trim_end_matches('\0')
+        .to_owned()
+}
@@ -6,19 +6,52 @@ pub mod public_api;
 
 pub mod geo;
 
+pub mod geo_json;
+
 pub mod wrappers;
 
+pub mod eligibility;
+
+#[macro_use]
+pub mod encryption;
+
+pub mod voting_token;
+
+pub mod ring_ballot;
+
+pub mod mixnet;
+
+pub mod pii;
+
+pub mod invitation;
+
+pub mod contact;
+
+pub mod key_rotation;
+
+pub mod recovery;
+
+pub mod multisig;
+
+pub mod approval;
+
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 
-use exonum::{crypto::Hash, runtime::CallerAddress as Address};
+use exonum::{
+    crypto::{self, Hash},
+    runtime::CallerAddress as Address,
+};
 
 use anyhow as failure;
 use exonum_derive::{BinaryValue, ObjectHash};
 use exonum_proto::ProtobufConvert;
 
+use eligibility::{EligibilityRule, EligibilityTerm};
 use wrappers::OptionalContainer;
 
-use crate::proto;
+use crate::{crypto::blind::VerificationKey, proto};
 
 pub type ParticipantAddress = Address;
 
@@ -29,12 +62,15 @@ pub struct Participant {
     pub addr: ParticipantAddress,
     /// Name of participant.
     pub name: String,
-    /// Email of participant.
-    pub email: String,
-    /// Personal phone number of participant.
-    pub phone_number: String,
-    /// Pass code of participant.
-    pub pass_code: String,
+    /// Salted commitment to email of participant.
+    pub email_commitment: Hash,
+    /// Salted commitment to personal phone number of participant.
+    pub phone_number_commitment: Hash,
+    /// Salted commitment to pass code of participant.
+    pub pass_code_commitment: Hash,
+    /// Keyed hash of normalised pass code, unique among participants. Attested by
+    /// validator holding the pass code, zero until then.
+    pub pass_code_fingerprint: Hash,
     /// `Administration` pub_key, where participant is resident.
     /// *Optional*.
     pub residence: OptionalContainer<AdministrationAddress>,
@@ -42,6 +78,88 @@ pub struct Participant {
     pub history_len: u64,
     /// `Hash` of the transaction history.
     pub history_hash: Hash,
+    /// Attributes assigned to participant by administrations.
+    pub attributes: Vec<ParticipantAttribute>,
+    /// Verification state of participant.
+    pub status: ParticipantStatus,
+    /// Whether participant has confirmed receiving code sent to the email.
+    pub is_email_verified: bool,
+    /// Whether participant has confirmed receiving code sent to the phone number.
+    pub is_phone_number_verified: bool,
+}
+
+/// Verification state of participant, changed by residence administration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParticipantStatus {
+    /// Participant is registered but not verified yet.
+    Pending,
+    /// Pass code of participant is checked, participant can vote.
+    Verified,
+    /// Participant can not vote until verified again.
+    Suspended,
+}
+
+impl ParticipantStatus {
+    /// Checks whether participant can be moved from this state to `next`.
+    pub fn can_change_to(self, next: Self) -> bool {
+        match (self, next) {
+            (Self::Pending, Self::Verified)
+            | (Self::Verified, Self::Suspended)
+            | (Self::Suspended, Self::Verified) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Default for ParticipantStatus {
+    fn default() -> Self {
+        Self::Pending
+    }
+}
+
+impl ProtobufConvert for ParticipantStatus {
+    type ProtoStruct = proto::ParticipantStatus;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        match self {
+            Self::Pending => proto::ParticipantStatus::PENDING,
+            Self::Verified => proto::ParticipantStatus::VERIFIED,
+            Self::Suspended => proto::ParticipantStatus::SUSPENDED,
+        }
+    }
+
+    fn from_pb(pb: Self::ProtoStruct) -> anyhow::Result<Self> {
+        Ok(match pb {
+            proto::ParticipantStatus::PENDING => Self::Pending,
+            proto::ParticipantStatus::VERIFIED => Self::Verified,
+            proto::ParticipantStatus::SUSPENDED => Self::Suspended,
+        })
+    }
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::ParticipantAttribute", serde_pb_convert)]
+pub struct ParticipantAttribute {
+    pub key: String,
+    pub value: String,
+    /// `Administration` which has assigned the attribute.
+    pub issuer: AdministrationAddress,
+}
+
+/// Location of participant submitted at specific moment.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::LocationRecord", serde_pb_convert)]
+pub struct LocationRecord {
+    pub date: DateTime<Utc>,
+    /// Administrations containing the location, from the most specific one to the root.
+    pub administrations: Vec<AdministrationAddress>,
+}
+
+impl LocationRecord {
+    /// The most specific administration containing the location.
+    pub fn administration(&self) -> AdministrationAddress {
+        self.administrations[0]
+    }
 }
 
 pub type AdministrationAddress = Address;
@@ -53,7 +171,7 @@ pub struct Administration {
     pub addr: AdministrationAddress,
     pub name: String,
     pub principal_key: OptionalContainer<AdministrationAddress>,
-    pub area: geo::Polygon,
+    pub area: geo::MultiPolygon,
     pub administration_level: u32,
     pub history_len: u64,
     pub history_hash: Hash,
@@ -73,6 +191,110 @@ pub struct Election {
     pub options: Vec<ElectionOption>,
     pub history_len: u64,
     pub history_hash: Hash,
+    /// Eligibility expression in prefix notation. Empty expression allows everyone.
+    pub eligibility: Vec<EligibilityTerm>,
+    pub ballot_mode: BallotMode,
+    /// End of reveal window. Used by `BallotMode::CommitReveal` only.
+    pub reveal_finish_date: DateTime<Utc>,
+    /// Key verifying voting tokens. Used by `BallotMode::Anonymous` only.
+    pub token_key: OptionalContainer<VerificationKey>,
+    /// Number of values in ballot. Used by `BallotMode::Mixnet` only.
+    pub ballot_length: u32,
+    pub results_visibility: ResultsVisibility,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BallotMode {
+    /// Votes are stored in clear.
+    Open,
+    /// Participants commit to `hash(option || salt)` during election and reveal
+    /// it after election finish.
+    CommitReveal,
+    /// Ballots are encrypted with joint key of trustees, only the sum is decrypted.
+    Encrypted,
+    /// Ballots are cast from unlinkable keys holding voting tokens blind-signed by issuer.
+    Anonymous,
+    /// Ballots are signed with linkable ring signature over keys of the frozen electorate.
+    Ring,
+    /// Ballots are encrypted value by value, shuffled by trustees and decrypted one by one.
+    Mixnet,
+}
+
+impl Default for BallotMode {
+    fn default() -> Self {
+        Self::Open
+    }
+}
+
+impl ProtobufConvert for BallotMode {
+    type ProtoStruct = proto::BallotMode;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        match self {
+            Self::Open => proto::BallotMode::OPEN,
+            Self::CommitReveal => proto::BallotMode::COMMIT_REVEAL,
+            Self::Encrypted => proto::BallotMode::ENCRYPTED,
+            Self::Anonymous => proto::BallotMode::ANONYMOUS,
+            Self::Ring => proto::BallotMode::RING,
+            Self::Mixnet => proto::BallotMode::MIXNET,
+        }
+    }
+
+    fn from_pb(pb: Self::ProtoStruct) -> anyhow::Result<Self> {
+        Ok(match pb {
+            proto::BallotMode::OPEN => Self::Open,
+            proto::BallotMode::COMMIT_REVEAL => Self::CommitReveal,
+            proto::BallotMode::ENCRYPTED => Self::Encrypted,
+            proto::BallotMode::ANONYMOUS => Self::Anonymous,
+            proto::BallotMode::RING => Self::Ring,
+            proto::BallotMode::MIXNET => Self::Mixnet,
+        })
+    }
+}
+
+/// Moment from which vote counts of election are visible through the API.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResultsVisibility {
+    /// Counts are visible while election is running.
+    Live,
+    /// Counts are hidden until election finish.
+    AfterFinish,
+    /// Counts are hidden until no more ballots can be counted.
+    AfterFinalisation,
+}
+
+impl Default for ResultsVisibility {
+    fn default() -> Self {
+        Self::Live
+    }
+}
+
+impl ProtobufConvert for ResultsVisibility {
+    type ProtoStruct = proto::ResultsVisibility;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        match self {
+            Self::Live => proto::ResultsVisibility::LIVE,
+            Self::AfterFinish => proto::ResultsVisibility::AFTER_FINISH,
+            Self::AfterFinalisation => proto::ResultsVisibility::AFTER_FINALISATION,
+        }
+    }
+
+    fn from_pb(pb: Self::ProtoStruct) -> anyhow::Result<Self> {
+        Ok(match pb {
+            proto::ResultsVisibility::LIVE => Self::Live,
+            proto::ResultsVisibility::AFTER_FINISH => Self::AfterFinish,
+            proto::ResultsVisibility::AFTER_FINALISATION => Self::AfterFinalisation,
+        })
+    }
+}
+
+/// Calculates commitment to selected option, used by `BallotMode::CommitReveal`.
+pub fn ballot_commitment(option_id: ElectionOptionAddress, salt: &Hash) -> Hash {
+    let mut buffer = Vec::with_capacity(4 + salt.as_ref().len());
+    buffer.extend_from_slice(&option_id.to_le_bytes());
+    buffer.extend_from_slice(salt.as_ref());
+    crypto::hash(&buffer)
 }
 
 pub type ElectionOptionAddress = i32;
@@ -84,6 +306,53 @@ pub struct ElectionOption {
     pub title: String,
 }
 
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::OptionVotes", serde_pb_convert)]
+pub struct OptionVotes {
+    pub option_id: ElectionOptionAddress,
+    pub votes_count: u32,
+}
+
+/// Ballots counted for every option of election.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::ElectionTally", serde_pb_convert)]
+pub struct ElectionTally {
+    pub options: Vec<OptionVotes>,
+}
+
+impl ElectionTally {
+    pub fn new(options: &[ElectionOption]) -> Self {
+        Self::from_counts(options, std::iter::repeat(0))
+    }
+
+    pub fn from_counts(options: &[ElectionOption], counts: impl IntoIterator<Item = u32>) -> Self {
+        Self {
+            options: options
+                .iter()
+                .zip(counts)
+                .map(|(option, votes_count)| OptionVotes {
+                    option_id: option.id,
+                    votes_count,
+                })
+                .collect(),
+        }
+    }
+
+    /// Counts ballot for option. Unknown options are ignored.
+    pub fn count(&mut self, option_id: ElectionOptionAddress) {
+        if let Some(option) = self.options.iter_mut().find(|o| o.option_id == option_id) {
+            option.votes_count += 1;
+        }
+    }
+
+    pub fn to_map(&self) -> HashMap<ElectionOptionAddress, u32> {
+        self.options
+            .iter()
+            .map(|o| (o.option_id, o.votes_count))
+            .collect()
+    }
+}
+
 impl Participant {
     /// Create a new `Participant`.
     pub fn from_transaction(
@@ -95,12 +364,62 @@ impl Participant {
         Self {
             addr,
             name: transaction.name,
-            email: transaction.email,
-            phone_number: transaction.phone_number,
-            pass_code: transaction.pass_code,
+            email_commitment: transaction.email_commitment,
+            phone_number_commitment: transaction.phone_number_commitment,
+            pass_code_commitment: transaction.pass_code_commitment,
+            pass_code_fingerprint: Hash::zero(),
             residence: transaction.residence,
             history_len,
             history_hash: *history_hash,
+            attributes: Vec::new(),
+            status: ParticipantStatus::Pending,
+            is_email_verified: false,
+            is_phone_number_verified: false,
+        }
+    }
+
+    /// Marks contact as verified. Other fields are ignored.
+    pub fn set_contact_verified(&mut self, field: pii::PiiField) {
+        match field {
+            pii::PiiField::Email => self.is_email_verified = true,
+            pii::PiiField::PhoneNumber => self.is_phone_number_verified = true,
+            pii::PiiField::PassCode => {}
+        }
+    }
+
+    pub fn is_verified(&self) -> bool {
+        self.status == ParticipantStatus::Verified
+    }
+
+    pub fn is_pass_code_attested(&self) -> bool {
+        self.pass_code_fingerprint != Hash::zero()
+    }
+
+    pub fn pii_commitment(&self, field: pii::PiiField) -> &Hash {
+        match field {
+            pii::PiiField::Email => &self.email_commitment,
+            pii::PiiField::PhoneNumber => &self.phone_number_commitment,
+            pii::PiiField::PassCode => &self.pass_code_commitment,
+        }
+    }
+
+    pub fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.key == key)
+            .map(|attribute| attribute.value.as_str())
+    }
+
+    /// Assigns attribute value on behalf of `issuer`. Empty value removes attribute.
+    pub fn set_attribute(&mut self, key: &str, value: &str, issuer: &AdministrationAddress) {
+        self.attributes.retain(|attribute| attribute.key != key);
+        if !value.is_empty() {
+            self.attributes.push(ParticipantAttribute {
+                key: key.to_owned(),
+                value: value.to_owned(),
+                issuer: *issuer,
+            });
+            self.attributes.sort_by(|a, b| a.key.cmp(&b.key));
         }
     }
     // Todo: Add methods for modification participant objects
@@ -111,7 +430,7 @@ impl Administration {
         &addr: &Address,
         name: &str,
         principal_key: &Option<AdministrationAddress>,
-        area: &geo::Polygon,
+        area: &geo::MultiPolygon,
         administration_level: u32,
         history_len: u64,
         history_hash: &Hash,
@@ -136,4 +455,34 @@ impl Election {
     pub fn not_started_yet(&self, moment: DateTime<Utc>) -> bool {
         !self.is_cancelled && self.start_date > moment
     }
+
+    pub fn is_reveal_open(&self, moment: DateTime<Utc>) -> bool {
+        self.ballot_mode == BallotMode::CommitReveal
+            && !self.is_cancelled
+            && self.finish_date <= moment
+            && self.reveal_finish_date > moment
+    }
+
+    /// Checks whether no more ballots can be counted: election is cancelled, or
+    /// finished and its reveal window, if any, is closed.
+    pub fn is_finalised(&self, moment: DateTime<Utc>) -> bool {
+        let closing_date = match self.ballot_mode {
+            BallotMode::CommitReveal => self.reveal_finish_date,
+            _ => self.finish_date,
+        };
+        self.is_cancelled || closing_date <= moment
+    }
+
+    /// Checks whether vote counts may be shown at `moment`.
+    pub fn are_results_visible(&self, moment: DateTime<Utc>) -> bool {
+        match self.results_visibility {
+            ResultsVisibility::Live => true,
+            ResultsVisibility::AfterFinish => self.is_cancelled || self.finish_date <= moment,
+            ResultsVisibility::AfterFinalisation => self.is_finalised(moment),
+        }
+    }
+
+    pub fn eligibility_rule(&self) -> EligibilityRule<'_> {
+        EligibilityRule::new(&self.eligibility)
+    }
 }
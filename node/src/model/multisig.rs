@@ -0,0 +1,131 @@
+//! Multi-signature administrations.
+//!
+//! Administration may declare a set of signers and a threshold. Sensitive actions of
+//! such administration are proposed by a signer and executed once enough signers have
+//! approved the proposal before it expires.
+
+use chrono::{DateTime, Duration, Utc};
+use exonum::runtime::CallerAddress as Address;
+
+use anyhow as failure;
+use exonum_derive::{BinaryValue, ObjectHash};
+use exonum_proto::ProtobufConvert;
+
+use super::{
+    transactions::{
+        IssueElection, IssueInvitations, SetParticipantAttributes, SetParticipantStatus,
+        SucceedAdministration, UpdateVoterRoll,
+    },
+    AdministrationAddress, ElectionAddress,
+};
+use crate::proto;
+
+/// Maximal number of signers of one administration.
+pub const MAX_SIGNERS: usize = 16;
+
+/// Time during which proposal can be approved.
+pub fn proposal_lifetime() -> Duration {
+    Duration::days(7)
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::SignerSet", serde_pb_convert)]
+pub struct SignerSet {
+    pub signers: Vec<Address>,
+    /// Number of approvals required to execute proposal.
+    pub threshold: u32,
+}
+
+impl SignerSet {
+    pub fn is_valid(&self) -> bool {
+        let mut signers = self.signers.clone();
+        signers.sort();
+        signers.dedup();
+        signers.len() == self.signers.len()
+            && signers.len() <= MAX_SIGNERS
+            && self.threshold > 0
+            && self.threshold as usize <= signers.len()
+    }
+
+    pub fn is_signer(&self, addr: &Address) -> bool {
+        self.signers.contains(addr)
+    }
+}
+
+/// Sensitive action of administration.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ProposalAction {
+    IssueElection(IssueElection),
+    CancelElection(ElectionAddress),
+    SetSigners(SignerSet),
+    IssueInvitations(IssueInvitations),
+    SetParticipantStatus(SetParticipantStatus),
+    UpdateVoterRoll(UpdateVoterRoll),
+    SetParticipantAttributes(SetParticipantAttributes),
+    SucceedAdministration(SucceedAdministration),
+}
+
+impl ProtobufConvert for ProposalAction {
+    type ProtoStruct = proto::ProposalAction;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        let mut pb = Self::ProtoStruct::new();
+        match self {
+            Self::IssueElection(arg) => pb.set_issue_election(arg.to_pb()),
+            Self::CancelElection(election_id) => pb.set_cancel_election(election_id.to_pb()),
+            Self::SetSigners(signers) => pb.set_set_signers(signers.to_pb()),
+            Self::IssueInvitations(arg) => pb.set_issue_invitations(arg.to_pb()),
+            Self::SetParticipantStatus(arg) => pb.set_set_participant_status(arg.to_pb()),
+            Self::UpdateVoterRoll(arg) => pb.set_update_voter_roll(arg.to_pb()),
+            Self::SetParticipantAttributes(arg) => pb.set_set_participant_attributes(arg.to_pb()),
+            Self::SucceedAdministration(arg) => pb.set_succeed_administration(arg.to_pb()),
+        }
+        pb
+    }
+
+    fn from_pb(mut pb: Self::ProtoStruct) -> anyhow::Result<Self> {
+        Ok(if pb.has_issue_election() {
+            Self::IssueElection(IssueElection::from_pb(pb.take_issue_election())?)
+        } else if pb.has_cancel_election() {
+            Self::CancelElection(ElectionAddress::from_pb(pb.take_cancel_election())?)
+        } else if pb.has_set_signers() {
+            Self::SetSigners(SignerSet::from_pb(pb.take_set_signers())?)
+        } else if pb.has_issue_invitations() {
+            Self::IssueInvitations(IssueInvitations::from_pb(pb.take_issue_invitations())?)
+        } else if pb.has_set_participant_status() {
+            Self::SetParticipantStatus(SetParticipantStatus::from_pb(
+                pb.take_set_participant_status(),
+            )?)
+        } else if pb.has_update_voter_roll() {
+            Self::UpdateVoterRoll(UpdateVoterRoll::from_pb(pb.take_update_voter_roll())?)
+        } else if pb.has_set_participant_attributes() {
+            Self::SetParticipantAttributes(SetParticipantAttributes::from_pb(
+                pb.take_set_participant_attributes(),
+            )?)
+        } else if pb.has_succeed_administration() {
+            Self::SucceedAdministration(SucceedAdministration::from_pb(
+                pb.take_succeed_administration(),
+            )?)
+        } else {
+            anyhow::bail!("Proposal action is not set")
+        })
+    }
+}
+
+/// Action proposed by signer of administration, keyed by hash of the proposing transaction.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::Proposal", serde_pb_convert)]
+pub struct Proposal {
+    pub administration: AdministrationAddress,
+    pub action: ProposalAction,
+    /// Signers approved the proposal, starting with the proposer.
+    pub approvals: Vec<Address>,
+    pub expires_at: DateTime<Utc>,
+    pub is_executed: bool,
+}
+
+impl Proposal {
+    pub fn is_pending(&self, moment: DateTime<Utc>) -> bool {
+        !self.is_executed && self.expires_at > moment
+    }
+}
@@ -0,0 +1,116 @@
+//! Personal data of participants.
+//!
+//! Only salted commitments to email, phone number and pass code are stored on-chain.
+//! Plaintext is kept off-chain by nodes (see `PiiStore`) and disclosed as commitment
+//! opening, which anyone can check against the participant record.
+
+use exonum::crypto::{self, Hash};
+use rand::{CryptoRng, RngCore};
+
+use anyhow as failure;
+use exonum_derive::{BinaryValue, ObjectHash};
+use exonum_proto::ProtobufConvert;
+
+use super::Participant;
+use crate::proto;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PiiField {
+    Email,
+    PhoneNumber,
+    PassCode,
+}
+
+impl PiiField {
+    pub(crate) fn tag(self) -> u8 {
+        match self {
+            Self::Email => 0,
+            Self::PhoneNumber => 1,
+            Self::PassCode => 2,
+        }
+    }
+}
+
+impl ProtobufConvert for PiiField {
+    type ProtoStruct = proto::PiiField;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        match self {
+            Self::Email => proto::PiiField::EMAIL,
+            Self::PhoneNumber => proto::PiiField::PHONE_NUMBER,
+            Self::PassCode => proto::PiiField::PASS_CODE,
+        }
+    }
+
+    fn from_pb(pb: Self::ProtoStruct) -> anyhow::Result<Self> {
+        Ok(match pb {
+            proto::PiiField::EMAIL => Self::Email,
+            proto::PiiField::PHONE_NUMBER => Self::PhoneNumber,
+            proto::PiiField::PASS_CODE => Self::PassCode,
+        })
+    }
+}
+
+/// Calculates salted commitment to personal data field.
+pub fn pii_commitment(field: PiiField, value: &str, salt: &Hash) -> Hash {
+    let mut buffer = Vec::with_capacity(1 + salt.as_ref().len() + value.len());
+    buffer.push(field.tag());
+    buffer.extend_from_slice(salt.as_ref());
+    buffer.extend_from_slice(value.as_bytes());
+    crypto::hash(&buffer)
+}
+
+/// Normalises pass code: separators and whitespace are dropped, letters are uppercased.
+pub fn normalise_pass_code(pass_code: &str) -> String {
+    pass_code
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// Calculates keyed hash of normalised pass code. Equal fingerprints of participants
+/// mean that they are the same person, while the pass code itself stays hidden.
+pub fn pass_code_fingerprint(key: &[u8], pass_code: &str) -> Hash {
+    let pass_code = normalise_pass_code(pass_code);
+    let mut buffer = Vec::with_capacity(8 + key.len() + pass_code.len());
+    buffer.extend_from_slice(&(key.len() as u64).to_le_bytes());
+    buffer.extend_from_slice(key);
+    buffer.extend_from_slice(pass_code.as_bytes());
+    crypto::hash(&buffer)
+}
+
+/// Opening of commitment to personal data field.
+#[derive(Clone, Debug, PartialEq, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::PiiOpening", serde_pb_convert)]
+pub struct PiiOpening {
+    pub field: PiiField,
+    pub value: String,
+    pub salt: Hash,
+}
+
+impl PiiOpening {
+    pub fn new(field: PiiField, value: &str, salt: Hash) -> Self {
+        Self {
+            field,
+            value: value.to_owned(),
+            salt,
+        }
+    }
+
+    /// Commits to `value` with random salt.
+    pub fn random<R: RngCore + CryptoRng>(field: PiiField, value: &str, rng: &mut R) -> Self {
+        let mut salt = [0; 32];
+        rng.fill_bytes(&mut salt);
+        Self::new(field, value, Hash::new(salt))
+    }
+
+    pub fn commitment(&self) -> Hash {
+        pii_commitment(self.field, &self.value, &self.salt)
+    }
+
+    /// Checks whether opening matches commitment stored in participant record.
+    pub fn opens(&self, participant: &Participant) -> bool {
+        participant.pii_commitment(self.field) == &self.commitment()
+    }
+}
@@ -4,17 +4,22 @@ use std::{collections::HashMap, fmt::Debug};
 use chrono::{DateTime, Utc};
 use exonum::{
     blockchain::BlockProof,
-    crypto::{Hash, PublicKey},
+    crypto::{Hash, PublicKey, Signature},
     messages::{AnyTx, Verified},
 };
 use exonum_merkledb::{
-    BinaryKey, BinaryValue, Group, ListProof, MapProof, ProofListIndex, Snapshot,
+    access::RawAccess, proof_map::Raw, BinaryKey, BinaryValue, Group, ListProof, MapProof,
+    ProofListIndex, RawProofMapIndex, Snapshot,
 };
 
 use super::{
+    invitation::Invitation,
+    multisig::{Proposal, SignerSet},
+    pii::{PiiField, PiiOpening},
+    recovery::{Guardians, Recovery},
     wrappers::{RawKeyModeWrapper, TypeWrapper},
     Administration, AdministrationAddress, Election, ElectionAddress, ElectionOptionAddress,
-    Participant, ParticipantAddress,
+    ElectionTally, Participant, ParticipantAddress,
 };
 use crate::schema::IndexPair;
 use exonum::blockchain::IndexProof;
@@ -119,6 +124,189 @@ impl History {
     }
 }
 
+/// Proof that ballot of participant is recorded in election, or that it is not.
+///
+/// Members of `election_votes` group are not aggregated into the state hash, so the proof
+/// goes through root hash of election votes stored in `election_vote_roots`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VoteReceipt {
+    pub block_proof: BlockProof,
+    /// Proof of `election_vote_roots` index in the state.
+    pub to_table: MapProof<String, Hash>,
+    /// Proof of root hash of election votes.
+    pub to_votes: MapProof<ElectionAddress, Hash, Raw>,
+    /// Proof of the option selected by participant.
+    pub to_vote: MapProof<ParticipantAddress, ElectionOptionAddress, Raw>,
+}
+
+/// Reason of proof rejection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Fail)]
+pub enum VerificationError {
+    #[fail(display = "Block is not signed by validators")]
+    InvalidBlockProof,
+    #[fail(display = "Proof of index does not match block")]
+    InvalidTableProof,
+    #[fail(display = "Proof of election entry does not match index")]
+    InvalidIndexProof,
+    #[fail(display = "Proof of vote does not match election votes")]
+    InvalidVoteProof,
+}
+
+/// Checks that service index `index_name` is a part of the block signed by validators.
+///
+/// Returns hash of the index.
+fn verify_table_proof(
+    block_proof: &BlockProof,
+    to_table: &MapProof<String, Hash>,
+    index_name: &str,
+    validator_keys: &[PublicKey],
+) -> Result<Hash, VerificationError> {
+    block_proof
+        .verify(validator_keys)
+        .map_err(|_| VerificationError::InvalidBlockProof)?;
+
+    let to_table = to_table
+        .check_against_hash(block_proof.block.state_hash)
+        .map_err(|_| VerificationError::InvalidTableProof)?;
+    to_table
+        .entries()
+        .find(|(name, _)| *name == index_name)
+        .map(|(_, hash)| *hash)
+        .ok_or(VerificationError::InvalidTableProof)
+}
+
+fn index_proof(
+    blockchain_data: &BlockchainData<&dyn Snapshot>,
+    index_name: &str,
+) -> Result<IndexProof, exonum_merkledb::Error> {
+    blockchain_data
+        .proof_for_service_index(index_name)
+        .ok_or_else(|| {
+            exonum_merkledb::Error::new(format!("No such index with name '{}'", index_name))
+        })
+}
+
+impl VoteReceipt {
+    pub(crate) fn try_from_indexes<T: RawAccess>(
+        blockchain_data: &BlockchainData<&dyn Snapshot>,
+        roots_index_name: &str,
+        roots: &RawProofMapIndex<T, ElectionAddress, Hash>,
+        votes: &RawProofMapIndex<T, ParticipantAddress, ElectionOptionAddress>,
+        election: ElectionAddress,
+        participant: ParticipantAddress,
+    ) -> Result<Self, exonum_merkledb::Error> {
+        let IndexProof {
+            block_proof,
+            index_proof,
+            ..
+        } = index_proof(blockchain_data, roots_index_name)?;
+
+        Ok(Self {
+            block_proof,
+            to_table: index_proof,
+            to_votes: roots.get_proof(election),
+            to_vote: votes.get_proof(participant),
+        })
+    }
+
+    /// Verifies receipt against consensus keys of validators.
+    ///
+    /// Returns option selected by participant, or `None` if the receipt proves that
+    /// participant has not voted.
+    pub fn verify(
+        &self,
+        service_name: &str,
+        validator_keys: &[PublicKey],
+        election: &ElectionAddress,
+        participant: &ParticipantAddress,
+    ) -> Result<Option<ElectionOptionAddress>, VerificationError> {
+        let table_hash = verify_table_proof(
+            &self.block_proof,
+            &self.to_table,
+            &format!("{}.election_vote_roots", service_name),
+            validator_keys,
+        )?;
+
+        let to_votes = self
+            .to_votes
+            .check_against_hash(table_hash)
+            .map_err(|_| VerificationError::InvalidIndexProof)?;
+        let votes_hash = match to_votes.all_entries().find(|(key, _)| *key == election) {
+            Some((_, Some(hash))) => *hash,
+            Some((_, None)) => return Ok(None),
+            None => return Err(VerificationError::InvalidIndexProof),
+        };
+
+        let to_vote = self
+            .to_vote
+            .check_against_hash(votes_hash)
+            .map_err(|_| VerificationError::InvalidVoteProof)?;
+        to_vote
+            .all_entries()
+            .find(|(key, _)| *key == participant)
+            .map(|(_, option)| option.copied())
+            .ok_or(VerificationError::InvalidVoteProof)
+    }
+}
+
+/// Counted ballots of election with the proof to the block.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TallyProof {
+    pub block_proof: BlockProof,
+    /// Proof of `election_tallies` index in the state.
+    pub to_table: MapProof<String, Hash>,
+    /// Proof of tally of election.
+    pub to_tally: MapProof<ElectionAddress, ElectionTally, Raw>,
+}
+
+impl TallyProof {
+    pub(crate) fn try_from_index<T: RawAccess>(
+        blockchain_data: &BlockchainData<&dyn Snapshot>,
+        index_name: &str,
+        tallies: &RawProofMapIndex<T, ElectionAddress, ElectionTally>,
+        election: ElectionAddress,
+    ) -> Result<Self, exonum_merkledb::Error> {
+        let IndexProof {
+            block_proof,
+            index_proof,
+            ..
+        } = index_proof(blockchain_data, index_name)?;
+
+        Ok(Self {
+            block_proof,
+            to_table: index_proof,
+            to_tally: tallies.get_proof(election),
+        })
+    }
+
+    /// Verifies tally against consensus keys of validators.
+    ///
+    /// Returns `None` if the proof shows that election has no tally yet.
+    pub fn verify(
+        &self,
+        service_name: &str,
+        validator_keys: &[PublicKey],
+        election: &ElectionAddress,
+    ) -> Result<Option<ElectionTally>, VerificationError> {
+        let table_hash = verify_table_proof(
+            &self.block_proof,
+            &self.to_table,
+            &format!("{}.election_tallies", service_name),
+            validator_keys,
+        )?;
+
+        let to_tally = self
+            .to_tally
+            .check_against_hash(table_hash)
+            .map_err(|_| VerificationError::InvalidIndexProof)?;
+        to_tally
+            .all_entries()
+            .find(|(key, _)| *key == election)
+            .map(|(_, tally)| tally.cloned())
+            .ok_or(VerificationError::InvalidIndexProof)
+    }
+}
+
 pub type PubKeyQuery = KeyQuery<PublicKey>;
 pub type I64Query = KeyQuery<i64>;
 
@@ -130,6 +318,82 @@ where
     pub key: K,
 }
 
+/// Query of participant-specific data of election.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ElectionParticipantQuery {
+    pub election: ElectionAddress,
+    pub participant: PublicKey,
+}
+
+/// Openings of personal data commitments submitted by participant.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PiiSubmission {
+    pub participant: PublicKey,
+    pub openings: Vec<PiiOpening>,
+}
+
+/// Request to send verification code to email or phone number of participant, signed by
+/// participant over `contact::request_message`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ContactVerificationQuery {
+    pub participant: PublicKey,
+    pub field: PiiField,
+    pub requested_at: DateTime<Utc>,
+    pub signature: Signature,
+}
+
+/// Guardians of participant and pending recovery of its account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryInfo {
+    pub guardians: Option<Guardians>,
+    pub recovery: Option<Recovery>,
+}
+
+/// Signers of administration and its proposals waiting for approvals.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposalsInfo {
+    pub signers: Option<SignerSet>,
+    pub pending: Vec<PendingProposal>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingProposal {
+    pub proposal_id: Hash,
+    pub proposal: Proposal,
+}
+
+/// Registration invitations issued by administration, with their usage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvitationReport {
+    pub administration: AdministrationAddress,
+    pub used: u32,
+    pub expired: u32,
+    pub unused: u32,
+    pub invitations: Vec<Invitation>,
+}
+
+/// Participants sharing a pass code or holding inconsistent pass code fingerprints,
+/// according to pass codes stored on the node.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DedupReport {
+    /// Groups of participants with the same pass code.
+    pub duplicates: Vec<Vec<ParticipantAddress>>,
+    /// Participants whose attested fingerprint does not match their pass code, which means
+    /// that it is attested with another key.
+    pub mismatched: Vec<ParticipantAddress>,
+    /// Participants whose pass code is not stored on the node.
+    pub unverified: Vec<ParticipantAddress>,
+}
+
+/// Claimed value of personal data field of participant with the salt of its commitment.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PiiCheckQuery {
+    pub participant: PublicKey,
+    pub field: PiiField,
+    pub value: String,
+    pub salt: Hash,
+}
+
 #[derive(Debug, Clone, Serialize, PartialEq)]
 pub struct ElectionGroup {
     pub organization_name: String,
@@ -227,6 +491,21 @@ impl From<(super::ElectionOption, u32)> for ElectionOptionConvert {
     }
 }
 
+/// Ballot commitments which have not been revealed in time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UnrevealedBallots {
+    pub election: ElectionAddress,
+    pub count: u32,
+    pub participants: Vec<ParticipantAddress>,
+}
+
+/// Decrypted ballots of mixnet election in the order of the last shuffle.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MixnetBallots {
+    pub election: ElectionAddress,
+    pub ballots: Vec<Vec<u32>>,
+}
+
 #[derive(Debug, Clone, Serialize, PartialEq)]
 pub enum Node<K: Clone, V: Clone> {
     WithChildren {
@@ -0,0 +1,109 @@
+//! Social recovery of lost participant keys.
+//!
+//! Participant nominates guardians, which are other participants or its residence
+//! administrations, and the number of approvals required. Each guardian approves one new
+//! key at a time and may switch to another one, so a single guardian can not pin the
+//! recovery to its own key. Once enough guardians approve rebinding the account to the same
+//! new key, the old key can still cancel the recovery during the challenge period. After
+//! the period the new key completes the recovery.
+
+use chrono::{DateTime, Duration, Utc};
+use exonum::{crypto::PublicKey, runtime::CallerAddress as Address};
+
+use anyhow as failure;
+use exonum_derive::{BinaryValue, ObjectHash};
+use exonum_proto::ProtobufConvert;
+
+use crate::proto;
+
+/// Maximal number of guardians of one participant.
+pub const MAX_GUARDIANS: usize = 16;
+
+/// Time after the last required approval during which the old key can cancel recovery.
+pub fn challenge_period() -> Duration {
+    Duration::days(3)
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::Guardians", serde_pb_convert)]
+pub struct Guardians {
+    /// Addresses of guardian participants or administrations.
+    pub guardians: Vec<Address>,
+    /// Number of approvals required for recovery.
+    pub threshold: u32,
+}
+
+/// New key proposed for recovery with the guardians approving it.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::RecoveryCandidate", serde_pb_convert)]
+pub struct RecoveryCandidate {
+    pub new_key: PublicKey,
+    /// Guardians approved the key, as listed in `Guardians`.
+    pub approvals: Vec<Address>,
+}
+
+/// Pending recovery of participant account.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::Recovery", serde_pb_convert)]
+pub struct Recovery {
+    /// Number of approvals required, fixed when recovery starts.
+    pub threshold: u32,
+    pub last_approval_at: DateTime<Utc>,
+    /// Keys approved by guardians, each guardian approving one of them.
+    pub candidates: Vec<RecoveryCandidate>,
+}
+
+impl Recovery {
+    pub fn new(threshold: u32, now: DateTime<Utc>) -> Self {
+        Self {
+            threshold,
+            last_approval_at: now,
+            candidates: Vec::new(),
+        }
+    }
+
+    /// New key approved by the required number of guardians.
+    pub fn approved_key(&self) -> Option<PublicKey> {
+        self.candidates
+            .iter()
+            .find(|candidate| candidate.approvals.len() >= self.threshold as usize)
+            .map(|candidate| candidate.new_key)
+    }
+
+    pub fn is_approved(&self) -> bool {
+        self.approved_key().is_some()
+    }
+
+    pub fn has_approved(&self, guardian: &Address, new_key: &PublicKey) -> bool {
+        self.candidates.iter().any(|candidate| {
+            candidate.new_key == *new_key && candidate.approvals.contains(guardian)
+        })
+    }
+
+    /// Records approval of `new_key` by guardian, withdrawing its approval of other keys.
+    pub fn approve(&mut self, guardian: &Address, new_key: PublicKey, now: DateTime<Utc>) {
+        for candidate in &mut self.candidates {
+            candidate.approvals.retain(|approval| approval != guardian);
+        }
+        self.candidates
+            .retain(|candidate| !candidate.approvals.is_empty());
+
+        match self
+            .candidates
+            .iter_mut()
+            .find(|candidate| candidate.new_key == new_key)
+        {
+            Some(candidate) => candidate.approvals.push(*guardian),
+            None => self.candidates.push(RecoveryCandidate {
+                new_key,
+                approvals: vec![*guardian],
+            }),
+        }
+        self.last_approval_at = now;
+    }
+
+    /// Checks whether recovery is approved and its challenge period is over.
+    pub fn is_unlocked(&self, moment: DateTime<Utc>) -> bool {
+        self.is_approved() && self.last_approval_at + challenge_period() <= moment
+    }
+}
@@ -0,0 +1,72 @@
+//! Data models of `BallotMode::Ring` elections.
+//!
+//! Eligible participants register ring keys before election start, which freezes the
+//! electorate. Ballot is signed with linkable ring signature over all registered keys.
+
+use std::borrow::Cow;
+
+use exonum::crypto::SecretKey;
+use exonum_merkledb::BinaryValue;
+use rand::{CryptoRng, RngCore};
+
+use anyhow as failure;
+use exonum_derive::{BinaryValue, ObjectHash};
+use exonum_proto::ProtobufConvert;
+
+use super::{ElectionAddress, ElectionOptionAddress};
+use crate::{
+    crypto::{
+        self,
+        ring::{KeyImage, RingKey, RingSignature},
+    },
+    proto,
+};
+
+impl_bytes_convert!(RingKey, KeyImage, RingSignature);
+
+/// Ring keys of the frozen electorate, ordered by participant address.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::ElectionRing", serde_pb_convert)]
+pub struct ElectionRing {
+    pub keys: Vec<RingKey>,
+}
+
+/// Derives secret ring key of participant for given election.
+pub fn ring_secret(participant_key: &SecretKey, election: &ElectionAddress) -> crypto::Scalar {
+    crypto::derive_scalar(
+        b"crypto-election/ring-secret",
+        &participant_key[..],
+        election.as_ref(),
+    )
+}
+
+/// Signature context of ballot selecting `option_id` in `election`.
+pub fn ring_context(election: &ElectionAddress, option_id: ElectionOptionAddress) -> Vec<u8> {
+    [
+        b"ring-ballot".as_ref(),
+        election.as_ref(),
+        &option_id.to_le_bytes(),
+    ]
+    .concat()
+}
+
+/// Signs ballot with ring key derived from `secret`.
+///
+/// Returns `None` if the key is not a member of `ring`.
+pub fn sign_ballot<R: RngCore + CryptoRng>(
+    election: &ElectionAddress,
+    option_id: ElectionOptionAddress,
+    ring: &[RingKey],
+    secret: &crypto::Scalar,
+    rng: &mut R,
+) -> Option<RingSignature> {
+    let key = RingKey::from_secret(secret);
+    let index = ring.iter().position(|member| *member == key)?;
+    Some(RingSignature::sign(
+        &ring_context(election, option_id),
+        ring,
+        index,
+        secret,
+        rng,
+    ))
+}
@@ -4,8 +4,32 @@ use anyhow as failure;
 use exonum_derive::{BinaryValue, ObjectHash};
 use exonum_proto::ProtobufConvert;
 
-use super::{geo, wrappers::OptionalContainer, AdministrationAddress, ElectionAddress};
-use crate::proto;
+use exonum::{
+    crypto::{Hash, PublicKey, Signature},
+    runtime::CallerAddress as Address,
+};
+
+use super::{
+    approval::CounterSignature,
+    eligibility::EligibilityTerm,
+    encryption::{Complaint, TransportKey},
+    geo,
+    multisig::ProposalAction,
+    pii::PiiField,
+    wrappers::OptionalContainer,
+    AdministrationAddress, BallotMode, ElectionAddress, ElectionOptionAddress, ParticipantAddress,
+    ParticipantStatus, ResultsVisibility,
+};
+use crate::{
+    crypto::{
+        ballot::{EncryptedBallot, PartialDecryption},
+        blind::{BlindChallenge, BlindResponse, BlindSignature, NonceCommitment, VerificationKey},
+        dkg::Dealing,
+        mixnet::{BallotList, MixnetBallot, ShuffleProof},
+        ring::{RingKey, RingSignature},
+    },
+    proto,
+};
 
 /// Election configuration parameters.
 #[derive(Clone, Debug, Serialize, Deserialize, ProtobufConvert, BinaryValue, ObjectHash)]
@@ -15,14 +39,141 @@ pub struct Config {
     pub time_service_name: String,
 }
 
+/// Registers participant under the signing key.
+///
+/// Node sees only the pass code commitment here, so duplicate pass codes are not rejected
+/// on creation. They are rejected by `AttestPassCode`, which every participant needs to be
+/// verified, invited ones included, so a duplicate account stays pending and can not vote.
 #[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
 #[protobuf_convert(source = "proto::CreateParticipant", serde_pb_convert)]
 pub struct CreateParticipant {
     pub name: String,
-    pub email: String,
-    pub phone_number: String,
+    /// Salted commitment to email, see `pii::pii_commitment`.
+    pub email_commitment: Hash,
+    /// Salted commitment to phone number.
+    pub phone_number_commitment: Hash,
     pub residence: OptionalContainer<AdministrationAddress>,
-    pub pass_code: String,
+    /// Salted commitment to pass code.
+    pub pass_code_commitment: Hash,
+    /// Signature over participant address by the key of one-time registration code, see
+    /// `invitation::redeem`. Empty if participant is not invited.
+    pub invitation: OptionalContainer<CounterSignature>,
+}
+
+/// Attests fingerprint of pass code of participant, calculated by validator from the pass
+/// code submitted to it with the key kept off-chain, see `pii::pass_code_fingerprint`.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::AttestPassCode", serde_pb_convert)]
+pub struct AttestPassCode {
+    pub participant: ParticipantAddress,
+    pub fingerprint: Hash,
+}
+
+/// Publishes challenge for verification code sent to participant by validator.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::PublishContactChallenge", serde_pb_convert)]
+pub struct PublishContactChallenge {
+    pub participant: ParticipantAddress,
+    pub field: PiiField,
+    pub challenge: Hash,
+}
+
+/// Confirms contact of participant with keyed hash of the received code.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::ConfirmContact", serde_pb_convert)]
+pub struct ConfirmContact {
+    pub field: PiiField,
+    /// See `contact::code_mac`.
+    pub code_mac: Hash,
+}
+
+/// Moves participant identity to the new key, see `key_rotation` module.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::RotateParticipantKey", serde_pb_convert)]
+pub struct RotateParticipantKey {
+    pub new_key: PublicKey,
+    pub new_key_signature: Signature,
+}
+
+/// Moves administration of transaction author to the new key. The new key signs the same
+/// message as for `RotateParticipantKey`.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::SucceedAdministration", serde_pb_convert)]
+pub struct SucceedAdministration {
+    pub new_key: PublicKey,
+    pub new_key_signature: Signature,
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::CancelElection", serde_pb_convert)]
+pub struct CancelElection {
+    pub election_id: ElectionAddress,
+    pub seed: u64,
+}
+
+/// Declares signers of administration of transaction author, see `multisig` module.
+/// Signers of multi-signature administration are changed by proposal only.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::SetSigners", serde_pb_convert)]
+pub struct SetSigners {
+    pub signers: Vec<Address>,
+    pub threshold: u32,
+}
+
+/// Proposes sensitive action of multi-signature administration, approved by the proposer.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::ProposeAction", serde_pb_convert)]
+pub struct ProposeAction {
+    pub administration: AdministrationAddress,
+    pub action: ProposalAction,
+    pub seed: u64,
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::ApproveProposal", serde_pb_convert)]
+pub struct ApproveProposal {
+    pub proposal_id: Hash,
+}
+
+/// Nominates guardians of transaction author, see `recovery` module.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::SetGuardians", serde_pb_convert)]
+pub struct SetGuardians {
+    pub guardians: Vec<Address>,
+    pub threshold: u32,
+}
+
+/// Approves recovery of participant account by guardian. The new key signs the same
+/// message as for `RotateParticipantKey`.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::ApproveRecovery", serde_pb_convert)]
+pub struct ApproveRecovery {
+    pub participant: ParticipantAddress,
+    pub new_key: PublicKey,
+    pub new_key_signature: Signature,
+}
+
+/// Completes unlocked recovery, sent from the new key.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::CompleteRecovery", serde_pb_convert)]
+pub struct CompleteRecovery {
+    pub participant: ParticipantAddress,
+}
+
+/// Cancels pending recovery of transaction author.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::CancelRecovery", serde_pb_convert)]
+pub struct CancelRecovery {
+    pub seed: u64,
+}
+
+/// Issues one-time registration codes, given by their public keys, see
+/// `invitation::invitation_keys`.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::IssueInvitations", serde_pb_convert)]
+pub struct IssueInvitations {
+    pub keys: Vec<PublicKey>,
+    pub expires_at: DateTime<Utc>,
 }
 
 #[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
@@ -30,7 +181,10 @@ pub struct CreateParticipant {
 pub struct CreateAdministration {
     pub name: String,
     pub principal_key: OptionalContainer<AdministrationAddress>,
-    pub area: geo::Polygon,
+    pub area: geo::MultiPolygon,
+    /// Signatures of principal or validators over creation message, see `approval` module.
+    pub approvals: Vec<CounterSignature>,
+    pub expires_at: DateTime<Utc>,
 }
 
 #[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
@@ -41,6 +195,15 @@ pub struct IssueElection {
     pub start_date: DateTime<Utc>,
     pub finish_date: DateTime<Utc>,
     pub options: Vec<String>,
+    pub eligibility: Vec<EligibilityTerm>,
+    pub ballot_mode: BallotMode,
+    /// End of reveal window. Required by `BallotMode::CommitReveal` only.
+    pub reveal_finish_date: DateTime<Utc>,
+    /// Key verifying voting tokens. Required by `BallotMode::Anonymous` only.
+    pub token_key: OptionalContainer<VerificationKey>,
+    /// Number of values in ballot. Required by `BallotMode::Mixnet` only.
+    pub ballot_length: u32,
+    pub results_visibility: ResultsVisibility,
 }
 
 #[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
@@ -51,6 +214,140 @@ pub struct Vote {
     pub seed: u64,
 }
 
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::CommitVote", serde_pb_convert)]
+pub struct CommitVote {
+    pub election_id: ElectionAddress,
+    /// Commitment to selected option, see `model::ballot_commitment`.
+    pub commitment: Hash,
+    pub seed: u64,
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::RevealVote", serde_pb_convert)]
+pub struct RevealVote {
+    pub election_id: ElectionAddress,
+    pub option_id: ElectionOptionAddress,
+    pub salt: Hash,
+    pub seed: u64,
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::CastEncryptedVote", serde_pb_convert)]
+pub struct CastEncryptedVote {
+    pub election_id: ElectionAddress,
+    pub ballot: EncryptedBallot,
+    pub seed: u64,
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::PublishTransportKey", serde_pb_convert)]
+pub struct PublishTransportKey {
+    pub election_id: ElectionAddress,
+    pub key: TransportKey,
+}
+
+/// Shares of a trustee secret dealt to trustees with transport keys.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::PublishDealing", serde_pb_convert)]
+pub struct PublishDealing {
+    pub election_id: ElectionAddress,
+    pub dealing: Dealing,
+}
+
+/// Confirmation that shares dealt to the trustee are valid, except for ones complained
+/// about.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::AcknowledgeDealings", serde_pb_convert)]
+pub struct AcknowledgeDealings {
+    pub election_id: ElectionAddress,
+    pub complaints: Vec<Complaint>,
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::PublishPartialDecryption", serde_pb_convert)]
+pub struct PublishPartialDecryption {
+    pub election_id: ElectionAddress,
+    pub decryption: PartialDecryption,
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::OfferVotingToken", serde_pb_convert)]
+pub struct OfferVotingToken {
+    pub election_id: ElectionAddress,
+    pub participant: ParticipantAddress,
+    /// Commitment to the nonce of the signing session, see `model::voting_token::token_nonce`.
+    pub commitment: NonceCommitment,
+    pub seed: u64,
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::RequestVotingToken", serde_pb_convert)]
+pub struct RequestVotingToken {
+    pub election_id: ElectionAddress,
+    /// Challenge blinded for the fresh voting key, see `model::voting_token::blind_token`.
+    pub challenge: BlindChallenge,
+    pub seed: u64,
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::IssueVotingToken", serde_pb_convert)]
+pub struct IssueVotingToken {
+    pub election_id: ElectionAddress,
+    pub participant: ParticipantAddress,
+    pub response: BlindResponse,
+    pub seed: u64,
+}
+
+/// Ballot of `BallotMode::Anonymous` election, signed by the fresh voting key.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::CastAnonymousVote", serde_pb_convert)]
+pub struct CastAnonymousVote {
+    pub election_id: ElectionAddress,
+    pub option_id: ElectionOptionAddress,
+    /// Unblinded voting token issued for the transaction author.
+    pub token: BlindSignature,
+    pub seed: u64,
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::RegisterRingKey", serde_pb_convert)]
+pub struct RegisterRingKey {
+    pub election_id: ElectionAddress,
+    /// Ring key of participant, see `model::ring_ballot::ring_secret`.
+    pub key: RingKey,
+    pub seed: u64,
+}
+
+/// Ballot of `BallotMode::Ring` election. May be sent by any key.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::CastRingVote", serde_pb_convert)]
+pub struct CastRingVote {
+    pub election_id: ElectionAddress,
+    pub option_id: ElectionOptionAddress,
+    /// Signature over the election ring, see `model::ring_ballot::sign_ballot`.
+    pub signature: RingSignature,
+    pub seed: u64,
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::CastMixnetVote", serde_pb_convert)]
+pub struct CastMixnetVote {
+    pub election_id: ElectionAddress,
+    /// Encrypted ballot values, see `model::mixnet::encrypt_ballot`.
+    pub ballot: MixnetBallot,
+    pub seed: u64,
+}
+
+/// Shuffle of the ballots of `BallotMode::Mixnet` election made by the next trustee.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::PublishShuffle", serde_pb_convert)]
+pub struct PublishShuffle {
+    pub election_id: ElectionAddress,
+    pub output: BallotList,
+    pub proof: ShuffleProof,
+}
+
 #[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
 #[protobuf_convert(source = "proto::SubmitLocation", serde_pb_convert)]
 pub struct SubmitLocation {
@@ -58,3 +355,36 @@ pub struct SubmitLocation {
     pub date: DateTime<Utc>,
     pub seed: u64,
 }
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::AttributeValue", serde_pb_convert)]
+pub struct AttributeValue {
+    pub key: String,
+    /// New attribute value. Empty value removes attribute.
+    pub value: String,
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::SetParticipantStatus", serde_pb_convert)]
+pub struct SetParticipantStatus {
+    pub participant: ParticipantAddress,
+    pub status: ParticipantStatus,
+    pub seed: u64,
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::SetParticipantAttributes", serde_pb_convert)]
+pub struct SetParticipantAttributes {
+    pub participant: ParticipantAddress,
+    pub attributes: Vec<AttributeValue>,
+    pub seed: u64,
+}
+
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::UpdateVoterRoll", serde_pb_convert)]
+pub struct UpdateVoterRoll {
+    pub roll_id: Hash,
+    pub added: Vec<ParticipantAddress>,
+    pub removed: Vec<ParticipantAddress>,
+    pub seed: u64,
+}
@@ -0,0 +1,101 @@
+//! Data models of `BallotMode::Anonymous` elections.
+//!
+//! Election issuer blind-signs a voting token for every eligible participant. Token is
+//! bound to a fresh voting key of participant, so the ballot cast with that key can not
+//! be linked to the participant.
+//!
+//! Issuer keeps at most one signing session open in an election: offering a token to the
+//! next participant abandons the previous session unless it has been challenged, and a
+//! challenged session must be answered first.
+
+use std::borrow::Cow;
+
+use exonum::{crypto::SecretKey, runtime::CallerAddress as Address};
+use exonum_merkledb::BinaryValue;
+use rand::{CryptoRng, RngCore};
+
+use anyhow as failure;
+use exonum_derive::{BinaryValue, ObjectHash};
+use exonum_proto::ProtobufConvert;
+
+use super::{wrappers::OptionalContainer, ElectionAddress, ParticipantAddress};
+use crate::{
+    crypto::{
+        self,
+        blind::{
+            BlindChallenge, BlindResponse, BlindSignature, Blinding, NonceCommitment,
+            VerificationKey,
+        },
+    },
+    proto,
+};
+
+impl_bytes_convert!(
+    VerificationKey,
+    NonceCommitment,
+    BlindChallenge,
+    BlindResponse,
+    BlindSignature
+);
+
+/// Blind signing session of a voting token.
+#[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
+#[protobuf_convert(source = "proto::TokenIssuance", serde_pb_convert)]
+pub struct TokenIssuance {
+    /// Nonce commitment offered by election issuer.
+    pub commitment: NonceCommitment,
+    /// Blinded challenge of participant.
+    pub challenge: OptionalContainer<BlindChallenge>,
+    /// Response of election issuer to the challenge.
+    pub response: OptionalContainer<BlindResponse>,
+}
+
+impl TokenIssuance {
+    pub fn new(commitment: NonceCommitment) -> Self {
+        Self {
+            commitment,
+            challenge: None.into(),
+            response: None.into(),
+        }
+    }
+}
+
+/// Derives secret token key of administration for given election.
+pub fn token_secret(administration_key: &SecretKey, election: &ElectionAddress) -> crypto::Scalar {
+    crypto::derive_scalar(
+        b"crypto-election/token-secret",
+        &administration_key[..],
+        election.as_ref(),
+    )
+}
+
+/// Derives nonce of the signing session with `participant`.
+///
+/// Service accepts only one challenge per participant, so the nonce is never reused.
+pub fn token_nonce(
+    administration_key: &SecretKey,
+    election: &ElectionAddress,
+    participant: &ParticipantAddress,
+) -> crypto::Scalar {
+    crypto::derive_scalar(
+        b"crypto-election/token-nonce",
+        &administration_key[..],
+        &[election.as_ref(), participant.as_ref()].concat(),
+    )
+}
+
+/// Message signed by voting token of `voter` in `election`.
+pub fn token_message(election: &ElectionAddress, voter: &Address) -> Vec<u8> {
+    [b"voting-token".as_ref(), election.as_ref(), voter.as_ref()].concat()
+}
+
+/// Blinds voting token for the fresh voting key with address `voter`.
+pub fn blind_token<R: RngCore + CryptoRng>(
+    election: &ElectionAddress,
+    voter: &Address,
+    key: &VerificationKey,
+    commitment: &NonceCommitment,
+    rng: &mut R,
+) -> (Blinding, BlindChallenge) {
+    Blinding::new(key, commitment, &token_message(election, voter), rng)
+}
@@ -5,10 +5,17 @@ use serde::{Deserialize, Serialize};
 use anyhow as failure;
 use exonum::{crypto::Hash, runtime::CallerAddress as Address};
 use exonum_derive::{BinaryValue, ObjectHash};
-use exonum_merkledb::proof_map::{Hashed, Raw};
+use exonum_merkledb::{
+    proof_map::{Hashed, Raw},
+    BinaryValue,
+};
 use exonum_proto::ProtobufConvert;
 
-use crate::proto;
+use super::approval::CounterSignature;
+use crate::{
+    crypto::blind::{BlindChallenge, BlindResponse, VerificationKey},
+    proto,
+};
 
 #[derive(Clone, Debug, ProtobufConvert, BinaryValue, ObjectHash)]
 #[protobuf_convert(source = "proto::VecI64Wrap", serde_pb_convert)]
@@ -104,6 +111,61 @@ impl From<Option<Address>> for OptionalContainer<Address> {
     }
 }
 
+/// Implements protobuf conversion of optional crypto types, stored as empty `bytes` when absent.
+macro_rules! impl_optional_bytes_convert {
+    ($($name:ty),*) => {
+        $(
+            impl ProtobufConvert for OptionalContainer<$name> {
+                type ProtoStruct = Vec<u8>;
+
+                fn to_pb(&self) -> Self::ProtoStruct {
+                    self.as_ref().map_or_else(Vec::new, |v| v.to_bytes())
+                }
+
+                fn from_pb(pb: Self::ProtoStruct) -> anyhow::Result<Self> {
+                    if pb.is_empty() {
+                        return Ok(Self(None));
+                    }
+                    <$name>::from_bytes(&pb).map(|v| Self(Some(v))).ok_or_else(|| {
+                        anyhow::format_err!("Malformed {}", stringify!($name))
+                    })
+                }
+            }
+
+            impl From<Option<$name>> for OptionalContainer<$name> {
+                fn from(option: Option<$name>) -> Self {
+                    Self(option)
+                }
+            }
+        )*
+    };
+}
+
+impl_optional_bytes_convert!(VerificationKey, BlindChallenge, BlindResponse);
+
+impl ProtobufConvert for OptionalContainer<CounterSignature> {
+    type ProtoStruct = Vec<u8>;
+
+    fn to_pb(&self) -> Self::ProtoStruct {
+        self.as_ref()
+            .as_ref()
+            .map_or_else(Vec::new, BinaryValue::to_bytes)
+    }
+
+    fn from_pb(pb: Self::ProtoStruct) -> anyhow::Result<Self> {
+        if pb.is_empty() {
+            return Ok(Self(None));
+        }
+        CounterSignature::from_bytes(pb.into()).map(|v| Self(Some(v)))
+    }
+}
+
+impl From<Option<CounterSignature>> for OptionalContainer<CounterSignature> {
+    fn from(option: Option<CounterSignature>) -> Self {
+        Self(option)
+    }
+}
+
 impl<T> AsRef<Option<T>> for OptionalContainer<T> {
     fn as_ref(&self) -> &Option<T> {
         &self.0
@@ -0,0 +1,82 @@
+//! Node-local store of personal data of participants.
+
+use std::{fmt, path::Path, sync::Arc};
+
+use exonum::crypto::Hash;
+use exonum_merkledb::{access::AccessExt, Database, DbOptions, RocksDB, TemporaryDB};
+use rand::RngCore;
+
+use crate::model::pii::{self, PiiOpening};
+
+/// Openings of personal data commitments, keyed by commitment.
+///
+/// The store is a database separate from the blockchain, so its content is never
+/// replicated to other nodes. Only openings of commitments recorded on-chain are
+/// accepted, and they are read back through private API only.
+///
+/// The store also holds the key of pass code fingerprints, which is shared by nodes
+/// keeping personal data and never published, so fingerprints can not be checked
+/// against guessed pass codes.
+#[derive(Clone)]
+pub struct PiiStore {
+    db: Arc<dyn Database>,
+    fingerprint_key: Arc<Vec<u8>>,
+}
+
+impl PiiStore {
+    const INDEX_NAME: &'static str = "pii_openings";
+
+    /// Opens persistent store at `path`.
+    pub fn open(path: impl AsRef<Path>, fingerprint_key: Vec<u8>) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            !fingerprint_key.is_empty(),
+            "Key of pass code fingerprints is empty"
+        );
+        let db = RocksDB::open(path, &DbOptions::default())?;
+        Ok(Self {
+            db: Arc::new(db),
+            fingerprint_key: Arc::new(fingerprint_key),
+        })
+    }
+
+    /// Creates store with random fingerprint key, which is dropped with the node, for tests.
+    pub fn temporary() -> Self {
+        let mut fingerprint_key = vec![0; 32];
+        rand::thread_rng().fill_bytes(&mut fingerprint_key);
+        Self {
+            db: Arc::new(TemporaryDB::new()),
+            fingerprint_key: Arc::new(fingerprint_key),
+        }
+    }
+
+    /// Calculates fingerprint of pass code with the key of this store.
+    pub fn pass_code_fingerprint(&self, pass_code: &str) -> Hash {
+        pii::pass_code_fingerprint(&self.fingerprint_key, pass_code)
+    }
+
+    pub fn get(&self, commitment: &Hash) -> Option<PiiOpening> {
+        let snapshot = self.db.snapshot();
+        snapshot
+            .as_ref()
+            .get_map::<_, Hash, PiiOpening>(Self::INDEX_NAME)
+            .get(commitment)
+    }
+
+    pub fn put(&self, openings: impl IntoIterator<Item = PiiOpening>) -> anyhow::Result<()> {
+        let fork = self.db.fork();
+        {
+            let mut index = fork.get_map::<_, Hash, PiiOpening>(Self::INDEX_NAME);
+            for opening in openings {
+                index.put(&opening.commitment(), opening);
+            }
+        }
+        self.db.merge(fork.into_patch())?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for PiiStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PiiStore").finish()
+    }
+}
@@ -1,6 +1,6 @@
 #![allow(bare_trait_objects)]
 
-pub use self::{geo::*, service::*, wrappers::*};
+pub use self::{geo::*, legacy::*, service::*, wrappers::*};
 
 include!(concat!(env!("OUT_DIR"), "/protobuf_mod.rs"));
 
@@ -0,0 +1,55 @@
+use exonum::crypto::Hash;
+use exonum_merkledb::{access::Access, BinaryValue};
+
+use crate::{
+    model::{eligibility::EligibilityContext, AdministrationAddress, Participant},
+    schema::Schema,
+};
+
+/// Eligibility facts about participant, backed by the database schema.
+#[derive(Debug)]
+pub(super) struct ParticipantEligibility<'a, T: Access> {
+    schema: &'a Schema<T>,
+    participant: &'a Participant,
+}
+
+impl<'a, T: Access> ParticipantEligibility<'a, T> {
+    pub(super) fn new(schema: &'a Schema<T>, participant: &'a Participant) -> Self {
+        Self {
+            schema,
+            participant,
+        }
+    }
+}
+
+impl<T: Access> EligibilityContext for ParticipantEligibility<'_, T> {
+    fn attribute(&self, key: &str) -> Option<&str> {
+        self.participant.attribute(key)
+    }
+
+    fn resides_within(&self, administration: &Hash) -> bool {
+        // Expression may refer to administration by its address before succession.
+        let target = match AdministrationAddress::from_bytes(administration.as_ref().into())
+            .ok()
+            .and_then(|addr| self.schema.current_administration_address(&addr))
+        {
+            Some(target) => target,
+            None => return false,
+        };
+        self.participant
+            .residence
+            .0
+            .and_then(|residence| self.schema.current_administration_address(&residence))
+            .and_then(|residence| self.schema.iter_principals_from_current(&residence))
+            .map_or(false, |mut principals| {
+                principals.any(|principal| principal.addr == target)
+            })
+    }
+
+    fn is_on_roll(&self, roll_id: &Hash) -> bool {
+        self.schema
+            .voter_rolls
+            .get(roll_id)
+            .contains(&self.participant.addr)
+    }
+}
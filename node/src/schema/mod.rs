@@ -3,18 +3,38 @@ use std::{collections::HashMap, ops::RangeBounds};
 use chrono::{DateTime, Duration, Utc};
 
 use exonum::{
-    crypto::Hash,
+    crypto::{Hash, PublicKey},
     merkledb::{
-        access::{Access, FromAccess, RawAccessMut},
-        Entry, Group, ObjectHash, ProofListIndex, ProofMapIndex, RawProofMapIndex,
+        access::{Access, FromAccess, RawAccess, RawAccessMut},
+        BinaryValue, Entry, Group, KeySetIndex, ObjectHash, ProofListIndex, ProofMapIndex,
+        RawProofMapIndex,
     },
+    runtime::CallerAddress as Address,
 };
 use exonum_derive::{FromAccess, RequireArtifact};
 
-use crate::model::{
-    geo,
-    transactions::{Config, CreateParticipant},
-    wrappers, *,
+use crate::{
+    crypto::{
+        ballot::{self, EncryptedBallot, PartialDecryption},
+        blind::{BlindChallenge, BlindResponse, NonceCommitment},
+        dkg::Dealing,
+        elgamal::EncryptionKey,
+        mixnet::{self, BallotList, MixnetBallot},
+        ring::RingKey,
+    },
+    model::{
+        contact::{self, ContactChallenge},
+        encryption::{key_generation_phase, ElectionEncryption, KeyGenerationPhase, TransportKey},
+        geo,
+        invitation::Invitation,
+        mixnet::{mix_step_timeout, ElectionMixing, INVALID_VALUE, MAX_BALLOT_VALUE},
+        multisig::{Proposal, SignerSet},
+        pii::PiiField,
+        recovery::{Guardians, Recovery},
+        transactions::{AttributeValue, Config, CreateParticipant, IssueElection},
+        voting_token::TokenIssuance,
+        wrappers, *,
+    },
 };
 
 pub use administration_repository::AdministrationRepository;
@@ -22,6 +42,7 @@ pub use election_repository::ElectionRepository;
 pub use participant_repository::ParticipantRepository;
 pub use repository::Repository;
 
+mod eligibility;
 mod iter;
 
 mod administration_repository;
@@ -29,13 +50,6 @@ mod election_repository;
 mod participant_repository;
 mod repository;
 
-binary_value_tuple_impls! {
-    #[derive(Debug)]
-    pub TupleContainer {
-        (DateTime<Utc>, AdministrationAddress),
-    }
-}
-
 /// Database schema for elections.
 #[derive(Debug, FromAccess)]
 pub(crate) struct SchemaImpl<T: Access> {
@@ -52,7 +66,6 @@ pub(crate) struct SchemaImpl<T: Access> {
     pub election_history: Group<T, ElectionAddress, ProofListIndex<T::Base, Hash>>,
 }
 
-type TimePositionInfo = TupleContainer<(DateTime<Utc>, AdministrationAddress)>;
 pub(crate) type IndexPair<A, K, V, KeyMode> = (
     ProofMapIndex<<A as Access>::Base, K, V, KeyMode>,
     Group<A, K, ProofListIndex<<A as Access>::Base, Hash>>,
@@ -61,9 +74,45 @@ pub(crate) type IndexPair<A, K, V, KeyMode> = (
 #[derive(Debug, FromAccess, RequireArtifact)]
 pub struct Schema<T: Access> {
     pub participants: RawProofMapIndex<T::Base, ParticipantAddress, Participant>,
+    /// Addresses which participants have rotated their keys to, keyed by the old address.
+    pub participant_redirects: RawProofMapIndex<T::Base, ParticipantAddress, ParticipantAddress>,
+    /// Guardians nominated by participants for recovery of their accounts.
+    pub participant_guardians: RawProofMapIndex<T::Base, ParticipantAddress, Guardians>,
+    /// Pending recoveries of participant accounts.
+    pub participant_recoveries: RawProofMapIndex<T::Base, ParticipantAddress, Recovery>,
+    /// Participants owning specific pass code fingerprints.
+    pub pass_code_owners: RawProofMapIndex<T::Base, Hash, ParticipantAddress>,
+    /// Registration invitations keyed by the public key derived from the code.
+    pub invitations: RawProofMapIndex<T::Base, PublicKey, Invitation>,
+    /// Keys of invitations issued by specific administrations.
+    pub administration_invitations:
+        Group<T, AdministrationAddress, ProofListIndex<T::Base, PublicKey>>,
+    /// Invitations redeemed by specific participants.
+    pub participant_invitations: RawProofMapIndex<T::Base, ParticipantAddress, PublicKey>,
+    /// Pending contact verifications keyed by `contact::challenge_id`.
+    pub contact_challenges: RawProofMapIndex<T::Base, Hash, ContactChallenge>,
+    /// Locations submitted by specific participants.
     pub participant_location_history:
-        Group<T, ParticipantAddress, ProofListIndex<T::Base, TimePositionInfo>>,
+        Group<T, ParticipantAddress, ProofListIndex<T::Base, LocationRecord>>,
     pub administrations: RawProofMapIndex<T::Base, AdministrationAddress, Administration>,
+    /// Administrations without principal.
+    pub root_administrations: KeySetIndex<T::Base, AdministrationAddress>,
+    /// Direct subordinates of specific administrations.
+    pub administration_children:
+        Group<T, AdministrationAddress, KeySetIndex<T::Base, AdministrationAddress>>,
+    /// Bounding boxes of administration areas, checked before the areas themselves while
+    /// descending the administration tree.
+    pub administration_bounds: RawProofMapIndex<T::Base, AdministrationAddress, geo::BoundingBox>,
+    /// Successors of administrations, keyed by the former address. References held by
+    /// participants, such as residence and location history, are resolved through it.
+    pub administration_redirects:
+        RawProofMapIndex<T::Base, AdministrationAddress, AdministrationAddress>,
+    /// Signers of multi-signature administrations.
+    pub administration_signers: RawProofMapIndex<T::Base, AdministrationAddress, SignerSet>,
+    /// Proposals of multi-signature administrations keyed by hash of proposing transaction.
+    pub proposals: RawProofMapIndex<T::Base, Hash, Proposal>,
+    /// Proposals of specific administrations.
+    pub administration_proposals: Group<T, AdministrationAddress, ProofListIndex<T::Base, Hash>>,
     pub elections: RawProofMapIndex<T::Base, ElectionAddress, Election>,
     /// Elections of specific administrations.
     pub administration_elections:
@@ -73,6 +122,66 @@ pub struct Schema<T: Access> {
         ElectionAddress,
         RawProofMapIndex<T::Base, ParticipantAddress, ElectionOptionAddress>,
     >,
+    /// Elections which specific participants have cast ballots, commitments, voting token
+    /// requests or ring keys in.
+    pub participant_elections: Group<T, ParticipantAddress, KeySetIndex<T::Base, ElectionAddress>>,
+    /// Root hashes of `election_votes` members. Group members are not aggregated into the
+    /// state hash, so vote receipts are proven through this index.
+    pub election_vote_roots: RawProofMapIndex<T::Base, ElectionAddress, Hash>,
+    /// Counted ballots of elections, updated on every counted ballot. Secret ballot
+    /// elections get their tally on decryption.
+    pub election_tallies: RawProofMapIndex<T::Base, ElectionAddress, ElectionTally>,
+    /// Ballot commitments of `BallotMode::CommitReveal` elections.
+    pub election_commitments:
+        Group<T, ElectionAddress, RawProofMapIndex<T::Base, ParticipantAddress, Hash>>,
+    /// Administrations maintaining specific voter rolls.
+    pub voter_roll_owners: RawProofMapIndex<T::Base, Hash, AdministrationAddress>,
+    /// Participants enrolled to specific voter rolls.
+    pub voter_rolls: Group<T, Hash, KeySetIndex<T::Base, ParticipantAddress>>,
+    /// Voter rolls which specific participants are enrolled to.
+    pub participant_voter_rolls: Group<T, ParticipantAddress, KeySetIndex<T::Base, Hash>>,
+    /// Key generation and tallying state of `BallotMode::Encrypted` elections.
+    pub election_encryption: RawProofMapIndex<T::Base, ElectionAddress, ElectionEncryption>,
+    /// Encrypted elections which election key is being generated for.
+    pub pending_key_generation: KeySetIndex<T::Base, ElectionAddress>,
+    /// Transport keys published by trustees of encrypted elections.
+    pub election_transport_keys:
+        Group<T, ElectionAddress, RawProofMapIndex<T::Base, PublicKey, TransportKey>>,
+    /// Dealings published by trustees of encrypted elections.
+    pub election_dealings: Group<T, ElectionAddress, RawProofMapIndex<T::Base, PublicKey, Dealing>>,
+    /// Trustees which have acknowledged dealings of encrypted elections.
+    pub election_acknowledgements: Group<T, ElectionAddress, KeySetIndex<T::Base, PublicKey>>,
+    /// Dealers disqualified by valid complaints of trustees.
+    pub election_disqualified: Group<T, ElectionAddress, KeySetIndex<T::Base, PublicKey>>,
+    /// Tally decryption shares published by trustees of encrypted elections.
+    pub election_decryptions:
+        Group<T, ElectionAddress, RawProofMapIndex<T::Base, PublicKey, PartialDecryption>>,
+    /// Encrypted ballots of `BallotMode::Encrypted` elections.
+    pub encrypted_ballots:
+        Group<T, ElectionAddress, RawProofMapIndex<T::Base, ParticipantAddress, EncryptedBallot>>,
+    /// Voting token signing sessions of `BallotMode::Anonymous` elections.
+    pub token_issuance:
+        Group<T, ElectionAddress, RawProofMapIndex<T::Base, ParticipantAddress, TokenIssuance>>,
+    /// Participants whose voting token signing session is open in specific elections.
+    pub open_token_sessions: RawProofMapIndex<T::Base, ElectionAddress, ParticipantAddress>,
+    /// Options selected with spent voting tokens, keyed by address of voting key.
+    pub spent_voting_tokens:
+        Group<T, ElectionAddress, RawProofMapIndex<T::Base, Address, ElectionOptionAddress>>,
+    /// Ring keys of participants registered for `BallotMode::Ring` elections.
+    pub ring_keys:
+        Group<T, ElectionAddress, RawProofMapIndex<T::Base, ParticipantAddress, RingKey>>,
+    /// Options selected by ring ballots, keyed by hash of key image.
+    pub spent_key_images:
+        Group<T, ElectionAddress, RawProofMapIndex<T::Base, Hash, ElectionOptionAddress>>,
+    /// Shuffling and decryption state of `BallotMode::Mixnet` elections.
+    pub election_mixing: RawProofMapIndex<T::Base, ElectionAddress, ElectionMixing>,
+    /// Mixnet elections which ballots are being shuffled.
+    pub pending_mixing: KeySetIndex<T::Base, ElectionAddress>,
+    /// Encrypted ballots of `BallotMode::Mixnet` elections.
+    pub mixnet_ballots:
+        Group<T, ElectionAddress, RawProofMapIndex<T::Base, ParticipantAddress, MixnetBallot>>,
+    /// Outputs of shuffles published by trustees of mixnet elections.
+    pub mix_outputs: Group<T, ElectionAddress, ProofListIndex<T::Base, BallotList>>,
 }
 
 impl<T: Access> SchemaImpl<T> {
@@ -191,11 +300,196 @@ impl<T: Access> Schema<T> {
         })
     }
 
+    /// Current address of the residence administration of participant.
+    pub fn residence_administration(
+        &self,
+        participant: &Participant,
+    ) -> Option<AdministrationAddress> {
+        participant
+            .residence
+            .0
+            .and_then(|residence| self.current_administration_address(&residence))
+    }
+
+    /// Checks whether `administration` is the residence administration of participant
+    /// or any of its principals.
+    pub fn is_residence_authority(
+        &self,
+        participant: &Participant,
+        administration: &AdministrationAddress,
+    ) -> bool {
+        self.residence_administration(participant)
+            .and_then(|residence| self.iter_principals_from_current(&residence))
+            .map_or(false, |mut principals| {
+                principals.any(|principal| principal.addr == *administration)
+            })
+    }
+
+    /// Administrations which areas contain the point, from the most specific one to the
+    /// root. Descends the administration tree, so only areas of the containing
+    /// administrations and of their siblings are checked.
+    pub fn containing_administrations(
+        &self,
+        point: &geo::Coordinate,
+    ) -> Vec<AdministrationAddress> {
+        let mut chain = Vec::new();
+        let mut candidates: Vec<_> = self.root_administrations.iter().collect();
+        while let Some(addr) = self.find_containing(&candidates, point) {
+            chain.push(addr);
+            candidates = self.administration_children.get(&addr).iter().collect();
+        }
+        chain.reverse();
+        chain
+    }
+
+    /// Subordinates of administration, or root administrations if `principal` is `None`.
+    pub fn subordinates(
+        &self,
+        principal: Option<&AdministrationAddress>,
+    ) -> Vec<AdministrationAddress> {
+        match principal {
+            Some(principal) => self.administration_children.get(principal).iter().collect(),
+            None => self.root_administrations.iter().collect(),
+        }
+    }
+
+    fn find_containing(
+        &self,
+        candidates: &[AdministrationAddress],
+        point: &geo::Coordinate,
+    ) -> Option<AdministrationAddress> {
+        candidates.iter().copied().find(|addr| {
+            self.administration_bounds
+                .get(addr)
+                .map_or(false, |bounds| bounds.contains(point))
+                && self
+                    .administrations
+                    .get(addr)
+                    .map_or(false, |administration| {
+                        administration.area.locate(point) == geo::PointLocation::Inside
+                    })
+        })
+    }
+
+    /// Follows key rotations from `addr` to the current address of participant.
+    pub fn current_participant_address(
+        &self,
+        addr: &ParticipantAddress,
+    ) -> Option<ParticipantAddress> {
+        follow_redirects(&self.participant_redirects, &self.participants, addr)
+    }
+
+    /// Follows successions from `addr` to the current address of administration.
+    pub fn current_administration_address(
+        &self,
+        addr: &AdministrationAddress,
+    ) -> Option<AdministrationAddress> {
+        follow_redirects(&self.administration_redirects, &self.administrations, addr)
+    }
+
+    /// Evaluates eligibility expression of election for given participant.
+    pub fn is_eligible(&self, election: &Election, participant: &Participant) -> bool {
+        election
+            .eligibility_rule()
+            .evaluate(&eligibility::ParticipantEligibility::new(self, participant))
+    }
+
     pub fn voted_yet(&self, election_addr: &ElectionAddress, address: &ParticipantAddress) -> bool {
-        self.election_votes
-            .get(election_addr)
-            .get(address)
-            .is_some()
+        self.election_votes.get(election_addr).contains(address)
+            || self
+                .election_commitments
+                .get(election_addr)
+                .contains(address)
+            || self.encrypted_ballots.get(election_addr).contains(address)
+            || self.mixnet_ballots.get(election_addr).contains(address)
+    }
+
+    /// Selects participants which have committed their ballots but have not revealed them.
+    pub fn unrevealed_commitments(
+        &self,
+        election_id: &ElectionAddress,
+    ) -> Option<Vec<ParticipantAddress>> {
+        self.elections.get(election_id).map(|_| {
+            let votes = self.election_votes.get(election_id);
+            self.election_commitments
+                .get(election_id)
+                .keys()
+                .filter(|participant| !votes.contains(participant))
+                .collect()
+        })
+    }
+
+    /// Selects ring keys of the election electorate in the order of participant addresses.
+    pub fn election_ring(&self, election_id: &ElectionAddress) -> Vec<RingKey> {
+        self.ring_keys.get(election_id).values().collect()
+    }
+
+    /// Determines phase of election key generation at `now`.
+    pub fn key_generation_phase(
+        &self,
+        election_id: &ElectionAddress,
+        encryption: &ElectionEncryption,
+        now: DateTime<Utc>,
+    ) -> KeyGenerationPhase {
+        encryption.key_generation_phase(
+            now,
+            self.election_transport_keys.get(election_id).iter().count(),
+            self.election_dealings.get(election_id).iter().count(),
+            self.election_acknowledgements
+                .get(election_id)
+                .iter()
+                .count(),
+        )
+    }
+
+    /// Selects dealings of trustees which have not been disqualified, together with
+    /// transport keys of their dealers.
+    pub fn qualified_dealings(
+        &self,
+        election_id: &ElectionAddress,
+    ) -> Vec<(PublicKey, TransportKey, Dealing)> {
+        let transport_keys = self.election_transport_keys.get(election_id);
+        let disqualified = self.election_disqualified.get(election_id);
+        self.election_dealings
+            .get(election_id)
+            .iter()
+            .filter(|(dealer, _)| !disqualified.contains(dealer))
+            .map(|(dealer, dealing)| {
+                let key = transport_keys.get(&dealer).unwrap();
+                (dealer, key, dealing)
+            })
+            .collect()
+    }
+
+    /// Selects ballots to be shuffled next: output of the last shuffle or, before the first
+    /// shuffle, cast ballots in the order of participant addresses.
+    pub fn mix_input(&self, election_id: &ElectionAddress) -> Option<BallotList> {
+        let election = self.elections.get(election_id)?;
+        let input = self
+            .mix_outputs
+            .get(election_id)
+            .last()
+            .unwrap_or_else(|| BallotList {
+                width: election.ballot_length as usize,
+                ballots: self
+                    .mixnet_ballots
+                    .get(election_id)
+                    .values()
+                    .map(|ballot| ballot.ciphertexts)
+                    .collect(),
+            });
+        Some(input)
+    }
+
+    /// Selects decrypted ballots of mixnet election in the order of the last shuffle.
+    pub fn mixnet_results(&self, election_id: &ElectionAddress) -> Option<Vec<Vec<u32>>> {
+        let election = self.elections.get(election_id)?;
+        self.election_encryption
+            .get(election_id)
+            .filter(|encryption| encryption.is_decrypted)?;
+        let mixing = self.election_mixing.get(election_id)?;
+        let width = election.ballot_length as usize;
+        Some(mixing.results.chunks(width).map(<[_]>::to_vec).collect())
     }
 
     pub fn available_elections<'a>(
@@ -211,23 +505,18 @@ impl<T: Access> Schema<T> {
         })
     }
 
+    /// Selects counted votes of election.
+    ///
+    /// Encrypted and mixnet elections have no results until their ballots are decrypted.
+    /// Mixnet elections count first values of decrypted ballots, which are first
+    /// preferences of ranked ballots.
     pub fn election_results(
         &self,
         election_id: &ElectionAddress,
     ) -> Option<HashMap<ElectionOptionAddress, u32>> {
-        self.elections.get(election_id).map(|e| {
-            let mut sum: HashMap<ElectionOptionAddress, u32> =
-                e.options.iter().map(|o| (o.id, 0)).collect();
-            self.election_votes
-                .get(election_id)
-                .iter()
-                .for_each(|(_, v)| {
-                    if let Some(counter) = sum.get_mut(&v) {
-                        *counter += 1;
-                    }
-                });
-            sum
-        })
+        self.election_tallies
+            .get(election_id)
+            .map(|tally| tally.to_map())
     }
 
     pub fn suggested_administrations_for<'a>(
@@ -242,16 +531,12 @@ impl<T: Access> Schema<T> {
                     .participant_location_history
                     .get(&participant.addr)
                     .iter()
-                    .skip_while(|loc| (loc.0).0 < month_ago)
-                    .map(|loc| (loc.0).1)
+                    .skip_while(|loc| loc.date < month_ago)
+                    .map(|loc| loc.administration())
                     .collect();
                 let last_locations = locations_for_last_month.into_iter().rev().take(30);
 
-                let root_administration_addresses = self
-                    .administrations
-                    .values()
-                    .filter(|adm| adm.principal_key.0.is_none())
-                    .map(|adm| adm.addr);
+                let root_administration_addresses = self.root_administrations.iter();
 
                 last_locations
                     .chain(participant.residence.0)
@@ -260,7 +545,7 @@ impl<T: Access> Schema<T> {
 
             let mut picked_administrations: HashMap<AdministrationAddress, u32> = HashMap::new();
 
-            for addr in locations {
+            for addr in locations.filter_map(|addr| self.current_administration_address(&addr)) {
                 match picked_administrations.get_mut(&addr) {
                     Some(counter) => *counter += 1,
                     None => {
@@ -287,14 +572,24 @@ where
         &mut self,
         key: &ParticipantAddress,
         participant: CreateParticipant,
+        invitation: Option<&PublicKey>,
         transaction: &Hash,
     ) {
-        let participant = {
+        let mut participant = {
             let mut history = self.participant_history.get(key);
             history.push(*transaction);
             let history_hash = history.object_hash();
             Participant::from_transaction(key, participant, history.len(), &history_hash)
         };
+        if let Some(invitation_key) = invitation {
+            let mut invitation = self.public.invitations.get(invitation_key).unwrap();
+            invitation.used_by = Some(*key).into();
+            participant.residence = Some(invitation.issuer).into();
+            self.public.invitations.put(invitation_key, invitation);
+            self.public
+                .participant_invitations
+                .put(key, *invitation_key);
+        }
         self.public.participants.put(key, participant);
     }
 
@@ -302,13 +597,16 @@ where
         &mut self,
         participant_addr: &ParticipantAddress,
         date: DateTime<Utc>,
-        &location: &AdministrationAddress,
+        administrations: Vec<AdministrationAddress>,
         transaction: &Hash,
     ) {
         self.public
             .participant_location_history
             .get(participant_addr)
-            .push((date, location).into());
+            .push(LocationRecord {
+                date,
+                administrations,
+            });
         let participant = {
             let mut history = self.participant_history.get(&participant_addr);
             history.push(*transaction);
@@ -323,15 +621,477 @@ where
         };
         self.public.participants.put(&participant_addr, participant);
     }
+
+    pub fn set_participant_attributes(
+        &mut self,
+        participant_addr: &ParticipantAddress,
+        issuer: &AdministrationAddress,
+        attributes: &[AttributeValue],
+        transaction: &Hash,
+    ) {
+        let participant = {
+            let mut history = self.participant_history.get(participant_addr);
+            history.push(*transaction);
+
+            let history_hash = history.object_hash();
+            let mut participant = self.public.participants.get(participant_addr).unwrap();
+            for attribute in attributes {
+                participant.set_attribute(&attribute.key, &attribute.value, issuer);
+            }
+            Participant {
+                history_len: history.len(),
+                history_hash,
+                ..participant
+            }
+        };
+        self.public.participants.put(participant_addr, participant);
+    }
+
+    pub fn set_participant_status(
+        &mut self,
+        participant_addr: &ParticipantAddress,
+        status: ParticipantStatus,
+        transaction: &Hash,
+    ) {
+        let participant = {
+            let mut history = self.participant_history.get(participant_addr);
+            history.push(*transaction);
+
+            let history_hash = history.object_hash();
+            let participant = self.public.participants.get(participant_addr).unwrap();
+            Participant {
+                status,
+                history_len: history.len(),
+                history_hash,
+                ..participant
+            }
+        };
+        self.public.participants.put(participant_addr, participant);
+    }
+
+    pub fn attest_pass_code(
+        &mut self,
+        participant_addr: &ParticipantAddress,
+        fingerprint: Hash,
+        transaction: &Hash,
+    ) {
+        let participant = {
+            let mut history = self.participant_history.get(participant_addr);
+            history.push(*transaction);
+
+            let history_hash = history.object_hash();
+            let participant = self.public.participants.get(participant_addr).unwrap();
+            // Invited participant is verified as soon as its pass code is known to be unique.
+            let status = if participant.status == ParticipantStatus::Pending
+                && self
+                    .public
+                    .participant_invitations
+                    .contains(participant_addr)
+            {
+                ParticipantStatus::Verified
+            } else {
+                participant.status
+            };
+            Participant {
+                pass_code_fingerprint: fingerprint,
+                status,
+                history_len: history.len(),
+                history_hash,
+                ..participant
+            }
+        };
+        self.public
+            .pass_code_owners
+            .put(&fingerprint, *participant_addr);
+        self.public.participants.put(participant_addr, participant);
+    }
+
+    /// Moves participant record, history, location history, voter roll membership and
+    /// ballots to the new address. Ring keys and mixnet ballots stay at the old address
+    /// once their order is fixed, because ring signatures and shuffles depend on it.
+    pub fn rotate_participant_key(
+        &mut self,
+        old_addr: &ParticipantAddress,
+        new_addr: &ParticipantAddress,
+        now: DateTime<Utc>,
+        transaction: &Hash,
+    ) {
+        let participant = {
+            let mut old_history = self.participant_history.get(old_addr);
+            let mut history = self.participant_history.get(new_addr);
+            history.extend(old_history.iter());
+            history.push(*transaction);
+            old_history.clear();
+
+            let history_hash = history.object_hash();
+            let participant = self.public.participants.get(old_addr).unwrap();
+            Participant {
+                addr: *new_addr,
+                history_len: history.len(),
+                history_hash,
+                ..participant
+            }
+        };
+        if participant.is_pass_code_attested() {
+            self.public
+                .pass_code_owners
+                .put(&participant.pass_code_fingerprint, *new_addr);
+        }
+        self.public.participants.remove(old_addr);
+        self.public.participants.put(new_addr, participant);
+        self.public.participant_redirects.put(old_addr, *new_addr);
+        move_entry(&mut self.public.participant_guardians, old_addr, new_addr);
+        self.public.participant_recoveries.remove(old_addr);
+
+        let mut old_locations = self.public.participant_location_history.get(old_addr);
+        self.public
+            .participant_location_history
+            .get(new_addr)
+            .extend(old_locations.iter());
+        old_locations.clear();
+
+        for field in &[PiiField::Email, PiiField::PhoneNumber] {
+            let old_id = contact::challenge_id(old_addr, *field);
+            if let Some(challenge) = self.public.contact_challenges.get(&old_id) {
+                self.public.contact_challenges.remove(&old_id);
+                self.public
+                    .contact_challenges
+                    .put(&contact::challenge_id(new_addr, *field), challenge);
+            }
+        }
+
+        if let Some(invitation_key) = self.public.participant_invitations.get(old_addr) {
+            let mut invitation = self.public.invitations.get(&invitation_key).unwrap();
+            invitation.used_by = Some(*new_addr).into();
+            self.public.invitations.put(&invitation_key, invitation);
+            move_entry(&mut self.public.participant_invitations, old_addr, new_addr);
+        }
+
+        let mut old_rolls = self.public.participant_voter_rolls.get(old_addr);
+        let mut rolls = self.public.participant_voter_rolls.get(new_addr);
+        for roll_id in old_rolls.iter() {
+            let mut roll = self.public.voter_rolls.get(&roll_id);
+            roll.remove(old_addr);
+            roll.insert(*new_addr);
+            rolls.insert(roll_id);
+        }
+        old_rolls.clear();
+
+        let mut old_elections = self.public.participant_elections.get(old_addr);
+        let mut elections = self.public.participant_elections.get(new_addr);
+        for election_id in old_elections.iter() {
+            elections.insert(election_id);
+            let election = self.public.elections.get(&election_id).unwrap();
+            let mut votes = self.public.election_votes.get(&election_id);
+            if move_entry(&mut votes, old_addr, new_addr) {
+                self.public
+                    .election_vote_roots
+                    .put(&election_id, votes.object_hash());
+            }
+            let public = &self.public;
+            move_entry(
+                &mut public.election_commitments.get(&election_id),
+                old_addr,
+                new_addr,
+            );
+            move_entry(
+                &mut public.encrypted_ballots.get(&election_id),
+                old_addr,
+                new_addr,
+            );
+            move_entry(
+                &mut public.token_issuance.get(&election_id),
+                old_addr,
+                new_addr,
+            );
+            if now < election.start_date {
+                move_entry(&mut public.ring_keys.get(&election_id), old_addr, new_addr);
+            }
+            if now < election.finish_date {
+                move_entry(
+                    &mut public.mixnet_ballots.get(&election_id),
+                    old_addr,
+                    new_addr,
+                );
+            }
+            if self.public.open_token_sessions.get(&election_id) == Some(*old_addr) {
+                self.public.open_token_sessions.put(&election_id, *new_addr);
+            }
+        }
+        old_elections.clear();
+    }
+
+    /// Replaces guardians of participant and drops recovery approved by the former ones.
+    pub fn set_guardians(
+        &mut self,
+        participant_addr: &ParticipantAddress,
+        guardians: Guardians,
+        transaction: &Hash,
+    ) {
+        self.public
+            .participant_guardians
+            .put(participant_addr, guardians);
+        self.cancel_recovery(participant_addr, transaction);
+    }
+
+    pub fn approve_recovery(
+        &mut self,
+        participant_addr: &ParticipantAddress,
+        guardian: &Address,
+        new_key: PublicKey,
+        now: DateTime<Utc>,
+    ) {
+        let mut recovery = match self.public.participant_recoveries.get(participant_addr) {
+            Some(recovery) => recovery,
+            None => {
+                let guardians = self
+                    .public
+                    .participant_guardians
+                    .get(participant_addr)
+                    .unwrap();
+                Recovery::new(guardians.threshold, now)
+            }
+        };
+        recovery.approve(guardian, new_key, now);
+        self.public
+            .participant_recoveries
+            .put(participant_addr, recovery);
+    }
+
+    pub fn cancel_recovery(&mut self, participant_addr: &ParticipantAddress, transaction: &Hash) {
+        self.public.participant_recoveries.remove(participant_addr);
+
+        let participant = {
+            let mut history = self.participant_history.get(participant_addr);
+            history.push(*transaction);
+
+            let history_hash = history.object_hash();
+            let participant = self.public.participants.get(participant_addr).unwrap();
+            Participant {
+                history_len: history.len(),
+                history_hash,
+                ..participant
+            }
+        };
+        self.public.participants.put(participant_addr, participant);
+    }
+
+    pub fn publish_contact_challenge(
+        &mut self,
+        participant_addr: &ParticipantAddress,
+        field: PiiField,
+        challenge: Hash,
+        expires_at: DateTime<Utc>,
+    ) {
+        let challenge = ContactChallenge {
+            challenge,
+            expires_at,
+            participant: *participant_addr,
+        };
+        self.public
+            .contact_challenges
+            .put(&contact::challenge_id(participant_addr, field), challenge);
+    }
+
+    pub fn confirm_contact(
+        &mut self,
+        participant_addr: &ParticipantAddress,
+        field: PiiField,
+        transaction: &Hash,
+    ) {
+        self.public
+            .contact_challenges
+            .remove(&contact::challenge_id(participant_addr, field));
+
+        let participant = {
+            let mut history = self.participant_history.get(participant_addr);
+            history.push(*transaction);
+
+            let history_hash = history.object_hash();
+            let mut participant = self.public.participants.get(participant_addr).unwrap();
+            participant.set_contact_verified(field);
+            Participant {
+                history_len: history.len(),
+                history_hash,
+                ..participant
+            }
+        };
+        self.public.participants.put(participant_addr, participant);
+    }
     //endregion
 
     //#region Administrations
+    pub fn update_voter_roll(
+        &mut self,
+        roll_id: &Hash,
+        owner: &AdministrationAddress,
+        added: &[ParticipantAddress],
+        removed: &[ParticipantAddress],
+    ) {
+        self.public.voter_roll_owners.put(roll_id, *owner);
+
+        let mut roll = self.public.voter_rolls.get(roll_id);
+        for participant in removed {
+            roll.remove(participant);
+            self.public
+                .participant_voter_rolls
+                .get(participant)
+                .remove(roll_id);
+        }
+        for participant in added {
+            roll.insert(*participant);
+            self.public
+                .participant_voter_rolls
+                .get(participant)
+                .insert(*roll_id);
+        }
+    }
+
+    pub fn issue_invitations(
+        &mut self,
+        issuer: &AdministrationAddress,
+        keys: &[PublicKey],
+        expires_at: DateTime<Utc>,
+    ) {
+        let mut issued = self.public.administration_invitations.get(issuer);
+        for key in keys {
+            let invitation = Invitation {
+                issuer: *issuer,
+                expires_at,
+                used_by: None.into(),
+            };
+            self.public.invitations.put(key, invitation);
+            issued.push(*key);
+        }
+    }
+
+    /// Moves administration to the new address. Administration record, history, elections
+    /// and invitations are moved and principal keys of subordinates are rewritten, other
+    /// references are resolved through `administration_redirects`.
+    pub fn succeed_administration(
+        &mut self,
+        old_addr: &AdministrationAddress,
+        new_addr: &AdministrationAddress,
+        transaction: &Hash,
+    ) {
+        let administration = {
+            let mut old_history = self.administration_history.get(old_addr);
+            let mut history = self.administration_history.get(new_addr);
+            history.extend(old_history.iter());
+            history.push(*transaction);
+            old_history.clear();
+
+            let history_hash = history.object_hash();
+            let administration = self.public.administrations.get(old_addr).unwrap();
+            Administration {
+                addr: *new_addr,
+                history_len: history.len(),
+                history_hash,
+                ..administration
+            }
+        };
+        let administration_principal = administration.principal_key.0;
+        self.public.administrations.remove(old_addr);
+        self.public.administrations.put(new_addr, administration);
+        self.public
+            .administration_redirects
+            .put(old_addr, *new_addr);
+
+        match administration_principal {
+            Some(principal) => {
+                let mut siblings = self.public.administration_children.get(&principal);
+                siblings.remove(old_addr);
+                siblings.insert(*new_addr);
+            }
+            None => {
+                self.public.root_administrations.remove(old_addr);
+                self.public.root_administrations.insert(*new_addr);
+            }
+        }
+        move_entry(&mut self.public.administration_bounds, old_addr, new_addr);
+
+        let mut old_children = self.public.administration_children.get(old_addr);
+        let mut children = self.public.administration_children.get(new_addr);
+        for addr in old_children.iter() {
+            let subordinate = self.public.administrations.get(&addr).unwrap();
+            let subordinate = Administration {
+                principal_key: Some(*new_addr).into(),
+                ..subordinate
+            };
+            self.public.administrations.put(&addr, subordinate);
+            children.insert(addr);
+        }
+        old_children.clear();
+
+        let mut old_elections = self.public.administration_elections.get(old_addr);
+        let mut elections = self.public.administration_elections.get(new_addr);
+        for election_id in old_elections.iter() {
+            let election = self.public.elections.get(&election_id).unwrap();
+            let election = Election {
+                issuer: *new_addr,
+                ..election
+            };
+            self.public.elections.put(&election_id, election);
+            elections.push(election_id);
+        }
+        old_elections.clear();
+
+        let mut old_issued = self.public.administration_invitations.get(old_addr);
+        let mut issued = self.public.administration_invitations.get(new_addr);
+        for key in old_issued.iter() {
+            let invitation = self.public.invitations.get(&key).unwrap();
+            let invitation = Invitation {
+                issuer: *new_addr,
+                ..invitation
+            };
+            self.public.invitations.put(&key, invitation);
+            issued.push(key);
+        }
+        old_issued.clear();
+
+        move_entry(&mut self.public.administration_signers, old_addr, new_addr);
+        let mut old_proposals = self.public.administration_proposals.get(old_addr);
+        let mut proposals = self.public.administration_proposals.get(new_addr);
+        for proposal_id in old_proposals.iter() {
+            let proposal = self.public.proposals.get(&proposal_id).unwrap();
+            let proposal = Proposal {
+                administration: *new_addr,
+                ..proposal
+            };
+            self.public.proposals.put(&proposal_id, proposal);
+            proposals.push(proposal_id);
+        }
+        old_proposals.clear();
+    }
+
+    pub fn set_signers(&mut self, administration: &AdministrationAddress, signers: SignerSet) {
+        self.public
+            .administration_signers
+            .put(administration, signers);
+    }
+
+    pub fn propose_action(&mut self, proposal_id: &Hash, proposal: Proposal) {
+        self.public
+            .administration_proposals
+            .get(&proposal.administration)
+            .push(*proposal_id);
+        self.public.proposals.put(proposal_id, proposal);
+    }
+
+    /// Adds approval of signer and marks proposal executed if `is_executed`.
+    pub fn approve_proposal(&mut self, proposal_id: &Hash, signer: &Address, is_executed: bool) {
+        let mut proposal = self.public.proposals.get(proposal_id).unwrap();
+        proposal.approvals.push(*signer);
+        proposal.is_executed = is_executed;
+        self.public.proposals.put(proposal_id, proposal);
+    }
+
     pub fn create_administration(
         &mut self,
         addr: &AdministrationAddress,
         name: &str,
         principal: &wrappers::OptionalContainer<AdministrationAddress>,
-        area: &geo::Polygon,
+        area: &geo::MultiPolygon,
         transaction: &Hash,
     ) {
         let administration = {
@@ -360,6 +1120,17 @@ where
                 &history_hash,
             )
         };
+        match principal.0 {
+            Some(principal) => self
+                .public
+                .administration_children
+                .get(&principal)
+                .insert(*addr),
+            None => self.public.root_administrations.insert(*addr),
+        }
+        self.public
+            .administration_bounds
+            .put(addr, area.bounding_box());
         self.public.administrations.put(addr, administration);
     }
     //endregion
@@ -367,20 +1138,18 @@ where
     //#region Elections
     pub fn issue_election(
         &mut self,
-        name: &str,
-        election_address: ElectionAddress,
         author_key: &AdministrationAddress,
-        start_date: &DateTime<Utc>,
-        finish_date: &DateTime<Utc>,
-        options: &[String],
+        arg: &IssueElection,
         transaction: &Hash,
     ) {
+        let election_address = arg.addr;
         let election = {
             let mut history = self.election_history.get(&election_address);
             history.push(*transaction);
             let history_hash = history.object_hash();
 
-            let options: Vec<ElectionOption> = options
+            let options: Vec<ElectionOption> = arg
+                .options
                 .iter()
                 .scan(0, |counter, t| {
                     *counter += 1;
@@ -393,17 +1162,28 @@ where
 
             Election {
                 addr: election_address,
-                name: name.to_owned(),
+                name: arg.name.to_owned(),
                 issuer: *author_key,
-                start_date: *start_date,
-                finish_date: *finish_date,
+                start_date: arg.start_date,
+                finish_date: arg.finish_date,
                 options,
                 history_len: history.len(),
                 history_hash,
                 is_cancelled: false,
+                eligibility: arg.eligibility.clone(),
+                ballot_mode: arg.ballot_mode,
+                reveal_finish_date: arg.reveal_finish_date,
+                token_key: arg.token_key,
+                ballot_length: arg.ballot_length,
+                results_visibility: arg.results_visibility,
             }
         };
 
+        if arg.ballot_mode != BallotMode::Encrypted && arg.ballot_mode != BallotMode::Mixnet {
+            self.public
+                .election_tallies
+                .put(&election_address, ElectionTally::new(&election.options));
+        }
         self.public.elections.put(&election_address, election);
 
         self.public
@@ -412,6 +1192,18 @@ where
             .push(election_address);
     }
 
+    pub fn cancel_election(&mut self, election_id: &ElectionAddress, transaction: &Hash) {
+        self.push_election_history(election_id, transaction);
+        let election = self.public.elections.get(election_id).unwrap();
+        self.public.elections.put(
+            election_id,
+            Election {
+                is_cancelled: true,
+                ..election
+            },
+        );
+    }
+
     pub fn vote(
         &mut self,
         election_id: ElectionAddress,
@@ -419,22 +1211,446 @@ where
         option_id: i32,
         transaction: &Hash,
     ) {
-        let mut history = self.election_history.get(&election_id);
+        self.push_election_history(&election_id, transaction);
+        self.enter_election(&election_id, participant_key);
+        let mut votes = self.public.election_votes.get(&election_id);
+        votes.put(participant_key, option_id);
+        self.public
+            .election_vote_roots
+            .put(&election_id, votes.object_hash());
+        self.count_ballot(&election_id, option_id);
+    }
+
+    pub fn commit_vote(
+        &mut self,
+        election_id: ElectionAddress,
+        participant_key: &ParticipantAddress,
+        commitment: Hash,
+        transaction: &Hash,
+    ) {
+        self.push_election_history(&election_id, transaction);
+        self.enter_election(&election_id, participant_key);
+        self.public
+            .election_commitments
+            .get(&election_id)
+            .put(participant_key, commitment);
+    }
+
+    pub fn setup_encryption(
+        &mut self,
+        election_id: &ElectionAddress,
+        trustees: Vec<PublicKey>,
+        key_deadline: DateTime<Utc>,
+    ) {
+        let election = self.public.elections.get(election_id).unwrap();
+        let options_count = if election.ballot_mode == BallotMode::Mixnet {
+            // The first mixer gets its time once both the election and key generation
+            // are over.
+            let key_generated = key_deadline + key_generation_phase() * 2;
+            let step_deadline = election.finish_date.max(key_generated) + mix_step_timeout();
+            self.public
+                .election_mixing
+                .put(election_id, ElectionMixing::new(step_deadline));
+            self.public.pending_mixing.insert(election_id);
+            0
+        } else {
+            election.options.len()
+        };
+        self.public.election_encryption.put(
+            election_id,
+            ElectionEncryption::new(trustees, options_count, key_deadline),
+        );
+        self.public.pending_key_generation.insert(election_id);
+    }
+
+    pub fn publish_transport_key(
+        &mut self,
+        election_id: &ElectionAddress,
+        trustee: &PublicKey,
+        key: TransportKey,
+    ) {
+        self.public
+            .election_transport_keys
+            .get(election_id)
+            .put(trustee, key);
+    }
+
+    pub fn publish_dealing(
+        &mut self,
+        election_id: &ElectionAddress,
+        dealer: &PublicKey,
+        dealing: Dealing,
+    ) {
+        self.public
+            .election_dealings
+            .get(election_id)
+            .put(dealer, dealing);
+    }
+
+    pub fn acknowledge_dealings(
+        &mut self,
+        election_id: &ElectionAddress,
+        trustee: &PublicKey,
+        disqualified: impl IntoIterator<Item = PublicKey>,
+    ) {
+        self.public
+            .election_acknowledgements
+            .get(election_id)
+            .insert(trustee);
+        let mut index = self.public.election_disqualified.get(election_id);
+        for dealer in disqualified {
+            index.insert(&dealer);
+        }
+    }
+
+    /// Combines election key from dealings of qualified trustees. Key generation fails if
+    /// no trustee is qualified, so the election can not accept ballots.
+    pub fn finish_key_generation(&mut self, election_id: &ElectionAddress) {
+        self.public.pending_key_generation.remove(election_id);
+
+        let dealings = self.public.qualified_dealings(election_id);
+        if dealings.is_empty() {
+            self.public.pending_mixing.remove(election_id);
+            return;
+        }
+
+        let mut encryption = self.public.election_encryption.get(election_id).unwrap();
+        let key_shares: Vec<EncryptionKey> = dealings
+            .iter()
+            .map(|(_, _, dealing)| dealing.key_share())
+            .collect();
+        encryption.joint_key = EncryptionKey::combine(&key_shares);
+        encryption.share_keys = (0..encryption.trustees.len())
+            .map(|position| {
+                EncryptionKey(
+                    dealings
+                        .iter()
+                        .map(|(_, _, dealing)| dealing.share_key(position))
+                        .sum(),
+                )
+            })
+            .collect();
+        encryption.qualified = dealings.into_iter().map(|(dealer, _, _)| dealer).collect();
+        encryption.is_key_ready = true;
+        self.public.election_encryption.put(election_id, encryption);
+    }
+
+    pub fn cast_encrypted_vote(
+        &mut self,
+        election_id: ElectionAddress,
+        participant_key: &ParticipantAddress,
+        ballot: EncryptedBallot,
+        transaction: &Hash,
+    ) {
+        self.push_election_history(&election_id, transaction);
+
+        let mut encryption = self.public.election_encryption.get(&election_id).unwrap();
+        encryption.tally = encryption
+            .tally
+            .iter()
+            .zip(&ballot.choices)
+            .map(|(sum, choice)| *sum + *choice)
+            .collect();
+        encryption.ballots_count += 1;
+        self.public
+            .election_encryption
+            .put(&election_id, encryption);
+
+        self.enter_election(&election_id, participant_key);
+        self.public
+            .encrypted_ballots
+            .get(&election_id)
+            .put(participant_key, ballot);
+    }
+
+    pub fn publish_partial_decryption(
+        &mut self,
+        election_id: &ElectionAddress,
+        trustee: &PublicKey,
+        decryption: PartialDecryption,
+    ) {
+        let mut decryptions = self.public.election_decryptions.get(election_id);
+        decryptions.put(trustee, decryption);
+
+        let mut encryption = self.public.election_encryption.get(election_id).unwrap();
+        if decryptions.iter().count() == encryption.threshold as usize {
+            let decryptions: Vec<(usize, PartialDecryption)> = decryptions
+                .iter()
+                .map(|(trustee, decryption)| {
+                    (encryption.trustee_position(&trustee).unwrap(), decryption)
+                })
+                .collect();
+            let election = self.public.elections.get(election_id).unwrap();
+            let tally = if let Some(mut mixing) = self.public.election_mixing.get(election_id) {
+                let ciphertexts = self.public.mix_input(election_id).unwrap().ciphertexts();
+                mixing.results =
+                    mixnet::decrypt_ciphertexts(&ciphertexts, &decryptions, MAX_BALLOT_VALUE)
+                        .into_iter()
+                        .map(|value| value.map_or(INVALID_VALUE, |value| value as u32))
+                        .collect();
+
+                let mut tally = ElectionTally::new(&election.options);
+                let width = election.ballot_length as usize;
+                for first_value in mixing.results.iter().step_by(width) {
+                    if *first_value != INVALID_VALUE {
+                        tally.count(*first_value as ElectionOptionAddress);
+                    }
+                }
+                self.public.election_mixing.put(election_id, mixing);
+                tally
+            } else {
+                let results = ballot::decrypt_tally(
+                    &encryption.tally,
+                    &decryptions,
+                    u64::from(encryption.ballots_count),
+                )
+                .expect("Tally of verified ballots is always decryptable");
+                encryption.results = results.into_iter().map(|count| count as u32).collect();
+                ElectionTally::from_counts(&election.options, encryption.results.iter().copied())
+            };
+            self.public.election_tallies.put(election_id, tally);
+            encryption.is_decrypted = true;
+            self.public.election_encryption.put(election_id, encryption);
+        }
+    }
+
+    pub fn cast_mixnet_vote(
+        &mut self,
+        election_id: ElectionAddress,
+        participant_key: &ParticipantAddress,
+        ballot: MixnetBallot,
+        transaction: &Hash,
+    ) {
+        self.push_election_history(&election_id, transaction);
+
+        let mut encryption = self.public.election_encryption.get(&election_id).unwrap();
+        encryption.ballots_count += 1;
+        self.public
+            .election_encryption
+            .put(&election_id, encryption);
+
+        self.enter_election(&election_id, participant_key);
+        self.public
+            .mixnet_ballots
+            .get(&election_id)
+            .put(participant_key, ballot);
+    }
+
+    pub fn publish_shuffle(
+        &mut self,
+        election_id: &ElectionAddress,
+        output: BallotList,
+        now: DateTime<Utc>,
+    ) {
+        self.public.mix_outputs.get(election_id).push(output);
+        self.next_mix_step(election_id, None, now);
+    }
+
+    /// Skips mixer which has not shuffled ballots in time.
+    pub fn skip_mixer(
+        &mut self,
+        election_id: &ElectionAddress,
+        mixer: PublicKey,
+        now: DateTime<Utc>,
+    ) {
+        self.next_mix_step(election_id, Some(mixer), now);
+    }
+
+    fn next_mix_step(
+        &mut self,
+        election_id: &ElectionAddress,
+        skipped: Option<PublicKey>,
+        now: DateTime<Utc>,
+    ) {
+        let trustees_count = self
+            .public
+            .election_encryption
+            .get(election_id)
+            .unwrap()
+            .trustees
+            .len();
+        let mut mixing = self.public.election_mixing.get(election_id).unwrap();
+        mixing.mix_steps += 1;
+        mixing.skipped.extend(skipped);
+        mixing.step_deadline = now + mix_step_timeout();
+        if mixing.is_finished(trustees_count) {
+            self.public.pending_mixing.remove(election_id);
+        }
+        self.public.election_mixing.put(election_id, mixing);
+    }
+
+    pub fn offer_voting_token(
+        &mut self,
+        election_id: ElectionAddress,
+        participant_key: &ParticipantAddress,
+        commitment: NonceCommitment,
+        transaction: &Hash,
+    ) {
+        self.push_election_history(&election_id, transaction);
+        self.enter_election(&election_id, participant_key);
+        let mut sessions = self.public.token_issuance.get(&election_id);
+        // Unchallenged session is abandoned, its nonce is never answered.
+        if let Some(open) = self.public.open_token_sessions.get(&election_id) {
+            sessions.remove(&open);
+        }
+        sessions.put(participant_key, TokenIssuance::new(commitment));
+        self.public
+            .open_token_sessions
+            .put(&election_id, *participant_key);
+    }
+
+    pub fn request_voting_token(
+        &mut self,
+        election_id: ElectionAddress,
+        participant_key: &ParticipantAddress,
+        challenge: BlindChallenge,
+        transaction: &Hash,
+    ) {
+        self.push_election_history(&election_id, transaction);
+        let mut sessions = self.public.token_issuance.get(&election_id);
+        let session = sessions.get(participant_key).unwrap();
+        sessions.put(
+            participant_key,
+            TokenIssuance {
+                challenge: Some(challenge).into(),
+                ..session
+            },
+        );
+    }
+
+    pub fn issue_voting_token(
+        &mut self,
+        election_id: ElectionAddress,
+        participant_key: &ParticipantAddress,
+        response: BlindResponse,
+        transaction: &Hash,
+    ) {
+        self.push_election_history(&election_id, transaction);
+        let mut sessions = self.public.token_issuance.get(&election_id);
+        let session = sessions.get(participant_key).unwrap();
+        sessions.put(
+            participant_key,
+            TokenIssuance {
+                response: Some(response).into(),
+                ..session
+            },
+        );
+        self.public.open_token_sessions.remove(&election_id);
+    }
+
+    pub fn cast_anonymous_vote(
+        &mut self,
+        election_id: ElectionAddress,
+        voter: &Address,
+        option_id: ElectionOptionAddress,
+        transaction: &Hash,
+    ) {
+        self.push_election_history(&election_id, transaction);
+        self.public
+            .spent_voting_tokens
+            .get(&election_id)
+            .put(voter, option_id);
+        self.count_ballot(&election_id, option_id);
+    }
+
+    pub fn register_ring_key(
+        &mut self,
+        election_id: ElectionAddress,
+        participant_key: &ParticipantAddress,
+        key: RingKey,
+        transaction: &Hash,
+    ) {
+        self.push_election_history(&election_id, transaction);
+        self.enter_election(&election_id, participant_key);
+        self.public
+            .ring_keys
+            .get(&election_id)
+            .put(participant_key, key);
+    }
+
+    pub fn cast_ring_vote(
+        &mut self,
+        election_id: ElectionAddress,
+        key_image: Hash,
+        option_id: ElectionOptionAddress,
+        transaction: &Hash,
+    ) {
+        self.push_election_history(&election_id, transaction);
+        self.public
+            .spent_key_images
+            .get(&election_id)
+            .put(&key_image, option_id);
+        self.count_ballot(&election_id, option_id);
+    }
+
+    fn count_ballot(&mut self, election_id: &ElectionAddress, option_id: ElectionOptionAddress) {
+        let mut tally = self.public.election_tallies.get(election_id).unwrap();
+        tally.count(option_id);
+        self.public.election_tallies.put(election_id, tally);
+    }
+
+    fn push_election_history(&mut self, election_id: &ElectionAddress, transaction: &Hash) {
+        let mut history = self.election_history.get(election_id);
         history.push(*transaction);
         let history_hash = history.object_hash();
-        let old_election = self.public.elections.get(&election_id).unwrap();
+        let old_election = self.public.elections.get(election_id).unwrap();
         self.public.elections.put(
-            &election_id,
+            election_id,
             Election {
                 history_len: old_election.history_len + 1,
                 history_hash,
                 ..old_election
             },
         );
+    }
+
+    /// Records that participant has data in election, which is moved on key rotation.
+    fn enter_election(&mut self, election_id: &ElectionAddress, participant: &ParticipantAddress) {
         self.public
-            .election_votes
-            .get(&election_id)
-            .put(participant_key, option_id);
+            .participant_elections
+            .get(participant)
+            .insert(*election_id);
     }
     //endregion
 }
+
+/// Moves entry of `from` key to `to` key, returns whether there has been the entry.
+fn move_entry<A, V>(
+    index: &mut RawProofMapIndex<A, ParticipantAddress, V>,
+    from: &ParticipantAddress,
+    to: &ParticipantAddress,
+) -> bool
+where
+    A: RawAccessMut,
+    V: BinaryValue,
+{
+    match index.get(from) {
+        Some(value) => {
+            index.remove(from);
+            index.put(to, value);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Follows redirects from `addr` and returns the final address if it is in `index`.
+fn follow_redirects<A, V>(
+    redirects: &RawProofMapIndex<A, Address, Address>,
+    index: &RawProofMapIndex<A, Address, V>,
+    addr: &Address,
+) -> Option<Address>
+where
+    A: RawAccess,
+    V: BinaryValue,
+{
+    let mut addr = *addr;
+    while let Some(next) = redirects.get(&addr) {
+        addr = next;
+    }
+    if index.contains(&addr) {
+        Some(addr)
+    } else {
+        None
+    }
+}
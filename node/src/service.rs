@@ -1,20 +1,129 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Utc};
 use exonum::{
-    merkledb::BinaryValue,
-    runtime::{CommonError, ExecutionContext, ExecutionError},
+    crypto::{self, Hash, PublicKey},
+    merkledb::{access::Access, BinaryValue, ObjectHash},
+    runtime::{
+        migrations::{InitMigrationError, LinearMigrations, MigrateData, MigrationScript},
+        versioning::Version,
+        CommonError, ExecutionContext, ExecutionError,
+    },
 };
 use exonum_derive::{ServiceDispatcher, ServiceFactory};
-use exonum_rust_runtime::{api::ServiceApiBuilder, Service};
+use exonum_rust_runtime::{
+    api::ServiceApiBuilder, AfterCommitContext, Service, ServiceFactory as _,
+};
 use exonum_supervisor::Configure;
 use exonum_time::TimeSchema;
 
 use crate::{
-    api::PublicApi, model::transactions::Config, schema::SchemaImpl, tx_behavior::ElectionInterface,
+    api::PublicApi,
+    contact_verifier::ContactVerifier,
+    crypto::{
+        ballot::PartialDecryption,
+        dkg::{Dealing, SharedKey},
+        elgamal::EncryptionKey,
+    },
+    migrations,
+    model::{
+        encryption::{self, Complaint, ElectionEncryption, KeyGenerationPhase, TransportKey},
+        mixnet,
+        transactions::{
+            AcknowledgeDealings, Config, PublishDealing, PublishPartialDecryption, PublishShuffle,
+            PublishTransportKey,
+        },
+        ElectionAddress,
+    },
+    pii_store::PiiStore,
+    schema::{Schema, SchemaImpl},
+    tx_behavior::{ElectionInterface, ElectionInterfaceMut, Error},
 };
 
-#[derive(Debug, ServiceFactory, ServiceDispatcher)]
+#[derive(Debug, Clone, ServiceFactory, ServiceDispatcher)]
 #[service_dispatcher(implements("ElectionInterface", raw = "Configure<Params = Config>"))]
-#[service_factory(proto_sources = "crate::proto")]
-pub struct ElectionService;
+#[service_factory(
+    proto_sources = "crate::proto",
+    service_constructor = "ElectionService::create_instance"
+)]
+pub struct ElectionService {
+    pii_store: PiiStore,
+    contact_verifier: ContactVerifier,
+    /// Digests of election states which trustee messages have been broadcast for.
+    trustee_broadcasts: Arc<Mutex<HashMap<ElectionAddress, Hash>>>,
+}
+
+impl ElectionService {
+    pub fn new(pii_store: PiiStore, contact_verifier: ContactVerifier) -> Self {
+        Self {
+            pii_store,
+            contact_verifier,
+            trustee_broadcasts: Arc::default(),
+        }
+    }
+
+    fn create_instance(&self) -> Box<dyn Service> {
+        Box::new(self.clone())
+    }
+}
+
+impl Default for ElectionService {
+    fn default() -> Self {
+        let codes_path = std::env::temp_dir().join("election-contact-codes");
+        Self::new(
+            PiiStore::temporary(),
+            ContactVerifier::file_sink(codes_path),
+        )
+    }
+}
+
+/// Digest of election state which messages of trustee depend on. Trustee broadcasts
+/// messages only when it changes, so pending transactions are not sent again every block.
+fn trustee_state_digest<T: Access>(
+    schema: &Schema<T>,
+    election_id: &ElectionAddress,
+    state: &ElectionEncryption,
+    now: Option<DateTime<Utc>>,
+) -> Hash {
+    let phase = now.map(|now| schema.key_generation_phase(election_id, state, now));
+    let is_finished = now.map_or(false, |now| {
+        schema
+            .elections
+            .get(election_id)
+            .map_or(false, |election| election.finish_date <= now)
+    });
+    let hashes = [
+        state.object_hash(),
+        schema
+            .election_transport_keys
+            .get(election_id)
+            .object_hash(),
+        schema.election_dealings.get(election_id).object_hash(),
+        schema.election_decryptions.get(election_id).object_hash(),
+        schema.mix_outputs.get(election_id).object_hash(),
+        schema
+            .election_mixing
+            .get(election_id)
+            .map_or_else(Hash::zero, |mixing| mixing.object_hash()),
+    ];
+    let acknowledgements = schema
+        .election_acknowledgements
+        .get(election_id)
+        .iter()
+        .count() as u64;
+
+    let mut bytes = Vec::with_capacity(hashes.len() * 32 + 10);
+    for hash in &hashes {
+        bytes.extend_from_slice(hash.as_ref());
+    }
+    bytes.extend_from_slice(&acknowledgements.to_le_bytes());
+    bytes.push(phase.map_or(0, |phase| phase as u8 + 1));
+    bytes.push(is_finished as u8);
+    crypto::hash(&bytes)
+}
 
 fn verify_config(context: &ExecutionContext<'_>, config: &Config) -> Result<(), ExecutionError> {
     let _time_schema: TimeSchema<_> = context
@@ -37,8 +146,260 @@ impl Service for ElectionService {
         Ok(())
     }
 
+    fn after_commit(&self, context: AfterCommitContext<'_>) {
+        if let Some(broadcaster) = context.broadcaster() {
+            let schema = SchemaImpl::new(context.service_data());
+            let now = schema.config.get().and_then(|config| {
+                context
+                    .data()
+                    .service_schema::<TimeSchema<_>, _>(config.time_service_name.as_str())
+                    .ok()
+                    .and_then(|time_schema| time_schema.time.get())
+            });
+            let keypair = broadcaster.keypair().clone();
+            let trustee = keypair.public_key();
+            let mut rng = rand::thread_rng();
+            let mut broadcaster = broadcaster.blocking();
+
+            // Take part in key generation, shuffling and decryption of encrypted elections.
+            let mut trustee_broadcasts = self.trustee_broadcasts.lock().unwrap();
+            for (election_id, state) in schema.public.election_encryption.iter() {
+                if !state.is_trustee(&trustee) || state.is_decrypted {
+                    trustee_broadcasts.remove(&election_id);
+                    continue;
+                }
+                let digest = trustee_state_digest(&schema.public, &election_id, &state, now);
+                if trustee_broadcasts.insert(election_id, digest) == Some(digest) {
+                    continue;
+                }
+
+                let secret = encryption::transport_secret(keypair.secret_key(), &election_id);
+                let position = state.trustee_position(&trustee).unwrap();
+                let transport_keys = schema.public.election_transport_keys.get(&election_id);
+
+                if schema.public.pending_key_generation.contains(&election_id) {
+                    let phase = match now {
+                        Some(now) => schema
+                            .public
+                            .key_generation_phase(&election_id, &state, now),
+                        None => continue,
+                    };
+                    let trustee_key = transport_keys.get(&trustee);
+                    let dealings = schema.public.election_dealings.get(&election_id);
+                    let context = encryption::trustee_context(&election_id, &trustee);
+
+                    match (phase, trustee_key) {
+                        (KeyGenerationPhase::Transport, None) => {
+                            let key =
+                                TransportKey::create(&election_id, &trustee, &secret, &mut rng);
+                            let tx = PublishTransportKey { election_id, key };
+                            if let Err(e) = broadcaster.publish_transport_key((), tx) {
+                                log::error!("Failed to broadcast transport key: {}", e);
+                            }
+                        }
+                        (KeyGenerationPhase::Dealing, Some(_)) if !dealings.contains(&trustee) => {
+                            let keys: Vec<Option<EncryptionKey>> = state
+                                .trustees
+                                .iter()
+                                .map(|trustee| transport_keys.get(trustee).map(|key| key.key))
+                                .collect();
+                            let dealing = Dealing::deal(
+                                &context,
+                                state.threshold as usize,
+                                &secret,
+                                &keys,
+                                &mut rng,
+                            );
+                            let tx = PublishDealing {
+                                election_id,
+                                dealing,
+                            };
+                            if let Err(e) = broadcaster.publish_dealing((), tx) {
+                                log::error!("Failed to broadcast dealing: {}", e);
+                            }
+                        }
+                        (KeyGenerationPhase::Acknowledgement, Some(_))
+                            if !schema
+                                .public
+                                .election_acknowledgements
+                                .get(&election_id)
+                                .contains(&trustee) =>
+                        {
+                            let complaints = dealings
+                                .iter()
+                                .filter_map(|(dealer, dealing)| {
+                                    let dealer_key = transport_keys.get(&dealer).unwrap().key;
+                                    let shared_key =
+                                        SharedKey::reveal(&context, &secret, &dealer_key, &mut rng);
+                                    let dealer_context =
+                                        encryption::trustee_context(&election_id, &dealer);
+                                    dealing
+                                        .open_share(&dealer_context, position, &shared_key.key)
+                                        .map_or(Some(Complaint { dealer, shared_key }), |_| None)
+                                })
+                                .collect();
+                            let tx = AcknowledgeDealings {
+                                election_id,
+                                complaints,
+                            };
+                            if let Err(e) = broadcaster.acknowledge_dealings((), tx) {
+                                log::error!("Failed to broadcast dealings acknowledgement: {}", e);
+                            }
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                let election = match schema.public.elections.get(&election_id) {
+                    Some(election) => election,
+                    None => continue,
+                };
+                let finished = now.map_or(false, |now| election.finish_date <= now);
+                if !finished || !state.is_key_ready {
+                    continue;
+                }
+
+                // Ballots of mixnet elections are shuffled by trustees in turn and the
+                // output of the last shuffle is decrypted instead of tally.
+                let ciphertexts = match schema.public.election_mixing.get(&election_id) {
+                    Some(mixing) if !mixing.is_finished(state.trustees.len()) => {
+                        if state.trustees[mixing.mix_steps as usize] == trustee {
+                            let input = schema.public.mix_input(&election_id).unwrap();
+                            let (output, proof) = mixnet::shuffle_ballots(
+                                &election_id,
+                                &trustee,
+                                &state.joint_key,
+                                &input,
+                                &mut rng,
+                            );
+                            let tx = PublishShuffle {
+                                election_id,
+                                output,
+                                proof,
+                            };
+                            if let Err(e) = broadcaster.publish_shuffle((), tx) {
+                                log::error!("Failed to broadcast ballots shuffle: {}", e);
+                            }
+                        }
+                        continue;
+                    }
+                    // Every mixer is skipped, so ballots are never decrypted.
+                    Some(mixing) if !mixing.is_mixed(state.trustees.len()) => continue,
+                    Some(_) => schema.public.mix_input(&election_id).unwrap().ciphertexts(),
+                    None => state.tally,
+                };
+
+                let decryptions = schema.public.election_decryptions.get(&election_id);
+                if decryptions.contains(&trustee) {
+                    continue;
+                }
+                // Trustee with an invalid share has complained in time, so its share is only
+                // missing if it has not taken part in key generation.
+                let dealings = schema.public.qualified_dealings(&election_id);
+                let share = encryption::secret_share(
+                    &election_id,
+                    position,
+                    &secret,
+                    dealings
+                        .iter()
+                        .map(|(dealer, key, dealing)| (dealer, key, dealing)),
+                );
+                if let Some(share) = share {
+                    let context = encryption::trustee_context(&election_id, &trustee);
+                    let decryption =
+                        PartialDecryption::create(&context, &share, &ciphertexts, &mut rng);
+                    let tx = PublishPartialDecryption {
+                        election_id,
+                        decryption,
+                    };
+                    if let Err(e) = broadcaster.publish_partial_decryption((), tx) {
+                        log::error!("Failed to broadcast tally decryption: {}", e);
+                    }
+                }
+            }
+        }
+    }
+
+    fn after_transactions(&self, context: ExecutionContext<'_>) -> Result<(), ExecutionError> {
+        let mut schema = SchemaImpl::new(context.service_data());
+        let now = schema.config.get().and_then(|config| {
+            context
+                .data()
+                .service_schema::<TimeSchema<_>, _>(config.time_service_name.as_str())
+                .ok()
+                .and_then(|time_schema| time_schema.time.get())
+        });
+        let now = match now {
+            Some(now) => now,
+            None => return Ok(()),
+        };
+
+        // Election key is combined once every trustee has acknowledged dealings or the
+        // acknowledgement phase is over.
+        let finished: Vec<ElectionAddress> = schema
+            .public
+            .pending_key_generation
+            .iter()
+            .filter(|election_id| {
+                let state = schema.public.election_encryption.get(election_id).unwrap();
+                schema.public.key_generation_phase(election_id, &state, now)
+                    == KeyGenerationPhase::Finished
+            })
+            .collect();
+        for election_id in &finished {
+            schema.finish_key_generation(election_id);
+        }
+
+        // Mixer which has not shuffled ballots in time is skipped, so the rest of
+        // trustees go on without it.
+        let timed_out: Vec<(ElectionAddress, PublicKey)> = schema
+            .public
+            .pending_mixing
+            .iter()
+            .filter_map(|election_id| {
+                let state = schema.public.election_encryption.get(&election_id).unwrap();
+                let mixing = schema.public.election_mixing.get(&election_id).unwrap();
+                if state.is_key_ready && mixing.step_deadline <= now {
+                    let mixer = state.trustees[mixing.mix_steps as usize];
+                    Some((election_id, mixer))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        for (election_id, mixer) in timed_out {
+            schema.skip_mixer(&election_id, mixer, now);
+        }
+        Ok(())
+    }
+
     fn wire_api(&self, builder: &mut ServiceApiBuilder) {
         PublicApi::wire(builder);
+        PublicApi::wire_pii(builder, self.pii_store.clone());
+        PublicApi::wire_contacts(
+            builder,
+            self.pii_store.clone(),
+            self.contact_verifier.clone(),
+        );
+    }
+}
+
+impl MigrateData for ElectionService {
+    fn migration_scripts(
+        &self,
+        start_version: &Version,
+    ) -> Result<Vec<MigrationScript>, InitMigrationError> {
+        let pii_store = self.pii_store.clone();
+        LinearMigrations::new(self.artifact_id().version)
+            .add_script(Version::new(0, 4, 0), migrations::migrate_to_fixed_point)
+            .add_script(Version::new(0, 5, 0), migrations::migrate_to_multi_polygon)
+            .add_script(Version::new(0, 6, 0), move |context| {
+                migrations::migrate_to_pii_commitments(context, &pii_store)?;
+                migrations::migrate_location_records(context)?;
+                migrations::backfill_election_indexes(context)
+            })
+            .select(start_version)
     }
 }
 
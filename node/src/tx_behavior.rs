@@ -1,12 +1,26 @@
-use geo::algorithm::contains::Contains;
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
 
 use exonum::{
     crypto::Hash,
+    merkledb::{access::Access, BinaryValue, ObjectHash},
     runtime::{CallerAddress as Address, CommonError, ExecutionContext, ExecutionError},
 };
 
 use crate::{
-    model::{self, transactions::*},
+    model::{
+        self,
+        approval::{self, CounterSignature},
+        contact,
+        eligibility::EligibilityOperator,
+        encryption::{ElectionEncryption, KeyGenerationPhase},
+        invitation, key_rotation,
+        multisig::{self, Proposal, ProposalAction, SignerSet},
+        recovery::{self, Guardians},
+        transactions::*,
+        AdministrationAddress, BallotMode, ElectionAddress,
+    },
     schema::{Repository, SchemaImpl},
     service::ElectionService,
 };
@@ -15,6 +29,7 @@ use crate::{
 pub trait ElectionInterface<Ctx> {
     type Output;
 
+    /// Registers participant. Pass code uniqueness is checked later by `attest_pass_code`.
     #[interface_method(id = 0)]
     fn create_participant(&self, ctx: Ctx, arg: CreateParticipant) -> Self::Output;
 
@@ -29,6 +44,102 @@ pub trait ElectionInterface<Ctx> {
 
     #[interface_method(id = 4)]
     fn submit_location(&self, ctx: Ctx, arg: SubmitLocation) -> Self::Output;
+
+    #[interface_method(id = 5)]
+    fn set_participant_attributes(&self, ctx: Ctx, arg: SetParticipantAttributes) -> Self::Output;
+
+    #[interface_method(id = 6)]
+    fn update_voter_roll(&self, ctx: Ctx, arg: UpdateVoterRoll) -> Self::Output;
+
+    #[interface_method(id = 7)]
+    fn commit_vote(&self, ctx: Ctx, arg: CommitVote) -> Self::Output;
+
+    #[interface_method(id = 8)]
+    fn reveal_vote(&self, ctx: Ctx, arg: RevealVote) -> Self::Output;
+
+    #[interface_method(id = 9)]
+    fn cast_encrypted_vote(&self, ctx: Ctx, arg: CastEncryptedVote) -> Self::Output;
+
+    #[interface_method(id = 10)]
+    fn publish_transport_key(&self, ctx: Ctx, arg: PublishTransportKey) -> Self::Output;
+
+    #[interface_method(id = 11)]
+    fn publish_partial_decryption(&self, ctx: Ctx, arg: PublishPartialDecryption) -> Self::Output;
+
+    #[interface_method(id = 12)]
+    fn offer_voting_token(&self, ctx: Ctx, arg: OfferVotingToken) -> Self::Output;
+
+    #[interface_method(id = 13)]
+    fn request_voting_token(&self, ctx: Ctx, arg: RequestVotingToken) -> Self::Output;
+
+    #[interface_method(id = 14)]
+    fn issue_voting_token(&self, ctx: Ctx, arg: IssueVotingToken) -> Self::Output;
+
+    #[interface_method(id = 15)]
+    fn cast_anonymous_vote(&self, ctx: Ctx, arg: CastAnonymousVote) -> Self::Output;
+
+    #[interface_method(id = 16)]
+    fn register_ring_key(&self, ctx: Ctx, arg: RegisterRingKey) -> Self::Output;
+
+    #[interface_method(id = 17)]
+    fn cast_ring_vote(&self, ctx: Ctx, arg: CastRingVote) -> Self::Output;
+
+    #[interface_method(id = 18)]
+    fn cast_mixnet_vote(&self, ctx: Ctx, arg: CastMixnetVote) -> Self::Output;
+
+    #[interface_method(id = 19)]
+    fn publish_shuffle(&self, ctx: Ctx, arg: PublishShuffle) -> Self::Output;
+
+    #[interface_method(id = 20)]
+    fn set_participant_status(&self, ctx: Ctx, arg: SetParticipantStatus) -> Self::Output;
+
+    #[interface_method(id = 21)]
+    fn issue_invitations(&self, ctx: Ctx, arg: IssueInvitations) -> Self::Output;
+
+    #[interface_method(id = 22)]
+    fn publish_contact_challenge(&self, ctx: Ctx, arg: PublishContactChallenge) -> Self::Output;
+
+    #[interface_method(id = 23)]
+    fn confirm_contact(&self, ctx: Ctx, arg: ConfirmContact) -> Self::Output;
+
+    #[interface_method(id = 24)]
+    fn rotate_participant_key(&self, ctx: Ctx, arg: RotateParticipantKey) -> Self::Output;
+
+    #[interface_method(id = 25)]
+    fn set_guardians(&self, ctx: Ctx, arg: SetGuardians) -> Self::Output;
+
+    #[interface_method(id = 26)]
+    fn approve_recovery(&self, ctx: Ctx, arg: ApproveRecovery) -> Self::Output;
+
+    #[interface_method(id = 27)]
+    fn complete_recovery(&self, ctx: Ctx, arg: CompleteRecovery) -> Self::Output;
+
+    #[interface_method(id = 28)]
+    fn cancel_recovery(&self, ctx: Ctx, arg: CancelRecovery) -> Self::Output;
+
+    #[interface_method(id = 29)]
+    fn succeed_administration(&self, ctx: Ctx, arg: SucceedAdministration) -> Self::Output;
+
+    #[interface_method(id = 30)]
+    fn cancel_election(&self, ctx: Ctx, arg: CancelElection) -> Self::Output;
+
+    #[interface_method(id = 31)]
+    fn set_signers(&self, ctx: Ctx, arg: SetSigners) -> Self::Output;
+
+    #[interface_method(id = 32)]
+    fn propose_action(&self, ctx: Ctx, arg: ProposeAction) -> Self::Output;
+
+    #[interface_method(id = 33)]
+    fn approve_proposal(&self, ctx: Ctx, arg: ApproveProposal) -> Self::Output;
+
+    #[interface_method(id = 34)]
+    fn publish_dealing(&self, ctx: Ctx, arg: PublishDealing) -> Self::Output;
+
+    #[interface_method(id = 35)]
+    fn acknowledge_dealings(&self, ctx: Ctx, arg: AcknowledgeDealings) -> Self::Output;
+
+    #[interface_method(id = 36)]
+    fn attest_pass_code(&self, ctx: Ctx, arg: AttestPassCode) -> Self::Output;
 }
 
 impl ElectionInterface<ExecutionContext<'_>> for ElectionService {
@@ -43,12 +154,38 @@ impl ElectionInterface<ExecutionContext<'_>> for ElectionService {
 
         let mut schema = SchemaImpl::new(ctx.service_data());
 
-        if !schema.public.participant_repository().has(&addr) {
-            schema.create_participant(&addr, arg, &tx_hash);
-            Ok(())
-        } else {
-            Err(Error::ParticipantAlreadyExists.into())
+        if schema.public.participant_repository().has(&addr) {
+            return Err(Error::ParticipantAlreadyExists.into());
         }
+
+        let invitation = if let Some(redemption) = arg.invitation.as_ref() {
+            let invitation = schema
+                .public
+                .invitations
+                .get(&redemption.key)
+                .ok_or(Error::InvitationNotFound)?;
+
+            // Redemption is bound to the registering key, so it can not be replayed.
+            if !redemption.verify(&invitation::redemption_message(&addr)) {
+                return Err(Error::InvalidInvitationRedemption.into());
+            }
+
+            if invitation.is_used() {
+                return Err(Error::InvitationUsedYet.into());
+            }
+
+            let config = schema.config.get().expect("Can't read service config");
+            if invitation.is_expired(current_time(&ctx, &config)) {
+                return Err(Error::InvitationExpired.into());
+            }
+
+            Some(redemption.key)
+        } else {
+            None
+        };
+
+        schema.create_participant(&addr, arg, invitation.as_ref(), &tx_hash);
+        Ok(())
     }
 
     fn create_administration(
@@ -58,60 +195,130 @@ impl ElectionInterface<ExecutionContext<'_>> for ElectionService {
     ) -> Self::Output {
         let (from, tx_hash) = extract_info(&ctx)?;
 
+        let message = approval::creation_message(
+            &from,
+            &arg.name,
+            arg.principal_key.0.as_ref(),
+            &arg.area,
+            arg.expires_at,
+        );
+        if !arg
+            .approvals
+            .iter()
+            .all(|approval| approval.verify(&message))
+        {
+            return Err(Error::InvalidApproval.into());
+        }
+        let approvers: HashSet<Address> =
+            arg.approvals.iter().map(CounterSignature::signer).collect();
+
+        let validators: Vec<Address> = ctx
+            .data()
+            .for_core()
+            .consensus_config()
+            .validator_keys
+            .iter()
+            .map(|keys| Address::from_key(keys.service_key))
+            .collect();
+
         let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
 
-        if !schema.public.administration_repository().has(&from) {
-            schema.create_administration(&from, &arg.name, &arg.principal_key, &arg.area, &tx_hash);
-            Ok(())
-        } else {
-            Err(Error::AdministrationAlreadyExists.into())
+        let now = current_time(&ctx, &config);
+        if arg.expires_at <= now || arg.expires_at - now > approval::approval_lifetime() {
+            return Err(Error::CreationRequestExpired.into());
         }
-    }
 
-    fn issue_election(&self, ctx: ExecutionContext<'_>, arg: IssueElection) -> Self::Output {
-        let (issuer, tx_hash) = extract_info(&ctx)?;
+        if schema.public.administration_repository().has(&from) {
+            return Err(Error::AdministrationAlreadyExists.into());
+        }
 
-        let mut schema = SchemaImpl::new(ctx.service_data());
+        // Key of succeeded administration stays reserved for redirects.
+        if schema.public.administration_redirects.contains(&from) {
+            return Err(Error::AdministrationKeyUsedYet.into());
+        }
+
+        let is_approved = match &arg.principal_key.0 {
+            Some(principal) => {
+                if !schema.public.administration_repository().has(principal) {
+                    return Err(Error::PrincipalNotFound.into());
+                }
+                match schema.public.administration_signers.get(principal) {
+                    Some(signers) => {
+                        approvers
+                            .iter()
+                            .filter(|approver| signers.is_signer(approver))
+                            .count()
+                            >= signers.threshold as usize
+                    }
+                    None => approvers.contains(principal),
+                }
+            }
+            None => {
+                approvers
+                    .iter()
+                    .filter(|approver| validators.contains(approver))
+                    .count()
+                    >= approval::validator_supermajority(validators.len())
+            }
+        };
+        if !is_approved {
+            return Err(Error::CreationNotApproved.into());
+        }
+
+        if !arg.area.is_valid() {
+            return Err(Error::InvalidArea.into());
+        }
 
-        if !schema.public.administration_repository().has(&issuer) {
-            return Err(Error::AdministrationNotFound.into());
+        if let Some(principal) = &arg.principal_key.0 {
+            let principal = schema.public.administrations.get(principal).unwrap();
+            if !principal.area.contains_multi_polygon(&arg.area) {
+                return Err(Error::AreaOutsidePrincipal.into());
+            }
         }
 
-        if arg.finish_date <= arg.start_date {
-            return Err(Error::ElectionFinishedEarlierStart.into());
+        if schema
+            .public
+            .subordinates(arg.principal_key.0.as_ref())
+            .iter()
+            .filter_map(|sibling| schema.public.administrations.get(sibling))
+            .any(|sibling| sibling.area.overlaps(&arg.area))
+        {
+            return Err(Error::AreaOverlapsSibling.into());
         }
 
-        schema.issue_election(
-            &arg.name,
-            arg.addr,
-            &issuer,
-            &arg.start_date,
-            &arg.finish_date,
-            &arg.options,
-            &tx_hash,
-        );
+        schema.create_administration(&from, &arg.name, &arg.principal_key, &arg.area, &tx_hash);
 
         Ok(())
     }
 
+    fn issue_election(&self, ctx: ExecutionContext<'_>, arg: IssueElection) -> Self::Output {
+        let (issuer, tx_hash) = extract_info(&ctx)?;
+
+        check_single_signer(&ctx, &issuer)?;
+        execute_issue_election(&ctx, &issuer, &arg, &tx_hash)
+    }
+
     fn vote(&self, ctx: ExecutionContext<'_>, arg: Vote) -> Self::Output {
         let (voter, tx_hash) = extract_info(&ctx)?;
 
         let mut schema = SchemaImpl::new(ctx.service_data());
         let config = schema.config.get().expect("Can't read service config");
 
-        if !schema.public.participant_repository().has(&voter) {
-            return Err(Error::ParticipantNotFound.into());
+        let participant = schema
+            .public
+            .participant_repository()
+            .get(&voter)
+            .ok_or(Error::ParticipantNotFound)?;
+
+        if !participant.is_verified() {
+            return Err(Error::ParticipantNotVerified.into());
         }
 
         match schema.public.election_repository().get(&arg.election_id) {
             None => return Err(Error::ElectionNotFound.into()),
             Some(election) => {
-                let time_schema: exonum_time::TimeSchema<_> = ctx
-                    .data()
-                    .service_schema(config.time_service_name.as_str())
-                    .unwrap();
-                let now = time_schema.time.get().expect("can not get time");
+                let now = current_time(&ctx, &config);
                 if election.not_started_yet(now) {
                     return Err(Error::ElectionNotStartedYet.into());
                 }
@@ -120,6 +327,10 @@ impl ElectionInterface<ExecutionContext<'_>> for ElectionService {
                     return Err(Error::ElectionInactive.into());
                 }
 
+                if election.ballot_mode != BallotMode::Open {
+                    return Err(Error::WrongBallotMode.into());
+                }
+
                 if !election
                     .options
                     .iter()
@@ -128,6 +339,10 @@ impl ElectionInterface<ExecutionContext<'_>> for ElectionService {
                 {
                     return Err(Error::OptionNotFound.into());
                 }
+
+                if !schema.public.is_eligible(&election, &participant) {
+                    return Err(Error::NotEligible.into());
+                }
             }
         }
 
@@ -140,48 +355,1670 @@ impl ElectionInterface<ExecutionContext<'_>> for ElectionService {
         Ok(())
     }
 
-    fn submit_location(&self, ctx: ExecutionContext<'_>, arg: SubmitLocation) -> Self::Output {
-        let (tx_author, tx_hash) = extract_info(&ctx)?;
+    fn commit_vote(&self, ctx: ExecutionContext<'_>, arg: CommitVote) -> Self::Output {
+        let (voter, tx_hash) = extract_info(&ctx)?;
 
         let mut schema = SchemaImpl::new(ctx.service_data());
         let config = schema.config.get().expect("Can't read service config");
 
-        if !schema.public.participant_repository().has(&tx_author) {
-            return Err(Error::ParticipantNotFound.into());
+        let participant = schema
+            .public
+            .participant_repository()
+            .get(&voter)
+            .ok_or(Error::ParticipantNotFound)?;
+
+        if !participant.is_verified() {
+            return Err(Error::ParticipantNotVerified.into());
+        }
+
+        let election = schema
+            .public
+            .election_repository()
+            .get(&arg.election_id)
+            .ok_or(Error::ElectionNotFound)?;
+
+        let now = current_time(&ctx, &config);
+        if election.not_started_yet(now) {
+            return Err(Error::ElectionNotStartedYet.into());
+        }
+
+        if !election.is_active(now) {
+            return Err(Error::ElectionInactive.into());
+        }
+
+        if election.ballot_mode != BallotMode::CommitReveal {
+            return Err(Error::WrongBallotMode.into());
+        }
+
+        if !schema.public.is_eligible(&election, &participant) {
+            return Err(Error::NotEligible.into());
+        }
+
+        if schema.public.voted_yet(&arg.election_id, &voter) {
+            return Err(Error::VotedYet.into());
+        }
+
+        schema.commit_vote(arg.election_id, &voter, arg.commitment, &tx_hash);
+
+        Ok(())
+    }
+
+    fn reveal_vote(&self, ctx: ExecutionContext<'_>, arg: RevealVote) -> Self::Output {
+        let (voter, tx_hash) = extract_info(&ctx)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        let election = schema
+            .public
+            .election_repository()
+            .get(&arg.election_id)
+            .ok_or(Error::ElectionNotFound)?;
+
+        if election.ballot_mode != BallotMode::CommitReveal {
+            return Err(Error::WrongBallotMode.into());
+        }
+
+        if !election.is_reveal_open(current_time(&ctx, &config)) {
+            return Err(Error::RevealNotOpen.into());
+        }
+
+        let commitment = schema
+            .public
+            .election_commitments
+            .get(&arg.election_id)
+            .get(&voter)
+            .ok_or(Error::CommitmentNotFound)?;
+
+        if schema
+            .public
+            .election_votes
+            .get(&arg.election_id)
+            .contains(&voter)
+        {
+            return Err(Error::RevealedYet.into());
+        }
+
+        if model::ballot_commitment(arg.option_id, &arg.salt) != commitment {
+            return Err(Error::CommitmentMismatch.into());
+        }
+
+        if !election
+            .options
+            .iter()
+            .any(|option| option.id == arg.option_id)
+        {
+            return Err(Error::OptionNotFound.into());
+        }
+
+        schema.vote(arg.election_id, &voter, arg.option_id, &tx_hash);
+
+        Ok(())
+    }
+
+    fn cast_encrypted_vote(
+        &self,
+        ctx: ExecutionContext<'_>,
+        arg: CastEncryptedVote,
+    ) -> Self::Output {
+        let (voter, tx_hash) = extract_info(&ctx)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        let participant = schema
+            .public
+            .participant_repository()
+            .get(&voter)
+            .ok_or(Error::ParticipantNotFound)?;
+
+        if !participant.is_verified() {
+            return Err(Error::ParticipantNotVerified.into());
+        }
+
+        let election = schema
+            .public
+            .election_repository()
+            .get(&arg.election_id)
+            .ok_or(Error::ElectionNotFound)?;
+
+        let now = current_time(&ctx, &config);
+        if election.not_started_yet(now) {
+            return Err(Error::ElectionNotStartedYet.into());
+        }
+
+        if !election.is_active(now) {
+            return Err(Error::ElectionInactive.into());
+        }
+
+        if election.ballot_mode != BallotMode::Encrypted {
+            return Err(Error::WrongBallotMode.into());
+        }
+
+        if !schema.public.is_eligible(&election, &participant) {
+            return Err(Error::NotEligible.into());
+        }
+
+        if schema.public.voted_yet(&arg.election_id, &voter) {
+            return Err(Error::VotedYet.into());
+        }
+
+        let encryption = schema
+            .public
+            .election_encryption
+            .get(&arg.election_id)
+            .unwrap();
+        if !encryption.is_key_ready {
+            return Err(Error::EncryptionKeyNotReady.into());
+        }
+
+        let context = model::encryption::ballot_context(&arg.election_id, &voter);
+        if !arg
+            .ballot
+            .verify(&context, &encryption.joint_key, election.options.len())
+        {
+            return Err(Error::InvalidBallotProof.into());
+        }
+
+        schema.cast_encrypted_vote(arg.election_id, &voter, arg.ballot, &tx_hash);
+
+        Ok(())
+    }
+
+    fn publish_transport_key(
+        &self,
+        ctx: ExecutionContext<'_>,
+        arg: PublishTransportKey,
+    ) -> Self::Output {
+        let trustee = ctx
+            .caller()
+            .author()
+            .ok_or(CommonError::UnauthorizedCaller)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        let encryption = schema
+            .public
+            .election_encryption
+            .get(&arg.election_id)
+            .ok_or(Error::ElectionNotFound)?;
+
+        if !encryption.is_trustee(&trustee) {
+            return Err(Error::NotTrustee.into());
+        }
+
+        check_key_generation_phase(
+            &schema,
+            &arg.election_id,
+            &encryption,
+            current_time(&ctx, &config),
+            KeyGenerationPhase::Transport,
+        )?;
+
+        if schema
+            .public
+            .election_transport_keys
+            .get(&arg.election_id)
+            .contains(&trustee)
+        {
+            return Err(Error::TrusteeSubmittedYet.into());
+        }
+
+        if !arg.key.verify(&arg.election_id, &trustee) {
+            return Err(Error::InvalidTransportKey.into());
+        }
+
+        schema.publish_transport_key(&arg.election_id, &trustee, arg.key);
+
+        Ok(())
+    }
+
+    fn publish_partial_decryption(
+        &self,
+        ctx: ExecutionContext<'_>,
+        arg: PublishPartialDecryption,
+    ) -> Self::Output {
+        let trustee = ctx
+            .caller()
+            .author()
+            .ok_or(CommonError::UnauthorizedCaller)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        let election = schema
+            .public
+            .election_repository()
+            .get(&arg.election_id)
+            .ok_or(Error::ElectionNotFound)?;
+        let encryption = schema
+            .public
+            .election_encryption
+            .get(&arg.election_id)
+            .ok_or(Error::WrongBallotMode)?;
+
+        if !encryption.is_trustee(&trustee) {
+            return Err(Error::NotTrustee.into());
+        }
+
+        if election.finish_date > current_time(&ctx, &config) {
+            return Err(Error::ElectionNotFinishedYet.into());
+        }
+
+        if !encryption.is_key_ready {
+            return Err(Error::EncryptionKeyNotReady.into());
+        }
+
+        if encryption.is_decrypted {
+            return Err(Error::ElectionDecryptedYet.into());
         }
 
-        let location = {
-            let point = geo::Point(arg.position.into());
+        if schema
+            .public
+            .election_decryptions
+            .get(&arg.election_id)
+            .contains(&trustee)
+        {
+            return Err(Error::TrusteeSubmittedYet.into());
+        }
 
-            let mut found_administrations_by_lvl = schema
+        let share_key = encryption.share_keys[encryption.trustee_position(&trustee).unwrap()];
+        let ciphertexts = if election.ballot_mode == BallotMode::Mixnet {
+            let mixing = schema.public.election_mixing.get(&arg.election_id).unwrap();
+            if !mixing.is_mixed(encryption.trustees.len()) {
+                return Err(Error::MixingNotFinished.into());
+            }
+            schema
                 .public
-                .administrations
-                .values()
-                .filter(|a| geo::Polygon::<_>::from(a.area.clone()).contains(&point))
-                .collect::<Vec<model::Administration>>();
-
-            found_administrations_by_lvl.sort_by(|a, b| {
-                a.administration_level
-                    .partial_cmp(&b.administration_level)
-                    .unwrap()
-            });
-
-            found_administrations_by_lvl
-                .first()
-                .ok_or(Error::BadLocation)?
-                .addr
+                .mix_input(&arg.election_id)
+                .unwrap()
+                .ciphertexts()
+        } else {
+            encryption.tally
         };
+        let context = model::encryption::trustee_context(&arg.election_id, &trustee);
+        if !arg.decryption.verify(&context, &share_key, &ciphertexts) {
+            return Err(Error::InvalidDecryption.into());
+        }
 
-        let time_schema: exonum_time::TimeSchema<_> = ctx
-            .data()
-            .service_schema(config.time_service_name.as_str())
+        schema.publish_partial_decryption(&arg.election_id, &trustee, arg.decryption);
+
+        Ok(())
+    }
+
+    fn cast_mixnet_vote(&self, ctx: ExecutionContext<'_>, arg: CastMixnetVote) -> Self::Output {
+        let (voter, tx_hash) = extract_info(&ctx)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        let participant = schema
+            .public
+            .participant_repository()
+            .get(&voter)
+            .ok_or(Error::ParticipantNotFound)?;
+
+        if !participant.is_verified() {
+            return Err(Error::ParticipantNotVerified.into());
+        }
+
+        let election = schema
+            .public
+            .election_repository()
+            .get(&arg.election_id)
+            .ok_or(Error::ElectionNotFound)?;
+
+        let now = current_time(&ctx, &config);
+        if election.not_started_yet(now) {
+            return Err(Error::ElectionNotStartedYet.into());
+        }
+
+        if !election.is_active(now) {
+            return Err(Error::ElectionInactive.into());
+        }
+
+        if election.ballot_mode != BallotMode::Mixnet {
+            return Err(Error::WrongBallotMode.into());
+        }
+
+        if !schema.public.is_eligible(&election, &participant) {
+            return Err(Error::NotEligible.into());
+        }
+
+        if schema.public.voted_yet(&arg.election_id, &voter) {
+            return Err(Error::VotedYet.into());
+        }
+
+        let encryption = schema
+            .public
+            .election_encryption
+            .get(&arg.election_id)
+            .unwrap();
+        if !encryption.is_key_ready {
+            return Err(Error::EncryptionKeyNotReady.into());
+        }
+
+        let context = model::encryption::ballot_context(&arg.election_id, &voter);
+        if !arg.ballot.verify(&context, election.ballot_length as usize) {
+            return Err(Error::InvalidBallotProof.into());
+        }
+
+        schema.cast_mixnet_vote(arg.election_id, &voter, arg.ballot, &tx_hash);
+
+        Ok(())
+    }
+
+    fn publish_shuffle(&self, ctx: ExecutionContext<'_>, arg: PublishShuffle) -> Self::Output {
+        let mixer = ctx
+            .caller()
+            .author()
+            .ok_or(CommonError::UnauthorizedCaller)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        let election = schema
+            .public
+            .election_repository()
+            .get(&arg.election_id)
+            .ok_or(Error::ElectionNotFound)?;
+        let mixing = schema
+            .public
+            .election_mixing
+            .get(&arg.election_id)
+            .ok_or(Error::WrongBallotMode)?;
+        let encryption = schema
+            .public
+            .election_encryption
+            .get(&arg.election_id)
             .unwrap();
-        let now = time_schema.time.get().expect("can not get time");
 
-        schema.submit_participant_location(&tx_author, now, &location, &tx_hash);
+        if !encryption.is_trustee(&mixer) {
+            return Err(Error::NotTrustee.into());
+        }
+
+        let now = current_time(&ctx, &config);
+        if election.finish_date > now {
+            return Err(Error::ElectionNotFinishedYet.into());
+        }
+
+        if !encryption.is_key_ready {
+            return Err(Error::EncryptionKeyNotReady.into());
+        }
+
+        if encryption.trustees.get(mixing.mix_steps as usize) != Some(&mixer) {
+            return Err(Error::NotNextMixer.into());
+        }
+
+        let input = schema.public.mix_input(&arg.election_id).unwrap();
+        let context = model::encryption::trustee_context(&arg.election_id, &mixer);
+        if !arg
+            .proof
+            .verify(&context, &encryption.joint_key, &input, &arg.output)
+        {
+            return Err(Error::InvalidShuffleProof.into());
+        }
+
+        schema.publish_shuffle(&arg.election_id, arg.output, now);
 
         Ok(())
     }
+
+    fn offer_voting_token(&self, ctx: ExecutionContext<'_>, arg: OfferVotingToken) -> Self::Output {
+        let (issuer, tx_hash) = extract_info(&ctx)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        let election = schema
+            .public
+            .election_repository()
+            .get(&arg.election_id)
+            .ok_or(Error::ElectionNotFound)?;
+
+        if election.ballot_mode != BallotMode::Anonymous {
+            return Err(Error::WrongBallotMode.into());
+        }
+
+        if election.issuer != issuer {
+            return Err(Error::NotElectionIssuer.into());
+        }
+
+        if election.is_cancelled || election.finish_date <= current_time(&ctx, &config) {
+            return Err(Error::ElectionInactive.into());
+        }
+
+        let participant = schema
+            .public
+            .participant_repository()
+            .get(&arg.participant)
+            .ok_or(Error::ParticipantNotFound)?;
+
+        if !participant.is_verified() {
+            return Err(Error::ParticipantNotVerified.into());
+        }
+
+        if !schema.public.is_eligible(&election, &participant) {
+            return Err(Error::NotEligible.into());
+        }
+
+        if schema
+            .public
+            .token_issuance
+            .get(&arg.election_id)
+            .contains(&arg.participant)
+        {
+            return Err(Error::TokenOfferedYet.into());
+        }
+
+        if let Some(open) = schema.public.open_token_sessions.get(&arg.election_id) {
+            let session = schema
+                .public
+                .token_issuance
+                .get(&arg.election_id)
+                .get(&open)
+                .ok_or(Error::TokenOfferNotFound)?;
+            if session.challenge.0.is_some() {
+                return Err(Error::TokenSessionOpen.into());
+            }
+        }
+
+        schema.offer_voting_token(arg.election_id, &arg.participant, arg.commitment, &tx_hash);
+
+        Ok(())
+    }
+
+    fn request_voting_token(
+        &self,
+        ctx: ExecutionContext<'_>,
+        arg: RequestVotingToken,
+    ) -> Self::Output {
+        let (participant, tx_hash) = extract_info(&ctx)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        let election = schema
+            .public
+            .election_repository()
+            .get(&arg.election_id)
+            .ok_or(Error::ElectionNotFound)?;
+
+        if election.ballot_mode != BallotMode::Anonymous {
+            return Err(Error::WrongBallotMode.into());
+        }
+
+        if election.is_cancelled || election.finish_date <= current_time(&ctx, &config) {
+            return Err(Error::ElectionInactive.into());
+        }
+
+        let session = schema
+            .public
+            .token_issuance
+            .get(&arg.election_id)
+            .get(&participant)
+            .ok_or(Error::TokenOfferNotFound)?;
+
+        if session.challenge.0.is_some() {
+            return Err(Error::TokenRequestedYet.into());
+        }
+
+        schema.request_voting_token(arg.election_id, &participant, arg.challenge, &tx_hash);
+
+        Ok(())
+    }
+
+    fn issue_voting_token(&self, ctx: ExecutionContext<'_>, arg: IssueVotingToken) -> Self::Output {
+        let (issuer, tx_hash) = extract_info(&ctx)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+
+        let election = schema
+            .public
+            .election_repository()
+            .get(&arg.election_id)
+            .ok_or(Error::ElectionNotFound)?;
+
+        if election.ballot_mode != BallotMode::Anonymous {
+            return Err(Error::WrongBallotMode.into());
+        }
+
+        if election.issuer != issuer {
+            return Err(Error::NotElectionIssuer.into());
+        }
+
+        let session = schema
+            .public
+            .token_issuance
+            .get(&arg.election_id)
+            .get(&arg.participant)
+            .ok_or(Error::TokenOfferNotFound)?;
+
+        let challenge = session.challenge.0.ok_or(Error::TokenRequestNotFound)?;
+
+        if session.response.0.is_some() {
+            return Err(Error::TokenIssuedYet.into());
+        }
+
+        let token_key = election.token_key.0.ok_or(Error::InvalidTokenKey)?;
+        if !arg
+            .response
+            .verify(&token_key, &session.commitment, &challenge)
+        {
+            return Err(Error::InvalidBlindSignature.into());
+        }
+
+        schema.issue_voting_token(arg.election_id, &arg.participant, arg.response, &tx_hash);
+
+        Ok(())
+    }
+
+    fn cast_anonymous_vote(
+        &self,
+        ctx: ExecutionContext<'_>,
+        arg: CastAnonymousVote,
+    ) -> Self::Output {
+        let (voter, tx_hash) = extract_info(&ctx)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        let election = schema
+            .public
+            .election_repository()
+            .get(&arg.election_id)
+            .ok_or(Error::ElectionNotFound)?;
+
+        let now = current_time(&ctx, &config);
+        if election.not_started_yet(now) {
+            return Err(Error::ElectionNotStartedYet.into());
+        }
+
+        if !election.is_active(now) {
+            return Err(Error::ElectionInactive.into());
+        }
+
+        if election.ballot_mode != BallotMode::Anonymous {
+            return Err(Error::WrongBallotMode.into());
+        }
+
+        if !election
+            .options
+            .iter()
+            .any(|option| option.id == arg.option_id)
+        {
+            return Err(Error::OptionNotFound.into());
+        }
+
+        let token_key = election.token_key.0.ok_or(Error::InvalidTokenKey)?;
+        let message = model::voting_token::token_message(&arg.election_id, &voter);
+        if !arg.token.verify(&token_key, &message) {
+            return Err(Error::InvalidVotingToken.into());
+        }
+
+        if schema
+            .public
+            .spent_voting_tokens
+            .get(&arg.election_id)
+            .contains(&voter)
+        {
+            return Err(Error::TokenSpentYet.into());
+        }
+
+        schema.cast_anonymous_vote(arg.election_id, &voter, arg.option_id, &tx_hash);
+
+        Ok(())
+    }
+
+    fn register_ring_key(&self, ctx: ExecutionContext<'_>, arg: RegisterRingKey) -> Self::Output {
+        let (participant_addr, tx_hash) = extract_info(&ctx)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        let participant = schema
+            .public
+            .participant_repository()
+            .get(&participant_addr)
+            .ok_or(Error::ParticipantNotFound)?;
+
+        if !participant.is_verified() {
+            return Err(Error::ParticipantNotVerified.into());
+        }
+
+        let election = schema
+            .public
+            .election_repository()
+            .get(&arg.election_id)
+            .ok_or(Error::ElectionNotFound)?;
+
+        if election.ballot_mode != BallotMode::Ring {
+            return Err(Error::WrongBallotMode.into());
+        }
+
+        if !election.not_started_yet(current_time(&ctx, &config)) {
+            return Err(Error::RingRegistrationClosed.into());
+        }
+
+        if !schema.public.is_eligible(&election, &participant) {
+            return Err(Error::NotEligible.into());
+        }
+
+        let ring_keys = schema.public.ring_keys.get(&arg.election_id);
+        if ring_keys.contains(&participant_addr) || ring_keys.values().any(|key| key == arg.key) {
+            return Err(Error::RingKeyRegisteredYet.into());
+        }
+
+        schema.register_ring_key(arg.election_id, &participant_addr, arg.key, &tx_hash);
+
+        Ok(())
+    }
+
+    fn cast_ring_vote(&self, ctx: ExecutionContext<'_>, arg: CastRingVote) -> Self::Output {
+        let (_, tx_hash) = extract_info(&ctx)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        let election = schema
+            .public
+            .election_repository()
+            .get(&arg.election_id)
+            .ok_or(Error::ElectionNotFound)?;
+
+        let now = current_time(&ctx, &config);
+        if election.not_started_yet(now) {
+            return Err(Error::ElectionNotStartedYet.into());
+        }
+
+        if !election.is_active(now) {
+            return Err(Error::ElectionInactive.into());
+        }
+
+        if election.ballot_mode != BallotMode::Ring {
+            return Err(Error::WrongBallotMode.into());
+        }
+
+        if !election
+            .options
+            .iter()
+            .any(|option| option.id == arg.option_id)
+        {
+            return Err(Error::OptionNotFound.into());
+        }
+
+        let ring = schema.public.election_ring(&arg.election_id);
+        let context = model::ring_ballot::ring_context(&arg.election_id, arg.option_id);
+        if !arg.signature.verify(&context, &ring) {
+            return Err(Error::InvalidRingSignature.into());
+        }
+
+        let key_image = arg.signature.key_image.object_hash();
+        if schema
+            .public
+            .spent_key_images
+            .get(&arg.election_id)
+            .contains(&key_image)
+        {
+            return Err(Error::KeyImageSpentYet.into());
+        }
+
+        schema.cast_ring_vote(arg.election_id, key_image, arg.option_id, &tx_hash);
+
+        Ok(())
+    }
+
+    fn submit_location(&self, ctx: ExecutionContext<'_>, arg: SubmitLocation) -> Self::Output {
+        let (tx_author, tx_hash) = extract_info(&ctx)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        let participant = schema
+            .public
+            .participant_repository()
+            .get(&tx_author)
+            .ok_or(Error::ParticipantNotFound)?;
+
+        // Pending participants may need location to get residence administration,
+        // which verifies them.
+        if participant.status == model::ParticipantStatus::Suspended {
+            return Err(Error::ParticipantSuspended.into());
+        }
+
+        let administrations = schema.public.containing_administrations(&arg.position);
+        if administrations.is_empty() {
+            return Err(Error::BadLocation.into());
+        }
+
+        let now = current_time(&ctx, &config);
+
+        schema.submit_participant_location(&tx_author, now, administrations, &tx_hash);
+
+        Ok(())
+    }
+
+    fn set_participant_attributes(
+        &self,
+        ctx: ExecutionContext<'_>,
+        arg: SetParticipantAttributes,
+    ) -> Self::Output {
+        let (issuer, tx_hash) = extract_info(&ctx)?;
+
+        check_single_signer(&ctx, &issuer)?;
+        execute_set_participant_attributes(&ctx, &issuer, &arg, &tx_hash)
+    }
+
+    fn set_participant_status(
+        &self,
+        ctx: ExecutionContext<'_>,
+        arg: SetParticipantStatus,
+    ) -> Self::Output {
+        let (official, tx_hash) = extract_info(&ctx)?;
+
+        check_single_signer(&ctx, &official)?;
+        execute_set_participant_status(&ctx, &official, &arg, &tx_hash)
+    }
+
+    fn issue_invitations(&self, ctx: ExecutionContext<'_>, arg: IssueInvitations) -> Self::Output {
+        let (issuer, _) = extract_info(&ctx)?;
+
+        check_single_signer(&ctx, &issuer)?;
+        execute_issue_invitations(&ctx, &issuer, &arg)
+    }
+
+    fn attest_pass_code(&self, ctx: ExecutionContext<'_>, arg: AttestPassCode) -> Self::Output {
+        let (validator, tx_hash) = extract_info(&ctx)?;
+
+        if !ctx
+            .data()
+            .for_core()
+            .consensus_config()
+            .validator_keys
+            .iter()
+            .any(|keys| Address::from_key(keys.service_key) == validator)
+        {
+            return Err(Error::NotValidator.into());
+        }
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+
+        let participant = schema
+            .public
+            .participant_repository()
+            .get(&arg.participant)
+            .ok_or(Error::ParticipantNotFound)?;
+
+        if participant.is_pass_code_attested() {
+            return Err(Error::PassCodeAttestedYet.into());
+        }
+
+        if arg.fingerprint == Hash::zero()
+            || schema.public.pass_code_owners.contains(&arg.fingerprint)
+        {
+            return Err(Error::PassCodeRegisteredYet.into());
+        }
+
+        schema.attest_pass_code(&arg.participant, arg.fingerprint, &tx_hash);
+
+        Ok(())
+    }
+
+    fn publish_contact_challenge(
+        &self,
+        ctx: ExecutionContext<'_>,
+        arg: PublishContactChallenge,
+    ) -> Self::Output {
+        let validator = ctx
+            .caller()
+            .author()
+            .ok_or(CommonError::UnauthorizedCaller)?;
+
+        if !ctx
+            .data()
+            .for_core()
+            .consensus_config()
+            .validator_keys
+            .iter()
+            .any(|keys| keys.service_key == validator)
+        {
+            return Err(Error::NotValidator.into());
+        }
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        if !contact::is_contact(arg.field) {
+            return Err(Error::NotContactField.into());
+        }
+
+        if !schema.public.participant_repository().has(&arg.participant) {
+            return Err(Error::ParticipantNotFound.into());
+        }
+
+        let expires_at = current_time(&ctx, &config) + contact::code_lifetime();
+        schema.publish_contact_challenge(&arg.participant, arg.field, arg.challenge, expires_at);
+
+        Ok(())
+    }
+
+    fn confirm_contact(&self, ctx: ExecutionContext<'_>, arg: ConfirmContact) -> Self::Output {
+        let (owner, tx_hash) = extract_info(&ctx)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        if !schema.public.participant_repository().has(&owner) {
+            return Err(Error::ParticipantNotFound.into());
+        }
+
+        let challenge = schema
+            .public
+            .contact_challenges
+            .get(&contact::challenge_id(&owner, arg.field))
+            .ok_or(Error::ContactChallengeNotFound)?;
+
+        if challenge.expires_at <= current_time(&ctx, &config) {
+            return Err(Error::ContactChallengeExpired.into());
+        }
+
+        if exonum::crypto::hash(arg.code_mac.as_ref()) != challenge.challenge {
+            return Err(Error::InvalidContactCode.into());
+        }
+
+        schema.confirm_contact(&owner, arg.field, &tx_hash);
+
+        Ok(())
+    }
+
+    fn rotate_participant_key(
+        &self,
+        ctx: ExecutionContext<'_>,
+        arg: RotateParticipantKey,
+    ) -> Self::Output {
+        let (owner, tx_hash) = extract_info(&ctx)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        if !schema.public.participant_repository().has(&owner) {
+            return Err(Error::ParticipantNotFound.into());
+        }
+
+        if !key_rotation::verify_rotation(&owner, &arg.new_key, &arg.new_key_signature) {
+            return Err(Error::InvalidKeyRotation.into());
+        }
+
+        // Former addresses are never reused, so redirects stay unambiguous.
+        let new_addr = Address::from_key(arg.new_key);
+        if schema.public.participants.contains(&new_addr)
+            || schema.public.participant_redirects.contains(&new_addr)
+        {
+            return Err(Error::ParticipantKeyUsedYet.into());
+        }
+
+        let now = current_time(&ctx, &config);
+        schema.rotate_participant_key(&owner, &new_addr, now, &tx_hash);
+
+        Ok(())
+    }
+
+    fn set_guardians(&self, ctx: ExecutionContext<'_>, arg: SetGuardians) -> Self::Output {
+        let (owner, tx_hash) = extract_info(&ctx)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+
+        let participant = schema
+            .public
+            .participant_repository()
+            .get(&owner)
+            .ok_or(Error::ParticipantNotFound)?;
+
+        // Guardians are other participants or residence administrations of the owner.
+        let mut guardians = HashSet::with_capacity(arg.guardians.len());
+        if arg.guardians.is_empty()
+            || arg.guardians.len() > recovery::MAX_GUARDIANS
+            || arg.threshold == 0
+            || arg.threshold as usize > arg.guardians.len()
+            || !arg.guardians.iter().all(|guardian| {
+                guardians.insert(guardian)
+                    && *guardian != owner
+                    && (schema.public.participants.contains(guardian)
+                        || schema.public.is_residence_authority(&participant, guardian))
+            })
+        {
+            return Err(Error::InvalidGuardians.into());
+        }
+
+        let guardians = Guardians {
+            guardians: arg.guardians,
+            threshold: arg.threshold,
+        };
+        schema.set_guardians(&owner, guardians, &tx_hash);
+
+        Ok(())
+    }
+
+    fn approve_recovery(&self, ctx: ExecutionContext<'_>, arg: ApproveRecovery) -> Self::Output {
+        let (author, _) = extract_info(&ctx)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        if !schema.public.participant_repository().has(&arg.participant) {
+            return Err(Error::ParticipantNotFound.into());
+        }
+
+        let guardians = schema
+            .public
+            .participant_guardians
+            .get(&arg.participant)
+            .ok_or(Error::GuardiansNotSet)?;
+
+        // Guardians may have rotated their keys since nomination.
+        let guardian = *guardians
+            .guardians
+            .iter()
+            .find(|guardian| {
+                schema.public.current_participant_address(guardian) == Some(author)
+                    || schema.public.current_administration_address(guardian) == Some(author)
+            })
+            .ok_or(Error::NotGuardian)?;
+
+        if !key_rotation::verify_rotation(&arg.participant, &arg.new_key, &arg.new_key_signature) {
+            return Err(Error::InvalidKeyRotation.into());
+        }
+
+        let new_addr = Address::from_key(arg.new_key);
+        if schema.public.participants.contains(&new_addr)
+            || schema.public.participant_redirects.contains(&new_addr)
+        {
+            return Err(Error::ParticipantKeyUsedYet.into());
+        }
+
+        if let Some(recovery) = schema.public.participant_recoveries.get(&arg.participant) {
+            // Extra approvals would extend the challenge period.
+            if recovery.is_approved() || recovery.has_approved(&guardian, &arg.new_key) {
+                return Err(Error::RecoveryApprovedYet.into());
+            }
+        }
+
+        let now = current_time(&ctx, &config);
+        schema.approve_recovery(&arg.participant, &guardian, arg.new_key, now);
+
+        Ok(())
+    }
+
+    fn complete_recovery(&self, ctx: ExecutionContext<'_>, arg: CompleteRecovery) -> Self::Output {
+        let (author, tx_hash) = extract_info(&ctx)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        let recovery = schema
+            .public
+            .participant_recoveries
+            .get(&arg.participant)
+            .ok_or(Error::RecoveryNotFound)?;
+
+        if recovery.approved_key().map(Address::from_key) != Some(author) {
+            return Err(Error::NotRecoveryKey.into());
+        }
+
+        let now = current_time(&ctx, &config);
+        if !recovery.is_unlocked(now) {
+            return Err(Error::RecoveryLocked.into());
+        }
+
+        if schema.public.participants.contains(&author)
+            || schema.public.participant_redirects.contains(&author)
+        {
+            return Err(Error::ParticipantKeyUsedYet.into());
+        }
+
+        schema.rotate_participant_key(&arg.participant, &author, now, &tx_hash);
+
+        Ok(())
+    }
+
+    fn cancel_recovery(&self, ctx: ExecutionContext<'_>, _arg: CancelRecovery) -> Self::Output {
+        let (owner, tx_hash) = extract_info(&ctx)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+
+        if !schema.public.participant_recoveries.contains(&owner) {
+            return Err(Error::RecoveryNotFound.into());
+        }
+
+        schema.cancel_recovery(&owner, &tx_hash);
+
+        Ok(())
+    }
+
+    fn succeed_administration(
+        &self,
+        ctx: ExecutionContext<'_>,
+        arg: SucceedAdministration,
+    ) -> Self::Output {
+        let (owner, tx_hash) = extract_info(&ctx)?;
+
+        check_single_signer(&ctx, &owner)?;
+        execute_succeed_administration(&ctx, &owner, &arg, &tx_hash)
+    }
+
+    fn cancel_election(&self, ctx: ExecutionContext<'_>, arg: CancelElection) -> Self::Output {
+        let (issuer, tx_hash) = extract_info(&ctx)?;
+
+        check_single_signer(&ctx, &issuer)?;
+        execute_cancel_election(&ctx, &issuer, &arg.election_id, &tx_hash)
+    }
+
+    fn set_signers(&self, ctx: ExecutionContext<'_>, arg: SetSigners) -> Self::Output {
+        let (owner, _) = extract_info(&ctx)?;
+
+        check_single_signer(&ctx, &owner)?;
+        let signers = SignerSet {
+            signers: arg.signers,
+            threshold: arg.threshold,
+        };
+        execute_action(
+            &ctx,
+            &owner,
+            &ProposalAction::SetSigners(signers),
+            &Hash::zero(),
+        )
+    }
+
+    fn propose_action(&self, ctx: ExecutionContext<'_>, arg: ProposeAction) -> Self::Output {
+        let (signer, tx_hash) = extract_info(&ctx)?;
+
+        let (signers, now) = {
+            let schema = SchemaImpl::new(ctx.service_data());
+            let config = schema.config.get().expect("Can't read service config");
+            let signers = schema
+                .public
+                .administration_signers
+                .get(&arg.administration)
+                .ok_or(Error::SignersNotSet)?;
+            (signers, current_time(&ctx, &config))
+        };
+
+        if !signers.is_signer(&signer) {
+            return Err(Error::NotSigner.into());
+        }
+
+        let is_executed = signers.threshold == 1;
+        if is_executed {
+            execute_action(&ctx, &arg.administration, &arg.action, &tx_hash)?;
+        }
+
+        let proposal = Proposal {
+            administration: arg.administration,
+            action: arg.action,
+            approvals: vec![signer],
+            expires_at: now + multisig::proposal_lifetime(),
+            is_executed,
+        };
+        SchemaImpl::new(ctx.service_data()).propose_action(&tx_hash, proposal);
+
+        Ok(())
+    }
+
+    fn approve_proposal(&self, ctx: ExecutionContext<'_>, arg: ApproveProposal) -> Self::Output {
+        let (signer, tx_hash) = extract_info(&ctx)?;
+
+        let (proposal, signers, now) = {
+            let schema = SchemaImpl::new(ctx.service_data());
+            let config = schema.config.get().expect("Can't read service config");
+            let proposal = schema
+                .public
+                .proposals
+                .get(&arg.proposal_id)
+                .ok_or(Error::ProposalNotFound)?;
+            let signers = schema
+                .public
+                .administration_signers
+                .get(&proposal.administration)
+                .ok_or(Error::SignersNotSet)?;
+            (proposal, signers, current_time(&ctx, &config))
+        };
+
+        if proposal.is_executed {
+            return Err(Error::ProposalExecutedYet.into());
+        }
+
+        if proposal.expires_at <= now {
+            return Err(Error::ProposalExpired.into());
+        }
+
+        if !signers.is_signer(&signer) {
+            return Err(Error::NotSigner.into());
+        }
+
+        if proposal.approvals.contains(&signer) {
+            return Err(Error::ProposalApprovedYet.into());
+        }
+
+        // Approvals of signers removed since the proposal are not counted.
+        let approvals = proposal
+            .approvals
+            .iter()
+            .filter(|approval| signers.is_signer(approval))
+            .count()
+            + 1;
+        let is_executed = approvals >= signers.threshold as usize;
+        if is_executed {
+            execute_action(&ctx, &proposal.administration, &proposal.action, &tx_hash)?;
+        }
+
+        SchemaImpl::new(ctx.service_data()).approve_proposal(
+            &arg.proposal_id,
+            &signer,
+            is_executed,
+        );
+
+        Ok(())
+    }
+
+    fn update_voter_roll(&self, ctx: ExecutionContext<'_>, arg: UpdateVoterRoll) -> Self::Output {
+        let (owner, _) = extract_info(&ctx)?;
+
+        check_single_signer(&ctx, &owner)?;
+        execute_update_voter_roll(&ctx, &owner, &arg)
+    }
+
+    fn publish_dealing(&self, ctx: ExecutionContext<'_>, arg: PublishDealing) -> Self::Output {
+        let dealer = ctx
+            .caller()
+            .author()
+            .ok_or(CommonError::UnauthorizedCaller)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        let encryption = schema
+            .public
+            .election_encryption
+            .get(&arg.election_id)
+            .ok_or(Error::ElectionNotFound)?;
+
+        if !encryption.is_trustee(&dealer) {
+            return Err(Error::NotTrustee.into());
+        }
+
+        check_key_generation_phase(
+            &schema,
+            &arg.election_id,
+            &encryption,
+            current_time(&ctx, &config),
+            KeyGenerationPhase::Dealing,
+        )?;
+
+        let transport_keys = schema.public.election_transport_keys.get(&arg.election_id);
+        if !transport_keys.contains(&dealer) {
+            return Err(Error::TransportKeyNotFound.into());
+        }
+
+        if schema
+            .public
+            .election_dealings
+            .get(&arg.election_id)
+            .contains(&dealer)
+        {
+            return Err(Error::TrusteeSubmittedYet.into());
+        }
+
+        // Every trustee with transport key gets a share, the others get none.
+        let context = model::encryption::trustee_context(&arg.election_id, &dealer);
+        let is_valid = arg.dealing.verify(
+            &context,
+            encryption.threshold as usize,
+            encryption.trustees.len(),
+        ) && encryption
+            .trustees
+            .iter()
+            .zip(&arg.dealing.shares)
+            .all(|(trustee, share)| share.is_some() == transport_keys.contains(trustee));
+        if !is_valid {
+            return Err(Error::InvalidDealing.into());
+        }
+
+        schema.publish_dealing(&arg.election_id, &dealer, arg.dealing);
+
+        Ok(())
+    }
+
+    fn acknowledge_dealings(
+        &self,
+        ctx: ExecutionContext<'_>,
+        arg: AcknowledgeDealings,
+    ) -> Self::Output {
+        let trustee = ctx
+            .caller()
+            .author()
+            .ok_or(CommonError::UnauthorizedCaller)?;
+
+        let mut schema = SchemaImpl::new(ctx.service_data());
+        let config = schema.config.get().expect("Can't read service config");
+
+        let encryption = schema
+            .public
+            .election_encryption
+            .get(&arg.election_id)
+            .ok_or(Error::ElectionNotFound)?;
+
+        if !encryption.is_trustee(&trustee) {
+            return Err(Error::NotTrustee.into());
+        }
+
+        check_key_generation_phase(
+            &schema,
+            &arg.election_id,
+            &encryption,
+            current_time(&ctx, &config),
+            KeyGenerationPhase::Acknowledgement,
+        )?;
+
+        let transport_keys = schema.public.election_transport_keys.get(&arg.election_id);
+        let trustee_key = transport_keys
+            .get(&trustee)
+            .ok_or(Error::TransportKeyNotFound)?;
+
+        if schema
+            .public
+            .election_acknowledgements
+            .get(&arg.election_id)
+            .contains(&trustee)
+        {
+            return Err(Error::TrusteeSubmittedYet.into());
+        }
+
+        // Complaint reveals Diffie-Hellman key of the trustee and the dealer, so anyone
+        // can check that the share is invalid.
+        let position = encryption.trustee_position(&trustee).unwrap();
+        let context = model::encryption::trustee_context(&arg.election_id, &trustee);
+        let dealings = schema.public.election_dealings.get(&arg.election_id);
+        let mut disqualified = Vec::with_capacity(arg.complaints.len());
+        for complaint in &arg.complaints {
+            let dealing = dealings
+                .get(&complaint.dealer)
+                .ok_or(Error::InvalidComplaint)?;
+            let dealer_key = transport_keys.get(&complaint.dealer).unwrap();
+            if !complaint
+                .shared_key
+                .verify(&context, &trustee_key.key, &dealer_key.key)
+            {
+                return Err(Error::InvalidComplaint.into());
+            }
+
+            let dealer_context =
+                model::encryption::trustee_context(&arg.election_id, &complaint.dealer);
+            if dealing
+                .open_share(&dealer_context, position, &complaint.shared_key.key)
+                .is_some()
+            {
+                return Err(Error::InvalidComplaint.into());
+            }
+            disqualified.push(complaint.dealer);
+        }
+
+        schema.acknowledge_dealings(&arg.election_id, &trustee, disqualified);
+
+        Ok(())
+    }
+}
+
+/// Rejects key generation messages sent out of their phase or after key generation.
+fn check_key_generation_phase<T: Access>(
+    schema: &SchemaImpl<T>,
+    election_id: &ElectionAddress,
+    encryption: &ElectionEncryption,
+    now: DateTime<Utc>,
+    expected: KeyGenerationPhase,
+) -> Result<(), ExecutionError> {
+    let is_pending = schema.public.pending_key_generation.contains(election_id);
+    if !is_pending
+        || schema
+            .public
+            .key_generation_phase(election_id, encryption, now)
+            != expected
+    {
+        return Err(Error::KeyGenerationPhaseClosed.into());
+    }
+    Ok(())
+}
+
+/// Rejects direct sensitive actions of multi-signature administrations.
+fn check_single_signer(
+    ctx: &ExecutionContext<'_>,
+    administration: &AdministrationAddress,
+) -> Result<(), ExecutionError> {
+    let schema = SchemaImpl::new(ctx.service_data());
+    if schema
+        .public
+        .administration_signers
+        .contains(administration)
+    {
+        return Err(Error::MultisigRequired.into());
+    }
+    Ok(())
+}
+
+/// Executes sensitive action on behalf of administration.
+fn execute_action(
+    ctx: &ExecutionContext<'_>,
+    administration: &AdministrationAddress,
+    action: &ProposalAction,
+    tx_hash: &Hash,
+) -> Result<(), ExecutionError> {
+    match action {
+        ProposalAction::IssueElection(arg) => {
+            execute_issue_election(ctx, administration, arg, tx_hash)
+        }
+        ProposalAction::CancelElection(election_id) => {
+            execute_cancel_election(ctx, administration, election_id, tx_hash)
+        }
+        ProposalAction::SetSigners(signers) => {
+            let mut schema = SchemaImpl::new(ctx.service_data());
+            if !schema
+                .public
+                .administration_repository()
+                .has(administration)
+            {
+                return Err(Error::AdministrationNotFound.into());
+            }
+            if !signers.is_valid() {
+                return Err(Error::InvalidSigners.into());
+            }
+            schema.set_signers(administration, signers.clone());
+            Ok(())
+        }
+        ProposalAction::IssueInvitations(arg) => {
+            execute_issue_invitations(ctx, administration, arg)
+        }
+        ProposalAction::SetParticipantStatus(arg) => {
+            execute_set_participant_status(ctx, administration, arg, tx_hash)
+        }
+        ProposalAction::UpdateVoterRoll(arg) => execute_update_voter_roll(ctx, administration, arg),
+        ProposalAction::SetParticipantAttributes(arg) => {
+            execute_set_participant_attributes(ctx, administration, arg, tx_hash)
+        }
+        ProposalAction::SucceedAdministration(arg) => {
+            execute_succeed_administration(ctx, administration, arg, tx_hash)
+        }
+    }
+}
+
+fn execute_set_participant_attributes(
+    ctx: &ExecutionContext<'_>,
+    issuer: &AdministrationAddress,
+    arg: &SetParticipantAttributes,
+    tx_hash: &Hash,
+) -> Result<(), ExecutionError> {
+    let mut schema = SchemaImpl::new(ctx.service_data());
+
+    if !schema.public.administration_repository().has(issuer) {
+        return Err(Error::AdministrationNotFound.into());
+    }
+
+    let participant = schema
+        .public
+        .participant_repository()
+        .get(&arg.participant)
+        .ok_or(Error::ParticipantNotFound)?;
+
+    if !schema.public.is_residence_authority(&participant, issuer) {
+        return Err(Error::NotResidenceAdministration.into());
+    }
+
+    if arg.attributes.iter().any(|a| a.key.is_empty()) {
+        return Err(Error::InvalidAttribute.into());
+    }
+
+    schema.set_participant_attributes(&arg.participant, issuer, &arg.attributes, tx_hash);
+
+    Ok(())
+}
+
+fn execute_succeed_administration(
+    ctx: &ExecutionContext<'_>,
+    owner: &AdministrationAddress,
+    arg: &SucceedAdministration,
+    tx_hash: &Hash,
+) -> Result<(), ExecutionError> {
+    let mut schema = SchemaImpl::new(ctx.service_data());
+
+    if !schema.public.administration_repository().has(owner) {
+        return Err(Error::AdministrationNotFound.into());
+    }
+
+    if !key_rotation::verify_rotation(owner, &arg.new_key, &arg.new_key_signature) {
+        return Err(Error::InvalidKeyRotation.into());
+    }
+
+    let new_addr = Address::from_key(arg.new_key);
+    if schema.public.administrations.contains(&new_addr)
+        || schema.public.administration_redirects.contains(&new_addr)
+    {
+        return Err(Error::AdministrationKeyUsedYet.into());
+    }
+
+    schema.succeed_administration(owner, &new_addr, tx_hash);
+
+    Ok(())
+}
+
+fn execute_set_participant_status(
+    ctx: &ExecutionContext<'_>,
+    official: &AdministrationAddress,
+    arg: &SetParticipantStatus,
+    tx_hash: &Hash,
+) -> Result<(), ExecutionError> {
+    let mut schema = SchemaImpl::new(ctx.service_data());
+
+    if !schema.public.administration_repository().has(official) {
+        return Err(Error::AdministrationNotFound.into());
+    }
+
+    let participant = schema
+        .public
+        .participant_repository()
+        .get(&arg.participant)
+        .ok_or(Error::ParticipantNotFound)?;
+
+    // Only the residence administration itself checks pass codes of its residents.
+    if schema.public.residence_administration(&participant) != Some(*official) {
+        return Err(Error::NotResidenceAdministration.into());
+    }
+
+    if !participant.status.can_change_to(arg.status) {
+        return Err(Error::InvalidStatusTransition.into());
+    }
+
+    // Participant is verified once it is known to hold a unique pass code.
+    if arg.status == model::ParticipantStatus::Verified && !participant.is_pass_code_attested() {
+        return Err(Error::PassCodeNotAttested.into());
+    }
+
+    schema.set_participant_status(&arg.participant, arg.status, tx_hash);
+
+    Ok(())
+}
+
+fn execute_issue_invitations(
+    ctx: &ExecutionContext<'_>,
+    issuer: &AdministrationAddress,
+    arg: &IssueInvitations,
+) -> Result<(), ExecutionError> {
+    let mut schema = SchemaImpl::new(ctx.service_data());
+    let config = schema.config.get().expect("Can't read service config");
+
+    if !schema.public.administration_repository().has(issuer) {
+        return Err(Error::AdministrationNotFound.into());
+    }
+
+    if arg.keys.is_empty()
+        || arg.keys.len() > invitation::MAX_INVITATIONS_PER_TX
+        || arg.expires_at <= current_time(ctx, &config)
+    {
+        return Err(Error::InvalidInvitationBatch.into());
+    }
+
+    let mut keys = HashSet::with_capacity(arg.keys.len());
+    if !arg
+        .keys
+        .iter()
+        .all(|key| keys.insert(key) && !schema.public.invitations.contains(key))
+    {
+        return Err(Error::InvitationIssuedYet.into());
+    }
+
+    schema.issue_invitations(issuer, &arg.keys, arg.expires_at);
+
+    Ok(())
+}
+
+fn execute_update_voter_roll(
+    ctx: &ExecutionContext<'_>,
+    owner: &AdministrationAddress,
+    arg: &UpdateVoterRoll,
+) -> Result<(), ExecutionError> {
+    let mut schema = SchemaImpl::new(ctx.service_data());
+
+    if !schema.public.administration_repository().has(owner) {
+        return Err(Error::AdministrationNotFound.into());
+    }
+
+    if let Some(roll_owner) = schema.public.voter_roll_owners.get(&arg.roll_id) {
+        if schema.public.current_administration_address(&roll_owner) != Some(*owner) {
+            return Err(Error::VoterRollOfOtherAdministration.into());
+        }
+    }
+
+    let participants = schema.public.participant_repository();
+    if !arg
+        .added
+        .iter()
+        .all(|participant| participants.has(participant))
+    {
+        return Err(Error::ParticipantNotFound.into());
+    }
+
+    schema.update_voter_roll(&arg.roll_id, owner, &arg.added, &arg.removed);
+
+    Ok(())
+}
+
+fn execute_cancel_election(
+    ctx: &ExecutionContext<'_>,
+    issuer: &AdministrationAddress,
+    election_id: &ElectionAddress,
+    tx_hash: &Hash,
+) -> Result<(), ExecutionError> {
+    let mut schema = SchemaImpl::new(ctx.service_data());
+    let config = schema.config.get().expect("Can't read service config");
+
+    let election = schema
+        .public
+        .elections
+        .get(election_id)
+        .ok_or(Error::ElectionNotFound)?;
+
+    if election.issuer != *issuer {
+        return Err(Error::NotElectionIssuer.into());
+    }
+
+    if election.is_cancelled {
+        return Err(Error::ElectionCancelledYet.into());
+    }
+
+    if election.finish_date <= current_time(ctx, &config) {
+        return Err(Error::ElectionInactive.into());
+    }
+
+    schema.cancel_election(election_id, tx_hash);
+
+    Ok(())
+}
+
+fn execute_issue_election(
+    ctx: &ExecutionContext<'_>,
+    issuer: &AdministrationAddress,
+    arg: &IssueElection,
+    tx_hash: &Hash,
+) -> Result<(), ExecutionError> {
+    let mut schema = SchemaImpl::new(ctx.service_data());
+
+    if !schema.public.administration_repository().has(issuer) {
+        return Err(Error::AdministrationNotFound.into());
+    }
+
+    if arg.finish_date <= arg.start_date {
+        return Err(Error::ElectionFinishedEarlierStart.into());
+    }
+
+    if arg.ballot_mode == BallotMode::CommitReveal && arg.reveal_finish_date <= arg.finish_date {
+        return Err(Error::InvalidRevealWindow.into());
+    }
+
+    let rule = model::eligibility::EligibilityRule::new(&arg.eligibility);
+    if !rule.is_well_formed() {
+        return Err(Error::InvalidEligibilityRule.into());
+    }
+    for (operator, target) in rule.targets() {
+        let target_exists = match operator {
+            EligibilityOperator::ResidesWithin => {
+                AdministrationAddress::from_bytes(target.as_ref().into())
+                    .map_or(false, |addr| schema.public.administrations.contains(&addr))
+            }
+            EligibilityOperator::OnRoll => schema.public.voter_roll_owners.contains(&target),
+            _ => true,
+        };
+        if !target_exists {
+            return Err(Error::InvalidEligibilityRule.into());
+        }
+    }
+
+    if arg.ballot_mode == BallotMode::Encrypted && arg.options.is_empty() {
+        return Err(Error::OptionNotFound.into());
+    }
+
+    if arg.ballot_mode == BallotMode::Anonymous && arg.token_key.0.is_none() {
+        return Err(Error::InvalidTokenKey.into());
+    }
+
+    if arg.ballot_mode == BallotMode::Mixnet
+        && (arg.ballot_length == 0 || arg.ballot_length > model::mixnet::MAX_BALLOT_LENGTH)
+    {
+        return Err(Error::InvalidBallotLength.into());
+    }
+
+    schema.issue_election(issuer, arg, tx_hash);
+
+    if arg.ballot_mode == BallotMode::Encrypted || arg.ballot_mode == BallotMode::Mixnet {
+        let trustees = ctx
+            .data()
+            .for_core()
+            .consensus_config()
+            .validator_keys
+            .iter()
+            .map(|keys| keys.service_key)
+            .collect();
+        let config = schema.config.get().expect("Can't read service config");
+        let key_deadline = current_time(ctx, &config) + model::encryption::key_generation_phase();
+        schema.setup_encryption(&arg.addr, trustees, key_deadline);
+    }
+
+    Ok(())
+}
+
+fn current_time(context: &ExecutionContext<'_>, config: &Config) -> DateTime<Utc> {
+    let time_schema: exonum_time::TimeSchema<_> = context
+        .data()
+        .service_schema(config.time_service_name.as_str())
+        .unwrap();
+    time_schema.time.get().expect("can not get time")
 }
 
 fn extract_info(context: &ExecutionContext<'_>) -> Result<(Address, Hash), ExecutionError> {
@@ -217,4 +2054,175 @@ pub enum Error {
     ElectionNotStartedYet = 10,
     /// Location does not contains in any administration area
     BadLocation = 11,
+    /// Eligibility expression is malformed or refers to unknown objects
+    InvalidEligibilityRule = 12,
+    /// Participant does not satisfy eligibility expression of election
+    NotEligible = 13,
+    /// Administration is not a residence administration of participant
+    NotResidenceAdministration = 14,
+    /// Attribute key must not be empty
+    InvalidAttribute = 15,
+    /// Voter roll is maintained by another administration
+    VoterRollOfOtherAdministration = 16,
+    /// Transaction does not match ballot mode of election
+    WrongBallotMode = 17,
+    /// Reveal window must finish after election finish
+    InvalidRevealWindow = 18,
+    /// Reveal window of election is not open
+    RevealNotOpen = 19,
+    /// Unable to find ballot commitment of participant
+    CommitmentNotFound = 20,
+    /// Revealed option does not match commitment
+    CommitmentMismatch = 21,
+    /// Ballot of participant has been revealed yet
+    RevealedYet = 22,
+    /// Election key has not been generated yet
+    EncryptionKeyNotReady = 23,
+    /// Proof of encrypted ballot validity is wrong
+    InvalidBallotProof = 24,
+    /// Transaction author is not a trustee of election
+    NotTrustee = 25,
+    /// Trustee has submitted the data yet
+    TrusteeSubmittedYet = 26,
+    /// Proof of transport key is wrong
+    InvalidTransportKey = 27,
+    /// Proof of tally decryption is wrong
+    InvalidDecryption = 28,
+    /// Election is not finished yet
+    ElectionNotFinishedYet = 29,
+    /// Voting token key is missing or malformed
+    InvalidTokenKey = 30,
+    /// Transaction author is not an issuer of election
+    NotElectionIssuer = 31,
+    /// Voting token has been offered to participant yet
+    TokenOfferedYet = 32,
+    /// Unable to find voting token offer for participant
+    TokenOfferNotFound = 33,
+    /// Voting token has been requested by participant yet
+    TokenRequestedYet = 34,
+    /// Participant has not requested voting token yet
+    TokenRequestNotFound = 35,
+    /// Voting token has been issued to participant yet
+    TokenIssuedYet = 36,
+    /// Blind signature does not match the signing session
+    InvalidBlindSignature = 37,
+    /// Voting token is not signed by election issuer for transaction author
+    InvalidVotingToken = 38,
+    /// Voting token has been spent yet
+    TokenSpentYet = 39,
+    /// Ring keys can be registered before election start only
+    RingRegistrationClosed = 40,
+    /// Participant or ring key has been registered in election ring yet
+    RingKeyRegisteredYet = 41,
+    /// Ring signature of ballot is wrong
+    InvalidRingSignature = 42,
+    /// Ballot with the same key image has been counted yet
+    KeyImageSpentYet = 43,
+    /// Ballot length of mixnet election is out of range
+    InvalidBallotLength = 44,
+    /// Transaction author is not the trustee which shuffles ballots next
+    NotNextMixer = 45,
+    /// Proof of ballots shuffle is wrong
+    InvalidShuffleProof = 46,
+    /// Not all trustees have shuffled ballots yet
+    MixingNotFinished = 47,
+    /// Participant with the same pass code has been registered yet
+    PassCodeRegisteredYet = 48,
+    /// Participant can not move to requested status from the current one
+    InvalidStatusTransition = 50,
+    /// Participant has not been verified by residence administration
+    ParticipantNotVerified = 51,
+    /// Participant has been suspended by residence administration
+    ParticipantSuspended = 52,
+    /// Invitation with the code is not issued
+    InvitationNotFound = 53,
+    /// Invitation has been used yet
+    InvitationUsedYet = 54,
+    /// Invitation has expired
+    InvitationExpired = 55,
+    /// Invitation batch is empty, too large or expires in the past
+    InvalidInvitationBatch = 56,
+    /// Invitation with the same key has been issued yet
+    InvitationIssuedYet = 57,
+    /// Transaction author is not a validator
+    NotValidator = 58,
+    /// Only email and phone number can be verified
+    NotContactField = 59,
+    /// Verification code has not been sent to the contact
+    ContactChallengeNotFound = 60,
+    /// Verification code has expired
+    ContactChallengeExpired = 61,
+    /// Verification code does not match the challenge
+    InvalidContactCode = 62,
+    /// New key has not signed the key rotation
+    InvalidKeyRotation = 63,
+    /// Key belongs to a participant or has belonged to one before rotation
+    ParticipantKeyUsedYet = 64,
+    /// Guardians are not unique, unknown or do not reach the threshold
+    InvalidGuardians = 65,
+    /// Participant has not nominated guardians
+    GuardiansNotSet = 66,
+    /// Transaction author is not a guardian of participant
+    NotGuardian = 67,
+    /// Participant account is not being recovered
+    RecoveryNotFound = 68,
+    /// Recovery has been approved by the guardian or by enough guardians yet
+    RecoveryApprovedYet = 70,
+    /// Transaction author is not the new key approved for recovery
+    NotRecoveryKey = 71,
+    /// Recovery is not approved or its challenge period is not over
+    RecoveryLocked = 72,
+    /// Key belongs to an administration or has belonged to one before succession
+    AdministrationKeyUsedYet = 73,
+    /// Signers are not unique, too many or do not reach the threshold
+    InvalidSigners = 74,
+    /// Action of multi-signature administration must be proposed and approved by signers
+    MultisigRequired = 75,
+    /// Administration has not declared signers
+    SignersNotSet = 76,
+    /// Transaction author is not a signer of administration
+    NotSigner = 77,
+    /// Unable to find proposal
+    ProposalNotFound = 78,
+    /// Proposal has expired
+    ProposalExpired = 79,
+    /// Proposal has been approved by the signer yet
+    ProposalApprovedYet = 80,
+    /// Proposal has been executed yet
+    ProposalExecutedYet = 81,
+    /// Election has been cancelled yet
+    ElectionCancelledYet = 82,
+    /// Counter-signature does not match creation request
+    InvalidApproval = 83,
+    /// Unable to find principal administration
+    PrincipalNotFound = 84,
+    /// Creation of administration is not approved by its principal or validators
+    CreationNotApproved = 85,
+    /// Area is not a set of non-overlapping simple counter-clockwise polygons with clockwise
+    /// holes and non-zero area
+    InvalidArea = 86,
+    /// Area of child administration does not lie within area of its principal
+    AreaOutsidePrincipal = 87,
+    /// Area overlaps area of administration with the same principal
+    AreaOverlapsSibling = 88,
+    /// Message does not match the current phase of key generation
+    KeyGenerationPhaseClosed = 89,
+    /// Trustee has not published transport key
+    TransportKeyNotFound = 90,
+    /// Dealing is malformed or does not share a secret with every trustee with transport key
+    InvalidDealing = 91,
+    /// Complaint is not proven or the complained share is valid
+    InvalidComplaint = 92,
+    /// Tally of election has been decrypted yet
+    ElectionDecryptedYet = 93,
+    /// Issuer has a challenged voting token session to answer first
+    TokenSessionOpen = 94,
+    /// Pass code fingerprint of participant has been attested yet
+    PassCodeAttestedYet = 95,
+    /// Pass code fingerprint of participant is not attested by validator yet
+    PassCodeNotAttested = 96,
+    /// Invitation is not signed over the address of the registering participant
+    InvalidInvitationRedemption = 97,
+    /// Creation request of administration is expired or expires too late
+    CreationRequestExpired = 98,
 }
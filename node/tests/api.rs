@@ -1,9 +1,9 @@
-use std::{collections::HashMap, time::SystemTime};
+use std::{collections::HashMap, convert::TryFrom, sync::Mutex, time::SystemTime};
 
 use chrono::{DateTime, Duration, Utc};
 
 use exonum::{
-    crypto::{hash, Hash, KeyPair, PublicKey},
+    crypto::{self, hash, Hash, KeyPair, PublicKey},
     helpers::Height,
     messages::{AnyTx, Verified},
     runtime::{CallerAddress, InstanceId},
@@ -15,15 +15,47 @@ use exonum_testkit::{
     ApiKind, TestKit, TestKitApi, TestKitBuilder,
 };
 use exonum_time::{MockTimeProvider, TimeServiceFactory};
+use geojson::{FeatureCollection, Geometry};
 
 use crypto_election_node::{
     constant::{BLOCKCHAIN_SERVICE_ID, BLOCKCHAIN_SERVICE_NAME},
+    contact_verifier::ContactVerifier,
+    crypto::{
+        blind::{BlindChallenge, BlindResponse, BlindSignature, NonceCommitment, VerificationKey},
+        ring::RingKey,
+    },
     model::{
-        geo::Polygon,
-        public_api::{AdministrationInfo, ElectionInfo, KeyQuery, ParticipantInfo},
-        transactions::{CreateAdministration, CreateParticipant, IssueElection, Vote},
-        Administration, AdministrationAddress, Election, ElectionAddress, Participant,
+        approval::{self, CounterSignature},
+        ballot_commitment, contact,
+        eligibility::EligibilityTerm,
+        encryption::{self, ElectionEncryption},
+        geo::{MultiPolygon, Polygon},
+        geo_json::GeoJsonError,
+        invitation, key_rotation, mixnet,
+        multisig::ProposalAction,
+        pii::{PiiField, PiiOpening},
+        public_api::{
+            AdministrationInfo, ContactVerificationQuery, DedupReport, ElectionInfo,
+            ElectionParticipantQuery, InvitationReport, KeyQuery, MixnetBallots, ParticipantInfo,
+            PiiCheckQuery, PiiSubmission, ProposalsInfo, RecoveryInfo, TallyProof,
+            UnrevealedBallots, VerificationError, VoteReceipt,
+        },
+        recovery,
+        ring_ballot::{self, ElectionRing},
+        transactions::{
+            ApproveProposal, ApproveRecovery, AttributeValue, CancelElection, CancelRecovery,
+            CastAnonymousVote, CastEncryptedVote, CastMixnetVote, CastRingVote, CommitVote,
+            CompleteRecovery, ConfirmContact, CreateAdministration, CreateParticipant,
+            IssueElection, IssueInvitations, IssueVotingToken, OfferVotingToken, ProposeAction,
+            RegisterRingKey, RequestVotingToken, RevealVote, RotateParticipantKey, SetGuardians,
+            SetParticipantAttributes, SetParticipantStatus, SetSigners, SubmitLocation,
+            SucceedAdministration, Vote,
+        },
+        voting_token::{self, TokenIssuance},
+        Administration, AdministrationAddress, BallotMode, Election, ElectionAddress,
+        LocationRecord, Participant, ParticipantStatus, ResultsVisibility,
     },
+    pii_store::PiiStore,
     service::ElectionService,
     ElectionInterface,
 };
@@ -43,8 +75,33 @@ fn pub_key_address(pub_key: PublicKey) -> CallerAddress {
     CallerAddress::from_key(pub_key)
 }
 
+/// Openings of personal data commitments of participant, salted deterministically.
+fn pii_openings(
+    key_pair: &KeyPair,
+    email: &str,
+    phone_number: &str,
+    pass_code: &str,
+) -> Vec<PiiOpening> {
+    [
+        (PiiField::Email, email),
+        (PiiField::PhoneNumber, phone_number),
+        (PiiField::PassCode, pass_code),
+    ]
+    .iter()
+    .map(|&(field, value)| {
+        let salt = hash(&[&key_pair.secret_key()[..], value.as_bytes()].concat());
+        PiiOpening::new(field, value, salt)
+    })
+    .collect()
+}
+
 struct ElectionApi {
     pub inner: TestKitApi,
+    /// Service keys of the only validator, which approve root administrations.
+    pub validator_keys: KeyPair,
+    /// Pass code openings of created participants, which are not submitted to the node yet.
+    pub pass_codes: Mutex<Vec<(PublicKey, PiiOpening)>>,
+    pub time_provider: MockTimeProvider,
 }
 
 impl ElectionApi {
@@ -73,18 +130,47 @@ impl ElectionApi {
         pass_code: &str,
         key_pair: &KeyPair,
     ) -> Verified<AnyTx> {
+        self.create_participant_with_invitation(
+            name,
+            email,
+            phone_number,
+            residence,
+            pass_code,
+            None,
+            key_pair,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn create_participant_with_invitation(
+        &self,
+        name: &str,
+        email: &str,
+        phone_number: &str,
+        residence: &Option<PublicKey>,
+        pass_code: &str,
+        invitation: Option<CounterSignature>,
+        key_pair: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let openings = pii_openings(key_pair, email, phone_number, pass_code);
         let tx = key_pair.create_participant(
             BLOCKCHAIN_SERVICE_ID,
             CreateParticipant {
                 name: name.to_owned(),
-                email: email.to_owned(),
-                phone_number: phone_number.to_owned(),
+                email_commitment: openings[0].commitment(),
+                phone_number_commitment: openings[1].commitment(),
                 residence: residence.map(pub_key_address).into(),
-                pass_code: pass_code.to_owned(),
+                pass_code_commitment: openings[2].commitment(),
+                invitation: invitation.into(),
             },
         );
 
         self.assert_tx_hash(&tx).await;
+        self.pass_codes
+            .lock()
+            .unwrap()
+            .push((key_pair.public_key(), openings[2].clone()));
 
         tx
     }
@@ -92,16 +178,66 @@ impl ElectionApi {
     async fn create_administration(
         &self,
         name: &str,
-        principal: &Option<PublicKey>,
-        area: &Polygon,
+        principal: Option<&KeyPair>,
+        area: &MultiPolygon,
+        administration_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let approver = principal.unwrap_or(&self.validator_keys);
+        self.create_approved_administration(name, principal, area, &[approver], administration_key)
+            .await
+    }
+
+    async fn create_approved_administration(
+        &self,
+        name: &str,
+        principal: Option<&KeyPair>,
+        area: &MultiPolygon,
+        approvers: &[&KeyPair],
+        administration_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let expires_at = self.time_provider.time() + Duration::hours(1);
+        self.create_expiring_administration(
+            name,
+            principal,
+            area,
+            approvers,
+            expires_at,
+            administration_key,
+        )
+        .await
+    }
+
+    async fn create_expiring_administration(
+        &self,
+        name: &str,
+        principal: Option<&KeyPair>,
+        area: &MultiPolygon,
+        approvers: &[&KeyPair],
+        expires_at: DateTime<Utc>,
         administration_key: &KeyPair,
     ) -> Verified<AnyTx> {
+        let addr = pub_key_address(administration_key.public_key());
+        let principal_key = principal.map(|keys| pub_key_address(keys.public_key()));
         let tx = administration_key.create_administration(
             BLOCKCHAIN_SERVICE_ID,
             CreateAdministration {
                 name: name.to_owned(),
-                principal_key: principal.map(pub_key_address).into(),
+                principal_key: principal_key.into(),
                 area: area.to_owned(),
+                approvals: approvers
+                    .iter()
+                    .map(|keys| {
+                        approval::sign_creation(
+                            &addr,
+                            name,
+                            principal_key.as_ref(),
+                            area,
+                            expires_at,
+                            keys,
+                        )
+                    })
+                    .collect(),
+                expires_at,
             },
         );
         self.assert_tx_hash(&tx).await;
@@ -111,8 +247,8 @@ impl ElectionApi {
     async fn create_administration_with_random_key(
         &self,
         name: &str,
-        principal: &Option<PublicKey>,
-        area: &Polygon,
+        principal: Option<&KeyPair>,
+        area: &MultiPolygon,
     ) -> (Verified<AnyTx>, KeyPair) {
         let key_pair = KeyPair::random();
 
@@ -130,6 +266,29 @@ impl ElectionApi {
         finish_date: &DateTime<Utc>,
         options: &[&str],
         issuer_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        self.issue_restricted_election(
+            addr,
+            name,
+            start_date,
+            finish_date,
+            options,
+            Vec::new(),
+            issuer_key,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn issue_restricted_election(
+        &self,
+        addr: Hash,
+        name: &str,
+        start_date: &DateTime<Utc>,
+        finish_date: &DateTime<Utc>,
+        options: &[&str],
+        eligibility: Vec<EligibilityTerm>,
+        issuer_key: &KeyPair,
     ) -> Verified<AnyTx> {
         let tx = issuer_key.issue_election(
             BLOCKCHAIN_SERVICE_ID,
@@ -139,23 +298,163 @@ impl ElectionApi {
                 start_date: start_date.to_owned(),
                 finish_date: finish_date.to_owned(),
                 options: options.iter().map(ToString::to_string).collect(),
+                eligibility,
+                ballot_mode: BallotMode::Open,
+                reveal_finish_date: finish_date.to_owned(),
+                token_key: None.into(),
+                ballot_length: 0,
+                results_visibility: ResultsVisibility::Live,
             },
         );
         self.assert_tx_hash(&tx).await;
         tx
     }
 
-    async fn vote(
+    async fn issue_election_with_mode(
+        &self,
+        addr: Hash,
+        start_date: &DateTime<Utc>,
+        finish_date: &DateTime<Utc>,
+        ballot_mode: BallotMode,
+        reveal_finish_date: &DateTime<Utc>,
+        issuer_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let tx = issuer_key.issue_election(
+            BLOCKCHAIN_SERVICE_ID,
+            IssueElection {
+                addr,
+                name: election1::NAME.to_owned(),
+                start_date: start_date.to_owned(),
+                finish_date: finish_date.to_owned(),
+                options: election1::OPTIONS.iter().map(ToString::to_string).collect(),
+                eligibility: Vec::new(),
+                ballot_mode,
+                reveal_finish_date: reveal_finish_date.to_owned(),
+                token_key: None.into(),
+                ballot_length: 0,
+                results_visibility: ResultsVisibility::Live,
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
+
+    async fn issue_anonymous_election(
+        &self,
+        addr: Hash,
+        start_date: &DateTime<Utc>,
+        finish_date: &DateTime<Utc>,
+        token_key: VerificationKey,
+        issuer_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let tx = issuer_key.issue_election(
+            BLOCKCHAIN_SERVICE_ID,
+            IssueElection {
+                addr,
+                name: election1::NAME.to_owned(),
+                start_date: start_date.to_owned(),
+                finish_date: finish_date.to_owned(),
+                options: election1::OPTIONS.iter().map(ToString::to_string).collect(),
+                eligibility: Vec::new(),
+                ballot_mode: BallotMode::Anonymous,
+                reveal_finish_date: finish_date.to_owned(),
+                token_key: Some(token_key).into(),
+                ballot_length: 0,
+                results_visibility: ResultsVisibility::Live,
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
+
+    async fn issue_mixnet_election(
+        &self,
+        addr: Hash,
+        start_date: &DateTime<Utc>,
+        finish_date: &DateTime<Utc>,
+        ballot_length: u32,
+        issuer_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let tx = issuer_key.issue_election(
+            BLOCKCHAIN_SERVICE_ID,
+            IssueElection {
+                addr,
+                name: election1::NAME.to_owned(),
+                start_date: start_date.to_owned(),
+                finish_date: finish_date.to_owned(),
+                options: election1::OPTIONS.iter().map(ToString::to_string).collect(),
+                eligibility: Vec::new(),
+                ballot_mode: BallotMode::Mixnet,
+                reveal_finish_date: finish_date.to_owned(),
+                token_key: None.into(),
+                ballot_length,
+                results_visibility: ResultsVisibility::Live,
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
+
+    async fn issue_election_with_visibility(
+        &self,
+        addr: Hash,
+        start_date: &DateTime<Utc>,
+        finish_date: &DateTime<Utc>,
+        results_visibility: ResultsVisibility,
+        issuer_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let tx = issuer_key.issue_election(
+            BLOCKCHAIN_SERVICE_ID,
+            IssueElection {
+                addr,
+                name: election1::NAME.to_owned(),
+                start_date: start_date.to_owned(),
+                finish_date: finish_date.to_owned(),
+                options: election1::OPTIONS.iter().map(ToString::to_string).collect(),
+                eligibility: Vec::new(),
+                ballot_mode: BallotMode::Open,
+                reveal_finish_date: finish_date.to_owned(),
+                token_key: None.into(),
+                ballot_length: 0,
+                results_visibility,
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
+
+    async fn commit_vote(
         &self,
         election_id: ElectionAddress,
         option_id: i32,
+        salt: &Hash,
         key_pair: &KeyPair,
     ) -> Verified<AnyTx> {
-        let tx = key_pair.vote(
+        let tx = key_pair.commit_vote(
             BLOCKCHAIN_SERVICE_ID,
-            Vote {
+            CommitVote {
+                election_id,
+                commitment: ballot_commitment(option_id, salt),
+                seed: rand::random(),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
+
+    async fn reveal_vote(
+        &self,
+        election_id: ElectionAddress,
+        option_id: i32,
+        salt: &Hash,
+        key_pair: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let tx = key_pair.reveal_vote(
+            BLOCKCHAIN_SERVICE_ID,
+            RevealVote {
                 election_id,
                 option_id,
+                salt: *salt,
                 seed: rand::random(),
             },
         );
@@ -163,574 +462,4016 @@ impl ElectionApi {
         tx
     }
 
-    async fn tx_info(&self, tx_hash: Hash) -> serde_json::Value {
-        self.inner
-            .public(ApiKind::Explorer)
-            .query(&TransactionQuery::new(tx_hash))
-            .get("v1/transactions")
-            .await
-            .unwrap()
+    async fn cast_encrypted_vote(
+        &self,
+        election_id: ElectionAddress,
+        option_index: usize,
+        key_pair: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let encryption = self.get_election_encryption(election_id).await;
+        let ballot = encryption::encrypt_ballot(
+            &election_id,
+            &pub_key_address(key_pair.public_key()),
+            &encryption.joint_key,
+            encryption.tally.len(),
+            option_index,
+            &mut rand::thread_rng(),
+        );
+        let tx = key_pair.cast_encrypted_vote(
+            BLOCKCHAIN_SERVICE_ID,
+            CastEncryptedVote {
+                election_id,
+                ballot,
+                seed: rand::random(),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
     }
 
-    async fn is_tx(&self, tx_hash: Hash, expected_status: &serde_json::Value) -> bool {
-        if let serde_json::Value::Object(mut info) = self.tx_info(tx_hash).await {
-            let tx_status = info.remove("status").unwrap();
-            tx_status == *expected_status
-        } else {
-            panic!("Invalid transaction info format, object expected");
-        }
+    async fn cast_mixnet_vote(
+        &self,
+        election_id: ElectionAddress,
+        values: &[u64],
+        key_pair: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let encryption = self.get_election_encryption(election_id).await;
+        let ballot = mixnet::encrypt_ballot(
+            &election_id,
+            &pub_key_address(key_pair.public_key()),
+            &encryption.joint_key,
+            values,
+            &mut rand::thread_rng(),
+        );
+        let tx = key_pair.cast_mixnet_vote(
+            BLOCKCHAIN_SERVICE_ID,
+            CastMixnetVote {
+                election_id,
+                ballot,
+                seed: rand::random(),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
     }
 
-    async fn assert_tx_status(&self, tx_hash: Hash, expected_status: &serde_json::Value) {
-        assert!(self.is_tx(tx_hash, expected_status).await)
+    async fn offer_voting_token(
+        &self,
+        election_id: ElectionAddress,
+        participant: &PublicKey,
+        administration_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let participant = pub_key_address(*participant);
+        let nonce =
+            voting_token::token_nonce(administration_key.secret_key(), &election_id, &participant);
+        let tx = administration_key.offer_voting_token(
+            BLOCKCHAIN_SERVICE_ID,
+            OfferVotingToken {
+                election_id,
+                participant,
+                commitment: NonceCommitment::from_nonce(&nonce),
+                seed: rand::random(),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
     }
 
-    async fn assert_tx_status_not(&self, tx_hash: Hash, expected_status: &serde_json::Value) {
-        assert!(!self.is_tx(tx_hash, expected_status).await)
+    async fn request_voting_token(
+        &self,
+        election_id: ElectionAddress,
+        challenge: BlindChallenge,
+        key_pair: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let tx = key_pair.request_voting_token(
+            BLOCKCHAIN_SERVICE_ID,
+            RequestVotingToken {
+                election_id,
+                challenge,
+                seed: rand::random(),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
     }
 
-    async fn assert_tx_successful(&self, tx_hash: Hash) {
-        self.assert_tx_status(tx_hash, &constant::tx_status_success())
-            .await
+    async fn issue_voting_token(
+        &self,
+        election_id: ElectionAddress,
+        participant: &PublicKey,
+        administration_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let session = self.get_voting_token(election_id, participant).await;
+        let participant = pub_key_address(*participant);
+        let secret = voting_token::token_secret(administration_key.secret_key(), &election_id);
+        let nonce =
+            voting_token::token_nonce(administration_key.secret_key(), &election_id, &participant);
+        let tx = administration_key.issue_voting_token(
+            BLOCKCHAIN_SERVICE_ID,
+            IssueVotingToken {
+                election_id,
+                participant,
+                response: BlindResponse::sign(&secret, &nonce, &session.challenge.0.unwrap()),
+                seed: rand::random(),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
     }
 
-    async fn assert_tx_fail(&self, tx_hash: Hash) {
-        // TODO: Create better implementation
-        self.assert_tx_status_not(tx_hash, &constant::tx_status_success())
-            .await
+    async fn cast_anonymous_vote(
+        &self,
+        election_id: ElectionAddress,
+        option_id: i32,
+        token: BlindSignature,
+        voting_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let tx = voting_key.cast_anonymous_vote(
+            BLOCKCHAIN_SERVICE_ID,
+            CastAnonymousVote {
+                election_id,
+                option_id,
+                token,
+                seed: rand::random(),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
     }
 
-    async fn assert_tx_hash(&self, tx: &Verified<AnyTx>) {
-        let tx_info: TransactionResponse = self
-            .inner
-            .public(ApiKind::Explorer)
-            .query(&serde_json::json!({ "tx_body": tx }))
-            .post("v1/transactions")
-            .await
-            .unwrap();
-        assert_eq!(tx_info.tx_hash, tx.object_hash());
+    async fn register_ring_key(
+        &self,
+        election_id: ElectionAddress,
+        key_pair: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let secret = ring_ballot::ring_secret(key_pair.secret_key(), &election_id);
+        let tx = key_pair.register_ring_key(
+            BLOCKCHAIN_SERVICE_ID,
+            RegisterRingKey {
+                election_id,
+                key: RingKey::from_secret(&secret),
+                seed: rand::random(),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
     }
 
-    async fn get_participant(&self, pub_key: &PublicKey) -> Option<Participant> {
-        let participant_info = self
+    /// Signs ballot with ring key of `key_pair` and sends it from a random key.
+    async fn cast_ring_vote(
+        &self,
+        election_id: ElectionAddress,
+        option_id: i32,
+        key_pair: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let ring: ElectionRing = self
             .inner
             .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
-            .query(&KeyQuery { key: *pub_key })
-            .get::<ParticipantInfo>("v1/participants/info")
+            .query(&KeyQuery { key: election_id })
+            .get("v1/elections/ring")
             .await
             .unwrap();
-
-        // Check parts of the proof returned together with the wallet.
-        let state_hash = participant_info.block_proof.block.state_hash;
-        let to_table = participant_info
-            .object_proof
-            .to_table
-            .check_against_hash(state_hash)
-            .unwrap();
-        let table_entries: Vec<_> = to_table.entries().collect();
-        assert_eq!(table_entries.len(), 1);
-        assert_eq!(
-            *table_entries[0].0,
-            format!("{}.participants", BLOCKCHAIN_SERVICE_NAME)
+        let signature = ring_ballot::sign_ballot(
+            &election_id,
+            option_id,
+            &ring.keys,
+            &ring_ballot::ring_secret(key_pair.secret_key(), &election_id),
+            &mut rand::thread_rng(),
+        )
+        .unwrap();
+        let tx = KeyPair::random().cast_ring_vote(
+            BLOCKCHAIN_SERVICE_ID,
+            CastRingVote {
+                election_id,
+                option_id,
+                signature,
+                seed: rand::random(),
+            },
         );
-        let table_hash = *table_entries[0].1;
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
 
-        let to_participant = participant_info
-            .object_proof
-            .to_object
-            .check_against_hash(table_hash)
-            .unwrap();
+    async fn get_voting_token(
+        &self,
+        election: ElectionAddress,
+        participant: &PublicKey,
+    ) -> TokenIssuance {
+        self.inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&ElectionParticipantQuery {
+                election,
+                participant: *participant,
+            })
+            .get("v1/elections/voting-token")
+            .await
+            .unwrap()
+    }
 
-        let address = pub_key_address(*pub_key);
+    async fn get_election_encryption(&self, id: ElectionAddress) -> ElectionEncryption {
+        self.inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&KeyQuery { key: id })
+            .get("v1/elections/encryption")
+            .await
+            .unwrap()
+    }
 
-        let (_, participant) = to_participant
-            .all_entries()
-            .find(|(&key, _)| key == address)?;
+    async fn get_mixnet_ballots(&self, id: ElectionAddress) -> MixnetBallots {
+        self.inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&KeyQuery { key: id })
+            .get("v1/elections/mixnet-ballots")
+            .await
+            .unwrap()
+    }
 
-        participant.cloned()
+    async fn set_participant_attribute(
+        &self,
+        participant: &PublicKey,
+        key: &str,
+        value: &str,
+        administration_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let tx = administration_key.set_participant_attributes(
+            BLOCKCHAIN_SERVICE_ID,
+            SetParticipantAttributes {
+                participant: pub_key_address(*participant),
+                attributes: vec![AttributeValue {
+                    key: key.to_owned(),
+                    value: value.to_owned(),
+                }],
+                seed: rand::random(),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
     }
 
-    async fn get_administration(&self, pub_key: &PublicKey) -> Option<Administration> {
-        let administration_info = self
-            .inner
+    async fn set_participant_status(
+        &self,
+        participant: &PublicKey,
+        status: ParticipantStatus,
+        administration_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let tx = administration_key.set_participant_status(
+            BLOCKCHAIN_SERVICE_ID,
+            SetParticipantStatus {
+                participant: pub_key_address(*participant),
+                status,
+                seed: rand::random(),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
+
+    async fn request_contact_verification(
+        &self,
+        field: PiiField,
+        requested_at: DateTime<Utc>,
+        participant_key: &KeyPair,
+    ) -> bool {
+        let participant = participant_key.public_key();
+        let message = contact::request_message(&pub_key_address(participant), field, requested_at);
+        self.inner
             .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
-            .query(&KeyQuery { key: *pub_key })
-            .get::<AdministrationInfo>("v1/administrations/info")
+            .query(&ContactVerificationQuery {
+                participant,
+                field,
+                requested_at,
+                signature: crypto::sign(message.as_ref(), participant_key.secret_key()),
+            })
+            .post::<()>("v1/participants/contacts/verify")
             .await
-            .unwrap();
+            .is_ok()
+    }
 
-        // Check parts of the proof returned together with the wallet.
-        let state_hash = administration_info.block_proof.block.state_hash;
-        let to_table = administration_info
-            .object_proof
-            .to_table
-            .check_against_hash(state_hash)
-            .unwrap();
-        let table_entries: Vec<_> = to_table.entries().collect();
-        assert_eq!(table_entries.len(), 1);
-        assert_eq!(
-            *table_entries[0].0,
-            format!("{}.administrations", BLOCKCHAIN_SERVICE_NAME)
+    async fn confirm_contact(
+        &self,
+        field: PiiField,
+        code: &str,
+        participant_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let participant = pub_key_address(participant_key.public_key());
+        let tx = participant_key.confirm_contact(
+            BLOCKCHAIN_SERVICE_ID,
+            ConfirmContact {
+                field,
+                code_mac: contact::code_mac(&participant, field, code),
+            },
         );
-        let table_hash = *table_entries[0].1;
-
-        let to_administration = administration_info
-            .object_proof
-            .to_object
-            .check_against_hash(table_hash)
-            .unwrap();
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
 
-        let address = pub_key_address(*pub_key);
+    async fn rotate_participant_key(
+        &self,
+        old_key: &KeyPair,
+        new_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let old = pub_key_address(old_key.public_key());
+        let tx = old_key.rotate_participant_key(
+            BLOCKCHAIN_SERVICE_ID,
+            RotateParticipantKey {
+                new_key: new_key.public_key(),
+                new_key_signature: key_rotation::sign_rotation(&old, new_key),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
 
-        let (_, administration) = to_administration
-            .all_entries()
-            .find(|(&key, _)| key == address)?;
+    async fn get_current_address(&self, pub_key: &PublicKey) -> Option<CallerAddress> {
+        self.inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&KeyQuery { key: *pub_key })
+            .get("v1/participants/current-address")
+            .await
+            .ok()
+    }
 
-        administration.cloned()
+    async fn succeed_administration(
+        &self,
+        old_key: &KeyPair,
+        new_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let old = pub_key_address(old_key.public_key());
+        let tx = old_key.succeed_administration(
+            BLOCKCHAIN_SERVICE_ID,
+            SucceedAdministration {
+                new_key: new_key.public_key(),
+                new_key_signature: key_rotation::sign_rotation(&old, new_key),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
     }
 
-    async fn get_election(&self, addr: &Hash) -> Option<Election> {
-        let election_info = self
-            .inner
+    async fn get_current_administration_address(
+        &self,
+        pub_key: &PublicKey,
+    ) -> Option<AdministrationAddress> {
+        self.inner
             .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
-            .query(&KeyQuery { key: *addr })
-            .get::<ElectionInfo>("v1/elections/info")
+            .query(&KeyQuery { key: *pub_key })
+            .get("v1/administrations/current-address")
             .await
-            .unwrap();
+            .ok()
+    }
 
-        // Check parts of the proof returned together with the wallet.
-        let state_hash = election_info.block_proof.block.state_hash;
-        let to_table = election_info
-            .object_proof
-            .to_table
-            .check_against_hash(state_hash)
-            .unwrap();
-        let table_entries: Vec<_> = to_table.entries().collect();
-        assert_eq!(table_entries.len(), 1);
-        assert_eq!(
-            *table_entries[0].0,
-            format!("{}.elections", BLOCKCHAIN_SERVICE_NAME)
+    async fn set_signers(
+        &self,
+        signers: &[PublicKey],
+        threshold: u32,
+        administration_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let tx = administration_key.set_signers(
+            BLOCKCHAIN_SERVICE_ID,
+            SetSigners {
+                signers: signers.iter().copied().map(pub_key_address).collect(),
+                threshold,
+            },
         );
-        let table_hash = *table_entries[0].1;
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
 
-        let to_election = election_info
-            .object_proof
-            .to_object
-            .check_against_hash(table_hash)
-            .unwrap();
+    async fn propose_action(
+        &self,
+        administration: &PublicKey,
+        action: ProposalAction,
+        signer_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let tx = signer_key.propose_action(
+            BLOCKCHAIN_SERVICE_ID,
+            ProposeAction {
+                administration: pub_key_address(*administration),
+                action,
+                seed: rand::random(),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
 
-        let (_, election) = to_election.all_entries().find(|(&key, _)| key == *addr)?;
+    async fn approve_proposal(&self, proposal_id: Hash, signer_key: &KeyPair) -> Verified<AnyTx> {
+        let tx =
+            signer_key.approve_proposal(BLOCKCHAIN_SERVICE_ID, ApproveProposal { proposal_id });
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
 
-        election.cloned()
+    async fn cancel_election(
+        &self,
+        election_id: ElectionAddress,
+        issuer_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let tx = issuer_key.cancel_election(
+            BLOCKCHAIN_SERVICE_ID,
+            CancelElection {
+                election_id,
+                seed: rand::random(),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
     }
 
-    async fn get_active_elections(&self, addr: &AdministrationAddress) -> Vec<Election> {
+    async fn get_proposals(&self, administration: &PublicKey) -> ProposalsInfo {
         self.inner
             .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
-            .query(&KeyQuery { key: *addr })
-            .get("v1/elections/active")
+            .query(&KeyQuery {
+                key: *administration,
+            })
+            .get("v1/administrations/proposals")
             .await
             .unwrap()
     }
 
-    async fn get_election_result(&self, id: ElectionAddress) -> HashMap<i32, u32> {
+    async fn submit_location(
+        &self,
+        position: [f64; 2],
+        participant_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let tx = participant_key.submit_location(
+            BLOCKCHAIN_SERVICE_ID,
+            SubmitLocation {
+                position: position.into(),
+                date: Utc::now(),
+                seed: rand::random(),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
+
+    async fn get_locations(&self, participant: &PublicKey) -> Vec<LocationRecord> {
         self.inner
             .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
-            .query(&KeyQuery { key: id })
-            .get("v1/elections/result")
+            .query(&KeyQuery { key: *participant })
+            .get("v1/participants/locations")
             .await
             .unwrap()
     }
-}
 
-fn create_test_kit() -> (TestKit, ElectionApi, MockTimeProvider) {
-    use crypto_election_node::model::transactions::Config;
-    use exonum_rust_runtime::spec::Spec;
+    async fn get_administrations_geojson(&self) -> FeatureCollection {
+        self.inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .get("v1/administrations/geojson")
+            .await
+            .unwrap()
+    }
 
-    let mock_provider = MockTimeProvider::new(SystemTime::now().into());
+    async fn set_guardians(
+        &self,
+        guardians: &[PublicKey],
+        threshold: u32,
+        participant_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let tx = participant_key.set_guardians(
+            BLOCKCHAIN_SERVICE_ID,
+            SetGuardians {
+                guardians: guardians.iter().copied().map(pub_key_address).collect(),
+                threshold,
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
 
-    let time_service = {
-        let service = TimeServiceFactory::with_provider(mock_provider.clone());
+    async fn approve_recovery(
+        &self,
+        participant: &PublicKey,
+        new_key: &KeyPair,
+        guardian_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let participant = pub_key_address(*participant);
+        let tx = guardian_key.approve_recovery(
+            BLOCKCHAIN_SERVICE_ID,
+            ApproveRecovery {
+                participant,
+                new_key: new_key.public_key(),
+                new_key_signature: key_rotation::sign_rotation(&participant, new_key),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
+
+    async fn complete_recovery(
+        &self,
+        participant: &PublicKey,
+        new_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let tx = new_key.complete_recovery(
+            BLOCKCHAIN_SERVICE_ID,
+            CompleteRecovery {
+                participant: pub_key_address(*participant),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
+
+    async fn cancel_recovery(&self, participant_key: &KeyPair) -> Verified<AnyTx> {
+        let tx = participant_key.cancel_recovery(
+            BLOCKCHAIN_SERVICE_ID,
+            CancelRecovery {
+                seed: rand::random(),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
+
+    async fn get_recovery(&self, participant: &PublicKey) -> RecoveryInfo {
+        self.inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&KeyQuery { key: *participant })
+            .get("v1/participants/recovery")
+            .await
+            .unwrap()
+    }
+
+    async fn issue_invitations(
+        &self,
+        codes: &[&str],
+        expires_at: DateTime<Utc>,
+        administration_key: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let issuer = pub_key_address(administration_key.public_key());
+        let tx = administration_key.issue_invitations(
+            BLOCKCHAIN_SERVICE_ID,
+            IssueInvitations {
+                keys: codes
+                    .iter()
+                    .map(|code| invitation::invitation_keys(&issuer, code).public_key())
+                    .collect(),
+                expires_at,
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
+
+    async fn create_invited_participant(
+        &self,
+        name: &str,
+        pass_code: &str,
+        issuer: &PublicKey,
+        invitation_code: &str,
+        key_pair: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let redemption = invitation::redeem(
+            &pub_key_address(*issuer),
+            invitation_code,
+            &pub_key_address(key_pair.public_key()),
+        );
+        self.create_participant_with_invitation(
+            name,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &None,
+            pass_code,
+            Some(redemption),
+            key_pair,
+        )
+        .await
+    }
+
+    async fn vote(
+        &self,
+        election_id: ElectionAddress,
+        option_id: i32,
+        key_pair: &KeyPair,
+    ) -> Verified<AnyTx> {
+        let tx = key_pair.vote(
+            BLOCKCHAIN_SERVICE_ID,
+            Vote {
+                election_id,
+                option_id,
+                seed: rand::random(),
+            },
+        );
+        self.assert_tx_hash(&tx).await;
+        tx
+    }
+
+    async fn get_election_result_proof(&self, id: ElectionAddress) -> TallyProof {
+        self.inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&KeyQuery { key: id })
+            .get("v1/elections/result-proof")
+            .await
+            .unwrap()
+    }
+
+    async fn get_vote_receipt(
+        &self,
+        election: ElectionAddress,
+        participant: &PublicKey,
+    ) -> VoteReceipt {
+        self.inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&ElectionParticipantQuery {
+                election,
+                participant: *participant,
+            })
+            .get("v1/elections/receipt")
+            .await
+            .unwrap()
+    }
+
+    async fn tx_info(&self, tx_hash: Hash) -> serde_json::Value {
+        self.inner
+            .public(ApiKind::Explorer)
+            .query(&TransactionQuery::new(tx_hash))
+            .get("v1/transactions")
+            .await
+            .unwrap()
+    }
+
+    async fn is_tx(&self, tx_hash: Hash, expected_status: &serde_json::Value) -> bool {
+        if let serde_json::Value::Object(mut info) = self.tx_info(tx_hash).await {
+            let tx_status = info.remove("status").unwrap();
+            tx_status == *expected_status
+        } else {
+            panic!("Invalid transaction info format, object expected");
+        }
+    }
+
+    async fn assert_tx_status(&self, tx_hash: Hash, expected_status: &serde_json::Value) {
+        assert!(self.is_tx(tx_hash, expected_status).await)
+    }
+
+    async fn assert_tx_status_not(&self, tx_hash: Hash, expected_status: &serde_json::Value) {
+        assert!(!self.is_tx(tx_hash, expected_status).await)
+    }
+
+    async fn assert_tx_successful(&self, tx_hash: Hash) {
+        self.assert_tx_status(tx_hash, &constant::tx_status_success())
+            .await
+    }
+
+    async fn assert_tx_fail(&self, tx_hash: Hash) {
+        // TODO: Create better implementation
+        self.assert_tx_status_not(tx_hash, &constant::tx_status_success())
+            .await
+    }
+
+    async fn assert_tx_hash(&self, tx: &Verified<AnyTx>) {
+        let tx_info: TransactionResponse = self
+            .inner
+            .public(ApiKind::Explorer)
+            .query(&serde_json::json!({ "tx_body": tx }))
+            .post("v1/transactions")
+            .await
+            .unwrap();
+        assert_eq!(tx_info.tx_hash, tx.object_hash());
+    }
+
+    async fn get_participant(&self, pub_key: &PublicKey) -> Option<Participant> {
+        let participant_info = self
+            .inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&KeyQuery { key: *pub_key })
+            .get::<ParticipantInfo>("v1/participants/info")
+            .await
+            .unwrap();
+
+        // Check parts of the proof returned together with the wallet.
+        let state_hash = participant_info.block_proof.block.state_hash;
+        let to_table = participant_info
+            .object_proof
+            .to_table
+            .check_against_hash(state_hash)
+            .unwrap();
+        let table_entries: Vec<_> = to_table.entries().collect();
+        assert_eq!(table_entries.len(), 1);
+        assert_eq!(
+            *table_entries[0].0,
+            format!("{}.participants", BLOCKCHAIN_SERVICE_NAME)
+        );
+        let table_hash = *table_entries[0].1;
+
+        let to_participant = participant_info
+            .object_proof
+            .to_object
+            .check_against_hash(table_hash)
+            .unwrap();
+
+        let address = pub_key_address(*pub_key);
+
+        let (_, participant) = to_participant
+            .all_entries()
+            .find(|(&key, _)| key == address)?;
+
+        participant.cloned()
+    }
+
+    async fn get_administration(&self, pub_key: &PublicKey) -> Option<Administration> {
+        let administration_info = self
+            .inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&KeyQuery { key: *pub_key })
+            .get::<AdministrationInfo>("v1/administrations/info")
+            .await
+            .unwrap();
+
+        // Check parts of the proof returned together with the wallet.
+        let state_hash = administration_info.block_proof.block.state_hash;
+        let to_table = administration_info
+            .object_proof
+            .to_table
+            .check_against_hash(state_hash)
+            .unwrap();
+        let table_entries: Vec<_> = to_table.entries().collect();
+        assert_eq!(table_entries.len(), 1);
+        assert_eq!(
+            *table_entries[0].0,
+            format!("{}.administrations", BLOCKCHAIN_SERVICE_NAME)
+        );
+        let table_hash = *table_entries[0].1;
+
+        let to_administration = administration_info
+            .object_proof
+            .to_object
+            .check_against_hash(table_hash)
+            .unwrap();
+
+        let address = pub_key_address(*pub_key);
+
+        let (_, administration) = to_administration
+            .all_entries()
+            .find(|(&key, _)| key == address)?;
+
+        administration.cloned()
+    }
+
+    async fn get_election(&self, addr: &Hash) -> Option<Election> {
+        let election_info = self
+            .inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&KeyQuery { key: *addr })
+            .get::<ElectionInfo>("v1/elections/info")
+            .await
+            .unwrap();
+
+        // Check parts of the proof returned together with the wallet.
+        let state_hash = election_info.block_proof.block.state_hash;
+        let to_table = election_info
+            .object_proof
+            .to_table
+            .check_against_hash(state_hash)
+            .unwrap();
+        let table_entries: Vec<_> = to_table.entries().collect();
+        assert_eq!(table_entries.len(), 1);
+        assert_eq!(
+            *table_entries[0].0,
+            format!("{}.elections", BLOCKCHAIN_SERVICE_NAME)
+        );
+        let table_hash = *table_entries[0].1;
+
+        let to_election = election_info
+            .object_proof
+            .to_object
+            .check_against_hash(table_hash)
+            .unwrap();
+
+        let (_, election) = to_election.all_entries().find(|(&key, _)| key == *addr)?;
+
+        election.cloned()
+    }
+
+    async fn get_active_elections(&self, addr: &AdministrationAddress) -> Vec<Election> {
+        self.inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&KeyQuery { key: *addr })
+            .get("v1/elections/active")
+            .await
+            .unwrap()
+    }
+
+    async fn are_election_results_hidden(&self, id: ElectionAddress) -> bool {
+        self.inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&KeyQuery { key: id })
+            .get::<HashMap<i32, u32>>("v1/elections/result")
+            .await
+            .is_err()
+    }
+
+    async fn is_vote_receipt_hidden(
+        &self,
+        election: ElectionAddress,
+        participant: &PublicKey,
+    ) -> bool {
+        self.inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&ElectionParticipantQuery {
+                election,
+                participant: *participant,
+            })
+            .get::<VoteReceipt>("v1/elections/receipt")
+            .await
+            .is_err()
+    }
+
+    async fn is_election_history_hidden(&self, id: ElectionAddress) -> bool {
+        self.inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&KeyQuery { key: id })
+            .get::<ElectionInfo>("v1/elections/info")
+            .await
+            .unwrap()
+            .history
+            .is_none()
+    }
+
+    async fn submit_pii(&self, participant: &PublicKey, openings: Vec<PiiOpening>) -> bool {
+        self.inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&PiiSubmission {
+                participant: *participant,
+                openings,
+            })
+            .post::<()>("v1/participants/pii")
+            .await
+            .is_ok()
+    }
+
+    /// Submits pass codes of created participants, so that validator attests their fingerprints.
+    async fn submit_pass_codes(&self) {
+        let pass_codes = std::mem::take(&mut *self.pass_codes.lock().unwrap());
+        for (participant, opening) in pass_codes {
+            self.submit_pii(&participant, vec![opening]).await;
+        }
+    }
+
+    async fn get_participant_pii(&self, participant: &PublicKey) -> Vec<PiiOpening> {
+        self.inner
+            .private(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&KeyQuery { key: *participant })
+            .get("v1/participants/pii")
+            .await
+            .unwrap()
+    }
+
+    async fn get_dedup_report(&self) -> DedupReport {
+        self.inner
+            .private(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .get("v1/participants/dedup-report")
+            .await
+            .unwrap()
+    }
+
+    async fn check_pii(&self, participant: &PublicKey, opening: &PiiOpening) -> bool {
+        self.inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&PiiCheckQuery {
+                participant: *participant,
+                field: opening.field,
+                value: opening.value.clone(),
+                salt: opening.salt,
+            })
+            .get("v1/participants/pii/check")
+            .await
+            .unwrap()
+    }
+
+    async fn get_invitation_report(&self, administration: &PublicKey) -> InvitationReport {
+        self.inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&KeyQuery {
+                key: *administration,
+            })
+            .get("v1/administrations/invitations")
+            .await
+            .unwrap()
+    }
+
+    async fn get_election_result(&self, id: ElectionAddress) -> HashMap<i32, u32> {
+        self.inner
+            .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+            .query(&KeyQuery { key: id })
+            .get("v1/elections/result")
+            .await
+            .unwrap()
+    }
+}
+
+fn create_test_kit() -> (TestKit, ElectionApi, MockTimeProvider) {
+    create_test_kit_with_service(ElectionService::default())
+}
+
+fn create_test_kit_with_service(
+    service: ElectionService,
+) -> (TestKit, ElectionApi, MockTimeProvider) {
+    use crypto_election_node::model::transactions::Config;
+    use exonum_rust_runtime::spec::Spec;
+
+    let mock_provider = MockTimeProvider::new(SystemTime::now().into());
+
+    let time_service = {
+        let service = TimeServiceFactory::with_provider(mock_provider.clone());
+
+        Spec::new(service).with_instance(TIME_SERVICE_ID, TIME_SERVICE_NAME, ())
+    };
+
+    let election_service = {
+        let config = Config {
+            time_service_name: TIME_SERVICE_NAME.to_owned(),
+        };
+
+        Spec::new(service).with_instance(BLOCKCHAIN_SERVICE_ID, BLOCKCHAIN_SERVICE_NAME, config)
+    };
+
+    let mut test_kit = TestKitBuilder::validator()
+        .with(Spec::new(ExplorerFactory).with_default_instance())
+        .with(time_service)
+        .with(election_service)
+        .build();
+
+    let api = ElectionApi {
+        inner: test_kit.api(),
+        validator_keys: test_kit.us().service_keypair(),
+        pass_codes: Mutex::default(),
+        time_provider: mock_provider.clone(),
+    };
+
+    test_kit.create_blocks_until(Height(2)); // Ensure that time is set
+
+    (test_kit, api, mock_provider)
+}
+
+/// Square area with lower left corner at (`x`, `y`).
+fn square(x: f64, y: f64, size: f64) -> Polygon {
+    Polygon {
+        interiors: Vec::with_capacity(0),
+        exterior: vec![
+            [x, y],
+            [x + size, y],
+            [x + size, y + size],
+            [x, y + size],
+            [x, y],
+        ]
+        .into(),
+    }
+}
+
+fn square_area(x: f64, y: f64, size: f64) -> MultiPolygon {
+    square(x, y, size).into()
+}
+
+fn default_area() -> MultiPolygon {
+    square_area(0.0, 0.0, 10.0)
+}
+
+#[tokio::test]
+async fn create_participant() {
+    let (mut test_kit, api, _) = create_test_kit();
+
+    let (tx, key_pair) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &None,
+            participant1::PASS_CODE,
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.assert_tx_successful(tx.object_hash()).await;
+
+    let participant = api.get_participant(&tx.author()).await.unwrap();
+
+    assert_eq!(participant.addr, author_address(&tx));
+    assert_eq!(participant.name, participant1::NAME);
+    let openings = pii_openings(
+        &key_pair,
+        participant1::EMAIL,
+        participant1::PHONE_NUMBER,
+        participant1::PASS_CODE,
+    );
+    assert!(openings.iter().all(|opening| opening.opens(&participant)));
+}
+
+#[tokio::test]
+async fn create_administration() {
+    let (mut test_kit, api, _) = create_test_kit();
+
+    let (tx, _) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+
+    test_kit.create_block();
+
+    api.assert_tx_successful(tx.object_hash()).await;
+
+    let administration = api.get_administration(&tx.author()).await.unwrap();
+
+    assert_eq!(administration.name, administration1::NAME);
+}
+
+#[tokio::test]
+async fn reject_create_different_participants_with_same_keys() {
+    let (mut test_kit, api, _) = create_test_kit();
+
+    let (tx, key) = api
+        .create_participant_with_random_key(
+            "John Doe",
+            "john_doe@example.com",
+            "1212",
+            &None,
+            "111",
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.assert_tx_successful(tx.object_hash()).await;
+
+    // using same key
+    let tx = api
+        .create_participant(
+            "John Smith",
+            "john_smith@example.com",
+            "2121",
+            &None,
+            "222",
+            &key,
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.assert_tx_fail(tx.object_hash()).await;
+}
+
+#[tokio::test]
+async fn reject_create_different_administrations_with_same_keys() {
+    let (mut test_kit, api, _) = create_test_kit();
+
+    let (tx, key) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+
+    test_kit.create_block();
+
+    api.assert_tx_successful(tx.object_hash()).await;
+
+    // using same key
+    let tx = api
+        .create_administration(administration2::NAME, Some(&key), &default_area(), &key)
+        .await;
+
+    test_kit.create_block();
+
+    api.assert_tx_fail(tx.object_hash()).await;
+}
+
+#[tokio::test]
+async fn administration_creation_requires_approval() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
+
+    // Root administration is approved by validators only.
+    let key_root = KeyPair::random();
+    let tx_self_approved = api
+        .create_approved_administration(
+            administration1::NAME,
+            None,
+            &default_area(),
+            &[&key_root],
+            &key_root,
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_self_approved.object_hash()).await;
+
+    // Approvals are valid until the request expires.
+    let now = time_provider.time();
+    let tx_expired = api
+        .create_expiring_administration(
+            administration1::NAME,
+            None,
+            &default_area(),
+            &[&api.validator_keys],
+            now - Duration::minutes(1),
+            &key_root,
+        )
+        .await;
+    let tx_far_expiry = api
+        .create_expiring_administration(
+            administration1::NAME,
+            None,
+            &default_area(),
+            &[&api.validator_keys],
+            now + approval::approval_lifetime() + Duration::hours(1),
+            &key_root,
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_expired.object_hash()).await;
+    api.assert_tx_fail(tx_far_expiry.object_hash()).await;
+
+    let tx_root = api
+        .create_administration(administration1::NAME, None, &default_area(), &key_root)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_root.object_hash()).await;
+
+    // Child administration is approved by its principal, which must exist.
+    let key_child = KeyPair::random();
+    let tx_unapproved = api
+        .create_approved_administration(
+            administration2::NAME,
+            Some(&key_root),
+            &default_area(),
+            &[&api.validator_keys],
+            &key_child,
+        )
+        .await;
+    let tx_unknown_principal = api
+        .create_administration_with_random_key(
+            administration2::NAME,
+            Some(&KeyPair::random()),
+            &default_area(),
+        )
+        .await
+        .0;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_unapproved.object_hash()).await;
+    api.assert_tx_fail(tx_unknown_principal.object_hash()).await;
+
+    let tx_child = api
+        .create_administration(
+            administration2::NAME,
+            Some(&key_root),
+            &default_area(),
+            &key_child,
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_child.object_hash()).await;
+
+    let child = api
+        .get_administration(&key_child.public_key())
+        .await
+        .unwrap();
+    assert_eq!(
+        child.principal_key.0,
+        Some(pub_key_address(key_root.public_key()))
+    );
+    assert_eq!(child.administration_level, 1);
+}
+
+#[tokio::test]
+async fn administration_area_is_validated() {
+    let (mut test_kit, api, _) = create_test_kit();
+
+    let self_intersecting = Polygon {
+        interiors: Vec::new(),
+        exterior: vec![
+            [0.0, 0.0],
+            [10.0, 10.0],
+            [10.0, 0.0],
+            [0.0, 10.0],
+            [0.0, 0.0],
+        ]
+        .into(),
+    };
+    let clockwise = Polygon {
+        interiors: Vec::new(),
+        exterior: vec![
+            [0.0, 0.0],
+            [0.0, 10.0],
+            [10.0, 10.0],
+            [10.0, 0.0],
+            [0.0, 0.0],
+        ]
+        .into(),
+    };
+    let unclosed = Polygon {
+        interiors: Vec::new(),
+        exterior: vec![[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]].into(),
+    };
+    let overlapping_parts = MultiPolygon {
+        polygons: vec![square(0.0, 0.0, 5.0), square(2.0, 2.0, 5.0)],
+    };
+    let no_parts = MultiPolygon { polygons: vec![] };
+    let invalid_areas: Vec<MultiPolygon> = vec![
+        self_intersecting.into(),
+        clockwise.into(),
+        unclosed.into(),
+        overlapping_parts,
+        no_parts,
+    ];
+    let mut invalid_txs = Vec::new();
+    for area in &invalid_areas {
+        let (tx, _) = api
+            .create_administration_with_random_key(administration1::NAME, None, area)
+            .await;
+        invalid_txs.push(tx);
+    }
+    let (tx_root, key_root) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+    test_kit.create_block();
+    for tx in invalid_txs {
+        api.assert_tx_fail(tx.object_hash()).await;
+    }
+    api.assert_tx_successful(tx_root.object_hash()).await;
+
+    // Child area must lie within the principal area.
+    let (tx_outside, _) = api
+        .create_administration_with_random_key(
+            administration2::NAME,
+            Some(&key_root),
+            &square_area(5.0, 5.0, 10.0),
+        )
+        .await;
+    let (tx_child, _) = api
+        .create_administration_with_random_key(
+            administration2::NAME,
+            Some(&key_root),
+            &square_area(0.0, 0.0, 5.0),
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_outside.object_hash()).await;
+    api.assert_tx_successful(tx_child.object_hash()).await;
+
+    // Siblings may touch, but not overlap.
+    let (tx_overlapping, _) = api
+        .create_administration_with_random_key(
+            administration2::NAME,
+            Some(&key_root),
+            &square_area(2.0, 2.0, 5.0),
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_overlapping.object_hash()).await;
+
+    let (tx_adjacent, _) = api
+        .create_administration_with_random_key(
+            administration2::NAME,
+            Some(&key_root),
+            &square_area(5.0, 0.0, 5.0),
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_adjacent.object_hash()).await;
+}
+
+#[tokio::test]
+async fn submitted_location_resolves_to_deepest_administration() {
+    let (mut test_kit, api, _) = create_test_kit();
+
+    let (_, key_country) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+    test_kit.create_block();
+    let (_, key_region) = api
+        .create_administration_with_random_key(
+            administration2::NAME,
+            Some(&key_country),
+            &square_area(0.0, 0.0, 5.0),
+        )
+        .await;
+    test_kit.create_block();
+    let (_, key_district) = api
+        .create_administration_with_random_key(
+            administration2::NAME,
+            Some(&key_region),
+            &square_area(0.0, 0.0, 2.0),
+        )
+        .await;
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &None,
+            participant1::PASS_CODE,
+        )
+        .await;
+    test_kit.create_block();
+
+    let tx_district = api.submit_location([1.0, 1.0], &key_alice).await;
+    test_kit.create_block();
+    let tx_country = api.submit_location([8.0, 8.0], &key_alice).await;
+    test_kit.create_block();
+    let tx_outside = api.submit_location([20.0, 20.0], &key_alice).await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_district.object_hash()).await;
+    api.assert_tx_successful(tx_country.object_hash()).await;
+    api.assert_tx_fail(tx_outside.object_hash()).await;
+
+    let chains: Vec<_> = api
+        .get_locations(&key_alice.public_key())
+        .await
+        .into_iter()
+        .map(|location| location.administrations)
+        .collect();
+    let address = |keys: &KeyPair| pub_key_address(keys.public_key());
+    assert_eq!(
+        chains,
+        vec![
+            vec![
+                address(&key_district),
+                address(&key_region),
+                address(&key_country)
+            ],
+            vec![address(&key_country)],
+        ]
+    );
+}
+
+#[tokio::test]
+#[ignore = "not implemented yet"]
+async fn select_administration_principals() {
+    let (mut test_kit, api, _) = create_test_kit();
+
+    let (tx_a1, key_a1) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+    let (tx_a2, _) = api
+        .create_administration_with_random_key(
+            administration2::NAME,
+            Some(&key_a1),
+            &default_area(),
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.assert_tx_successful(tx_a1.object_hash()).await;
+    api.assert_tx_successful(tx_a2.object_hash()).await;
+
+    // FixMe: adapt this test for new version
+
+    //let snapshot = test_kit.snapshot();
+    //
+    //let schema = ElectionSchema::new(&snapshot);
+    //
+    //let a1_principals = schema
+    //    .iter_principals(&tx_a1.author())
+    //    .unwrap()
+    //    .collect::<Box<[_]>>();
+    //let a2_principals = schema
+    //    .iter_principals(&tx_a2.author())
+    //    .unwrap()
+    //    .collect::<Box<[_]>>();
+    //
+    //assert_eq!(a1_principals.len(), 0);
+    //assert_eq!(a2_principals.len(), 1);
+    //assert_eq!(a2_principals[0].pub_key, tx_a1.author());
+}
+
+#[tokio::test]
+#[ignore = "not implemented yet"]
+async fn select_principals_elections() {
+    //ToDo: Add participants selection
+}
+
+#[tokio::test]
+async fn issue_election() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
+
+    let (tx_administration, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+
+    test_kit.create_block();
+
+    let elections_before = api
+        .get_active_elections(&author_address(&tx_administration))
+        .await;
+
+    assert!(elections_before.is_empty());
+
+    let start_date = time_provider.time();
+    let finish_date = start_date + Duration::hours(1);
+
+    let election_address = hash(&KeyPair::random().secret_key()[..]);
+
+    let create_election_tx = api
+        .issue_election(
+            election_address,
+            election1::NAME,
+            &start_date,
+            &finish_date,
+            election1::OPTIONS,
+            &key_administration,
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.assert_tx_successful(create_election_tx.object_hash())
+        .await;
+
+    let elections_after = api
+        .get_active_elections(&author_address(&tx_administration))
+        .await;
+
+    assert_eq!(elections_after.len(), 1);
+
+    let election = api.get_election(&election_address).await.unwrap();
+
+    assert_eq!(election1::NAME, election.name);
+    assert_eq!(start_date, election.start_date);
+    assert_eq!(finish_date, election.finish_date);
+    assert_eq!(
+        pub_key_address(key_administration.public_key()),
+        election.issuer
+    )
+}
+
+#[tokio::test]
+async fn reject_issuing_election_by_unknown_administration() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
+
+    let start_date = time_provider.time();
+    let finish_date = start_date + Duration::hours(1);
+
+    // Case 1: Issuing election with random key
+    let create_election_tx = api
+        .issue_election(
+            hash(&KeyPair::random().secret_key()[..]),
+            election1::NAME,
+            &start_date,
+            &finish_date,
+            election1::OPTIONS,
+            &KeyPair::random(),
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.assert_tx_fail(create_election_tx.object_hash()).await;
+
+    // Case 2: Issuing election with participant key
+    let (tx, participant_key) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &None,
+            participant1::PASS_CODE,
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.assert_tx_successful(tx.object_hash()).await;
+
+    let create_election_tx = api
+        .issue_election(
+            hash(&KeyPair::random().secret_key()[..]),
+            election1::NAME,
+            &start_date,
+            &finish_date,
+            election1::OPTIONS,
+            &participant_key,
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.assert_tx_fail(create_election_tx.object_hash()).await;
+}
+
+#[tokio::test]
+async fn election_results_counting() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
+
+    let (tx_administration, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant1::PASS_CODE,
+        )
+        .await;
+
+    let (_, key_bob) = api
+        .create_participant_with_random_key(
+            participant2::NAME,
+            participant2::EMAIL,
+            participant2::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant2::PASS_CODE,
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.submit_pass_codes().await;
+    test_kit.create_block();
+
+    api.set_participant_status(
+        &key_alice.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    api.set_participant_status(
+        &key_bob.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
+
+    let now = time_provider.time();
+
+    let election_address = hash(&KeyPair::random().secret_key()[..]);
+
+    let create_election_tx = api
+        .issue_election(
+            election_address,
+            election1::NAME,
+            &now,
+            &(now + Duration::hours(1)),
+            election1::OPTIONS,
+            &key_administration,
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.assert_tx_successful(create_election_tx.object_hash())
+        .await;
+
+    let elections = api
+        .get_active_elections(&author_address(&tx_administration))
+        .await;
+
+    assert_eq!(elections.len(), 1);
+
+    let election = &elections[0];
+
+    let options = &election.options;
+
+    assert_eq!(options.len(), 3);
+
+    let mut expected: HashMap<i32, u32> = options.iter().map(|op| (op.id, 0)).collect();
+
+    let results = api.get_election_result(election.addr).await;
+
+    assert_eq!(results, expected);
+
+    let alice_position = options[0].id;
+    let bob_position = options[2].id;
+
+    let tx_vote_alice = api.vote(election.addr, alice_position, &key_alice).await;
+    let tx_vote_bob = api.vote(election.addr, bob_position, &key_bob).await;
+    test_kit.create_block();
+
+    api.assert_tx_successful(tx_vote_alice.object_hash()).await;
+    api.assert_tx_successful(tx_vote_bob.object_hash()).await;
+
+    *expected.get_mut(&bob_position).unwrap() += 1;
+    *expected.get_mut(&alice_position).unwrap() += 1;
+
+    let results = api.get_election_result(election.addr).await;
+
+    assert_eq!(results, expected);
+}
+
+#[tokio::test]
+async fn vote_restricted_by_participant_attribute() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
+
+    let (tx_administration, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+
+    test_kit.create_block();
+
+    let residence = Some(tx_administration.author());
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &residence,
+            participant1::PASS_CODE,
+        )
+        .await;
+
+    let now = time_provider.time();
+    let election_address = hash(&KeyPair::random().secret_key()[..]);
+
+    let tx_election = api
+        .issue_restricted_election(
+            election_address,
+            election1::NAME,
+            &now,
+            &(now + Duration::hours(1)),
+            election1::OPTIONS,
+            vec![
+                EligibilityTerm::and(),
+                EligibilityTerm::attribute_in("citizen", &["yes"]),
+                EligibilityTerm::resides_within(&author_address(&tx_administration)),
+            ],
+            &key_administration,
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.assert_tx_successful(tx_election.object_hash()).await;
+
+    let tx_vote = api.vote(election_address, 1, &key_alice).await;
+    test_kit.create_block();
+
+    api.assert_tx_fail(tx_vote.object_hash()).await;
+
+    let tx_attribute = api
+        .set_participant_attribute(
+            &key_alice.public_key(),
+            "citizen",
+            "yes",
+            &key_administration,
+        )
+        .await;
+    api.submit_pass_codes().await;
+    test_kit.create_block();
+
+    let tx_verify = api
+        .set_participant_status(
+            &key_alice.public_key(),
+            ParticipantStatus::Verified,
+            &key_administration,
+        )
+        .await;
+    test_kit.create_block();
+
+    api.assert_tx_successful(tx_attribute.object_hash()).await;
+    api.assert_tx_successful(tx_verify.object_hash()).await;
+
+    let participant = api.get_participant(&key_alice.public_key()).await.unwrap();
+    assert_eq!(participant.attribute("citizen"), Some("yes"));
+
+    let tx_vote = api.vote(election_address, 1, &key_alice).await;
+    test_kit.create_block();
+
+    api.assert_tx_successful(tx_vote.object_hash()).await;
+}
+
+#[tokio::test]
+async fn commit_reveal_election_counts_revealed_ballots_only() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
+
+    let (_, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant1::PASS_CODE,
+        )
+        .await;
+    let (_, key_bob) = api
+        .create_participant_with_random_key(
+            participant2::NAME,
+            participant2::EMAIL,
+            participant2::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant2::PASS_CODE,
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.submit_pass_codes().await;
+    test_kit.create_block();
+
+    api.set_participant_status(
+        &key_alice.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    api.set_participant_status(
+        &key_bob.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
+
+    let now = time_provider.time();
+    let finish_date = now + Duration::hours(1);
+    let election_address = hash(&KeyPair::random().secret_key()[..]);
+
+    let tx_election = api
+        .issue_election_with_mode(
+            election_address,
+            &now,
+            &finish_date,
+            BallotMode::CommitReveal,
+            &(finish_date + Duration::hours(1)),
+            &key_administration,
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_election.object_hash()).await;
+
+    let alice_salt = hash(b"alice salt");
+    let bob_salt = hash(b"bob salt");
+
+    // Open ballots are not accepted by commit-reveal election.
+    let tx_open_vote = api.vote(election_address, 1, &key_alice).await;
+    let tx_alice = api
+        .commit_vote(election_address, 1, &alice_salt, &key_alice)
+        .await;
+    let tx_bob = api
+        .commit_vote(election_address, 2, &bob_salt, &key_bob)
+        .await;
+    test_kit.create_block();
+
+    api.assert_tx_fail(tx_open_vote.object_hash()).await;
+    api.assert_tx_successful(tx_alice.object_hash()).await;
+    api.assert_tx_successful(tx_bob.object_hash()).await;
+
+    // Reveal is not allowed before election finish.
+    let tx_early_reveal = api
+        .reveal_vote(election_address, 1, &alice_salt, &key_alice)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_early_reveal.object_hash()).await;
+
+    time_provider.add_time(Duration::minutes(90));
+    test_kit.create_blocks_until(test_kit.height().next().next());
+
+    let tx_wrong_reveal = api
+        .reveal_vote(election_address, 2, &alice_salt, &key_alice)
+        .await;
+    let tx_reveal = api
+        .reveal_vote(election_address, 1, &alice_salt, &key_alice)
+        .await;
+    test_kit.create_block();
+
+    api.assert_tx_fail(tx_wrong_reveal.object_hash()).await;
+    api.assert_tx_successful(tx_reveal.object_hash()).await;
+
+    let results = api.get_election_result(election_address).await;
+    assert_eq!(results[&1], 1);
+    assert_eq!(results[&2], 0);
+
+    let unrevealed: UnrevealedBallots = api
+        .inner
+        .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+        .query(&KeyQuery {
+            key: election_address,
+        })
+        .get("v1/elections/unrevealed")
+        .await
+        .unwrap();
+    assert_eq!(unrevealed.count, 1);
+    assert_eq!(
+        unrevealed.participants,
+        vec![pub_key_address(key_bob.public_key())]
+    );
+}
+
+#[tokio::test]
+async fn encrypted_election_tally_is_decrypted_by_trustees() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
+
+    let (_, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant1::PASS_CODE,
+        )
+        .await;
+    let (_, key_bob) = api
+        .create_participant_with_random_key(
+            participant2::NAME,
+            participant2::EMAIL,
+            participant2::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant2::PASS_CODE,
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.submit_pass_codes().await;
+    test_kit.create_block();
+
+    api.set_participant_status(
+        &key_alice.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    api.set_participant_status(
+        &key_bob.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
+
+    let now = time_provider.time();
+    let finish_date = now + Duration::hours(1);
+    let election_address = hash(&KeyPair::random().secret_key()[..]);
+
+    let tx_election = api
+        .issue_election_with_mode(
+            election_address,
+            &now,
+            &finish_date,
+            BallotMode::Encrypted,
+            &finish_date,
+            &key_administration,
+        )
+        .await;
+    // Election is issued in the first block, trustee publishes transport key, dealing and
+    // acknowledgement in the next three ones.
+    test_kit.create_blocks_until(Height(test_kit.height().0 + 4));
+    api.assert_tx_successful(tx_election.object_hash()).await;
+
+    let encryption = api.get_election_encryption(election_address).await;
+    assert!(encryption.is_key_ready);
+    assert_eq!(encryption.threshold, 1);
+    assert_eq!(encryption.qualified, vec![api.validator_keys.public_key()]);
+    assert_eq!(encryption.tally.len(), election1::OPTIONS.len());
+
+    let tx_alice = api
+        .cast_encrypted_vote(election_address, 2, &key_alice)
+        .await;
+    let tx_bob = api.cast_encrypted_vote(election_address, 2, &key_bob).await;
+    test_kit.create_block();
+
+    api.assert_tx_successful(tx_alice.object_hash()).await;
+    api.assert_tx_successful(tx_bob.object_hash()).await;
+
+    // Ballots are not stored in clear and results are unknown until decryption.
+    assert!(api
+        .inner
+        .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+        .query(&KeyQuery {
+            key: election_address
+        })
+        .get::<HashMap<i32, u32>>("v1/elections/result")
+        .await
+        .is_err());
+
+    time_provider.add_time(Duration::hours(2));
+    test_kit.create_blocks_until(test_kit.height().next().next().next());
+
+    let encryption = api.get_election_encryption(election_address).await;
+    assert!(encryption.is_decrypted);
+    assert_eq!(encryption.ballots_count, 2);
+
+    let results = api.get_election_result(election_address).await;
+    assert_eq!(results[&1], 0);
+    assert_eq!(results[&2], 0);
+    assert_eq!(results[&3], 2);
+}
+
+#[tokio::test]
+async fn anonymous_election_accepts_one_ballot_per_token() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
+
+    let (_, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant1::PASS_CODE,
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.submit_pass_codes().await;
+    test_kit.create_block();
+
+    api.set_participant_status(
+        &key_alice.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
+
+    let now = time_provider.time();
+    let election_address = hash(&KeyPair::random().secret_key()[..]);
+    let token_key = VerificationKey::from_secret(&voting_token::token_secret(
+        key_administration.secret_key(),
+        &election_address,
+    ));
+
+    let tx_election = api
+        .issue_anonymous_election(
+            election_address,
+            &now,
+            &(now + Duration::hours(1)),
+            token_key,
+            &key_administration,
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_election.object_hash()).await;
+
+    let tx_offer = api
+        .offer_voting_token(
+            election_address,
+            &key_alice.public_key(),
+            &key_administration,
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_offer.object_hash()).await;
+
+    // Token is blinded for the fresh key, which is not known to anybody else.
+    let voting_key = KeyPair::random();
+    let session = api
+        .get_voting_token(election_address, &key_alice.public_key())
+        .await;
+    let (blinding, challenge) = voting_token::blind_token(
+        &election_address,
+        &pub_key_address(voting_key.public_key()),
+        &token_key,
+        &session.commitment,
+        &mut rand::thread_rng(),
+    );
+
+    let tx_request = api
+        .request_voting_token(election_address, challenge, &key_alice)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_request.object_hash()).await;
+
+    let tx_issue = api
+        .issue_voting_token(
+            election_address,
+            &key_alice.public_key(),
+            &key_administration,
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_issue.object_hash()).await;
+
+    let session = api
+        .get_voting_token(election_address, &key_alice.public_key())
+        .await;
+    let token = blinding.unblind(&session.response.0.unwrap());
+
+    // Token is valid for the voting key only.
+    let tx_stolen = api
+        .cast_anonymous_vote(election_address, 1, token, &KeyPair::random())
+        .await;
+    let tx_vote = api
+        .cast_anonymous_vote(election_address, 2, token, &voting_key)
+        .await;
+    test_kit.create_block();
+
+    api.assert_tx_fail(tx_stolen.object_hash()).await;
+    api.assert_tx_successful(tx_vote.object_hash()).await;
+
+    let tx_double_vote = api
+        .cast_anonymous_vote(election_address, 1, token, &voting_key)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_double_vote.object_hash()).await;
+
+    let results = api.get_election_result(election_address).await;
+    assert_eq!(results[&1], 0);
+    assert_eq!(results[&2], 1);
+}
+
+#[tokio::test]
+async fn anonymous_election_keeps_one_signing_session_open() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
+
+    let (_, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+
+    let mut participants = Vec::new();
+    for (name, email, phone_number, pass_code) in &[
+        (
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            participant1::PASS_CODE,
+        ),
+        (
+            participant2::NAME,
+            participant2::EMAIL,
+            participant2::PHONE_NUMBER,
+            participant2::PASS_CODE,
+        ),
+        (
+            participant3::NAME,
+            participant3::EMAIL,
+            participant3::PHONE_NUMBER,
+            participant3::PASS_CODE,
+        ),
+    ] {
+        let (_, key) = api
+            .create_participant_with_random_key(
+                name,
+                email,
+                phone_number,
+                &Some(key_administration.public_key()),
+                pass_code,
+            )
+            .await;
+        participants.push(key);
+    }
+    test_kit.create_block();
+
+    api.submit_pass_codes().await;
+    test_kit.create_block();
+
+    for key in &participants {
+        api.set_participant_status(
+            &key.public_key(),
+            ParticipantStatus::Verified,
+            &key_administration,
+        )
+        .await;
+    }
+    test_kit.create_block();
+
+    let now = time_provider.time();
+    let election_address = hash(&KeyPair::random().secret_key()[..]);
+    let token_key = VerificationKey::from_secret(&voting_token::token_secret(
+        key_administration.secret_key(),
+        &election_address,
+    ));
+    api.issue_anonymous_election(
+        election_address,
+        &now,
+        &(now + Duration::hours(1)),
+        token_key,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
+
+    let (key_alice, key_bob, key_carol) = (&participants[0], &participants[1], &participants[2]);
+    let challenge = |session: &TokenIssuance| {
+        voting_token::blind_token(
+            &election_address,
+            &pub_key_address(KeyPair::random().public_key()),
+            &token_key,
+            &session.commitment,
+            &mut rand::thread_rng(),
+        )
+        .1
+    };
+
+    api.offer_voting_token(
+        election_address,
+        &key_alice.public_key(),
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
+    let session = api
+        .get_voting_token(election_address, &key_alice.public_key())
+        .await;
+    let tx_request = api
+        .request_voting_token(election_address, challenge(&session), key_alice)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_request.object_hash()).await;
+
+    // Challenged session must be answered before the next one opens.
+    let tx_offer = api
+        .offer_voting_token(election_address, &key_bob.public_key(), &key_administration)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_offer.object_hash()).await;
+
+    api.issue_voting_token(
+        election_address,
+        &key_alice.public_key(),
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
+
+    let tx_offer = api
+        .offer_voting_token(election_address, &key_bob.public_key(), &key_administration)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_offer.object_hash()).await;
+    let session_bob = api
+        .get_voting_token(election_address, &key_bob.public_key())
+        .await;
+
+    // Unchallenged session is abandoned by the next offer.
+    let tx_offer = api
+        .offer_voting_token(
+            election_address,
+            &key_carol.public_key(),
+            &key_administration,
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_offer.object_hash()).await;
+
+    let session_carol = api
+        .get_voting_token(election_address, &key_carol.public_key())
+        .await;
+    let tx_late_request = api
+        .request_voting_token(election_address, challenge(&session_bob), key_bob)
+        .await;
+    let tx_request = api
+        .request_voting_token(election_address, challenge(&session_carol), key_carol)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_late_request.object_hash()).await;
+    api.assert_tx_successful(tx_request.object_hash()).await;
+}
+
+#[tokio::test]
+async fn open_token_session_follows_rotated_key() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
+
+    let (_, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant1::PASS_CODE,
+        )
+        .await;
+    let (_, key_bob) = api
+        .create_participant_with_random_key(
+            participant2::NAME,
+            participant2::EMAIL,
+            participant2::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant2::PASS_CODE,
+        )
+        .await;
+    test_kit.create_block();
+
+    api.submit_pass_codes().await;
+    test_kit.create_block();
+
+    for key in &[&key_alice, &key_bob] {
+        api.set_participant_status(
+            &key.public_key(),
+            ParticipantStatus::Verified,
+            &key_administration,
+        )
+        .await;
+    }
+    test_kit.create_block();
+
+    let now = time_provider.time();
+    let election_address = hash(&KeyPair::random().secret_key()[..]);
+    let token_key = VerificationKey::from_secret(&voting_token::token_secret(
+        key_administration.secret_key(),
+        &election_address,
+    ));
+    api.issue_anonymous_election(
+        election_address,
+        &now,
+        &(now + Duration::hours(1)),
+        token_key,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
+
+    let tx_offer = api
+        .offer_voting_token(
+            election_address,
+            &key_alice.public_key(),
+            &key_administration,
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_offer.object_hash()).await;
+
+    // Session opened for the former key is moved along with the participant.
+    let key_new = KeyPair::random();
+    let tx_rotate = api.rotate_participant_key(&key_alice, &key_new).await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_rotate.object_hash()).await;
+    let session = api
+        .get_voting_token(election_address, &key_new.public_key())
+        .await;
+    assert!(session.challenge.0.is_none());
+
+    // Unchallenged session of the rotated key is abandoned by the next offer.
+    let tx_offer = api
+        .offer_voting_token(election_address, &key_bob.public_key(), &key_administration)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_offer.object_hash()).await;
+}
+
+#[tokio::test]
+async fn ring_election_accepts_one_ballot_per_ring_key() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
+
+    let (_, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant1::PASS_CODE,
+        )
+        .await;
+    let (_, key_bob) = api
+        .create_participant_with_random_key(
+            participant2::NAME,
+            participant2::EMAIL,
+            participant2::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant2::PASS_CODE,
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.submit_pass_codes().await;
+    test_kit.create_block();
+
+    api.set_participant_status(
+        &key_alice.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    api.set_participant_status(
+        &key_bob.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
+
+    let start_date = time_provider.time() + Duration::hours(1);
+    let finish_date = start_date + Duration::hours(1);
+    let election_address = hash(&KeyPair::random().secret_key()[..]);
+
+    let tx_election = api
+        .issue_election_with_mode(
+            election_address,
+            &start_date,
+            &finish_date,
+            BallotMode::Ring,
+            &finish_date,
+            &key_administration,
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_election.object_hash()).await;
+
+    let tx_alice_key = api.register_ring_key(election_address, &key_alice).await;
+    let tx_bob_key = api.register_ring_key(election_address, &key_bob).await;
+    test_kit.create_block();
+
+    api.assert_tx_successful(tx_alice_key.object_hash()).await;
+    api.assert_tx_successful(tx_bob_key.object_hash()).await;
+
+    time_provider.add_time(Duration::minutes(90));
+    test_kit.create_blocks_until(test_kit.height().next().next());
+
+    // Electorate is frozen after election start.
+    let (_, key_carol) = api
+        .create_participant_with_random_key(
+            participant3::NAME,
+            participant3::EMAIL,
+            participant3::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant3::PASS_CODE,
+        )
+        .await;
+    test_kit.create_block();
+    api.submit_pass_codes().await;
+    test_kit.create_block();
+
+    api.set_participant_status(
+        &key_carol.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
+    let tx_late_key = api.register_ring_key(election_address, &key_carol).await;
+    let tx_alice = api.cast_ring_vote(election_address, 1, &key_alice).await;
+    let tx_bob = api.cast_ring_vote(election_address, 3, &key_bob).await;
+    test_kit.create_block();
+
+    api.assert_tx_fail(tx_late_key.object_hash()).await;
+    api.assert_tx_successful(tx_alice.object_hash()).await;
+    api.assert_tx_successful(tx_bob.object_hash()).await;
+
+    let tx_alice_again = api.cast_ring_vote(election_address, 2, &key_alice).await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_alice_again.object_hash()).await;
+
+    let results = api.get_election_result(election_address).await;
+    assert_eq!(results[&1], 1);
+    assert_eq!(results[&2], 0);
+    assert_eq!(results[&3], 1);
+}
+
+#[tokio::test]
+async fn mixnet_election_decrypts_shuffled_ballots() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
+
+    let (_, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant1::PASS_CODE,
+        )
+        .await;
+    let (_, key_bob) = api
+        .create_participant_with_random_key(
+            participant2::NAME,
+            participant2::EMAIL,
+            participant2::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant2::PASS_CODE,
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.submit_pass_codes().await;
+    test_kit.create_block();
+
+    api.set_participant_status(
+        &key_alice.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    api.set_participant_status(
+        &key_bob.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
+
+    let now = time_provider.time();
+    let finish_date = now + Duration::hours(1);
+    let election_address = hash(&KeyPair::random().secret_key()[..]);
+
+    let tx_election = api
+        .issue_mixnet_election(election_address, &now, &finish_date, 4, &key_administration)
+        .await;
+    // Election is issued in the first block, trustee publishes transport key, dealing and
+    // acknowledgement in the next three ones.
+    test_kit.create_blocks_until(Height(test_kit.height().0 + 4));
+    api.assert_tx_successful(tx_election.object_hash()).await;
+
+    // Alice ranks options, Bob writes a free-text ballot.
+    let ranking = mixnet::encode_ranking(&[2, 3, 1], 4).unwrap();
+    let text = mixnet::encode_text("hi!", 4).unwrap();
+    let tx_alice = api
+        .cast_mixnet_vote(election_address, &ranking, &key_alice)
+        .await;
+    let tx_bob = api
+        .cast_mixnet_vote(election_address, &text, &key_bob)
+        .await;
+    test_kit.create_block();
+
+    api.assert_tx_successful(tx_alice.object_hash()).await;
+    api.assert_tx_successful(tx_bob.object_hash()).await;
+
+    // Trustee shuffles ballots after election finish and then decrypts the shuffle output.
+    time_provider.add_time(Duration::hours(2));
+    test_kit.create_blocks_until(Height(test_kit.height().0 + 4));
+
+    let encryption = api.get_election_encryption(election_address).await;
+    assert!(encryption.is_decrypted);
+
+    let mut ballots = api.get_mixnet_ballots(election_address).await.ballots;
+    ballots.sort();
+    let mut expected: Vec<Vec<u32>> = vec![
+        ranking.iter().map(|&value| value as u32).collect(),
+        text.iter().map(|&value| value as u32).collect(),
+    ];
+    expected.sort();
+    assert_eq!(ballots, expected);
+    assert!(ballots
+        .iter()
+        .any(|ballot| mixnet::decode_text(ballot) == "hi!"));
+
+    let results = api.get_election_result(election_address).await;
+    assert_eq!(results[&1], 0);
+    assert_eq!(results[&2], 1);
+    assert_eq!(results[&3], 0);
+}
+
+#[tokio::test]
+async fn vote_receipt_proves_recorded_ballot() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
+
+    let (_, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant1::PASS_CODE,
+        )
+        .await;
+    let (_, key_bob) = api
+        .create_participant_with_random_key(
+            participant2::NAME,
+            participant2::EMAIL,
+            participant2::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant2::PASS_CODE,
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.submit_pass_codes().await;
+    test_kit.create_block();
+
+    api.set_participant_status(
+        &key_alice.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    api.set_participant_status(
+        &key_bob.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
+
+    let now = time_provider.time();
+    let election_address = hash(&KeyPair::random().secret_key()[..]);
+    api.issue_election(
+        election_address,
+        election1::NAME,
+        &now,
+        &(now + Duration::hours(1)),
+        election1::OPTIONS,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
+
+    let tx_alice = api.vote(election_address, 2, &key_alice).await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_alice.object_hash()).await;
+
+    let validator_keys: Vec<PublicKey> = test_kit
+        .network()
+        .validators()
+        .iter()
+        .map(|validator| validator.public_keys().consensus_key)
+        .collect();
+
+    let receipt = api
+        .get_vote_receipt(election_address, &key_alice.public_key())
+        .await;
+    assert_eq!(
+        receipt.verify(
+            BLOCKCHAIN_SERVICE_NAME,
+            &validator_keys,
+            &election_address,
+            &pub_key_address(key_alice.public_key()),
+        ),
+        Ok(Some(2))
+    );
+
+    // Receipt of participant which has not voted proves absence of the vote.
+    let receipt = api
+        .get_vote_receipt(election_address, &key_bob.public_key())
+        .await;
+    assert_eq!(
+        receipt.verify(
+            BLOCKCHAIN_SERVICE_NAME,
+            &validator_keys,
+            &election_address,
+            &pub_key_address(key_bob.public_key()),
+        ),
+        Ok(None)
+    );
+
+    // Receipt does not prove anything about other participants or other validators.
+    assert_eq!(
+        receipt.verify(
+            BLOCKCHAIN_SERVICE_NAME,
+            &validator_keys,
+            &election_address,
+            &pub_key_address(key_alice.public_key()),
+        ),
+        Err(VerificationError::InvalidVoteProof)
+    );
+    assert_eq!(
+        receipt.verify(
+            BLOCKCHAIN_SERVICE_NAME,
+            &[KeyPair::random().public_key()],
+            &election_address,
+            &pub_key_address(key_bob.public_key()),
+        ),
+        Err(VerificationError::InvalidBlockProof)
+    );
+}
+
+#[tokio::test]
+async fn election_results_are_proven_to_block() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
+
+    let (_, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant1::PASS_CODE,
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.submit_pass_codes().await;
+    test_kit.create_block();
+
+    api.set_participant_status(
+        &key_alice.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
+
+    let now = time_provider.time();
+    let election_address = hash(&KeyPair::random().secret_key()[..]);
+    api.issue_election(
+        election_address,
+        election1::NAME,
+        &now,
+        &(now + Duration::hours(1)),
+        election1::OPTIONS,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
+
+    let tx_alice = api.vote(election_address, 3, &key_alice).await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_alice.object_hash()).await;
+
+    let validator_keys: Vec<PublicKey> = test_kit
+        .network()
+        .validators()
+        .iter()
+        .map(|validator| validator.public_keys().consensus_key)
+        .collect();
+
+    let tally = api
+        .get_election_result_proof(election_address)
+        .await
+        .verify(BLOCKCHAIN_SERVICE_NAME, &validator_keys, &election_address)
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        tally.to_map(),
+        api.get_election_result(election_address).await
+    );
+    assert_eq!(tally.to_map()[&3], 1);
+
+    // Unknown election has no tally, which is proven as well.
+    let unknown_address = hash(&KeyPair::random().secret_key()[..]);
+    let proof = api.get_election_result_proof(unknown_address).await;
+    assert_eq!(
+        proof
+            .verify(BLOCKCHAIN_SERVICE_NAME, &validator_keys, &unknown_address)
+            .map(|tally| tally.is_none()),
+        Ok(true)
+    );
+}
+
+#[tokio::test]
+async fn election_results_are_hidden_until_finish() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
+
+    let (_, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant1::PASS_CODE,
+        )
+        .await;
+
+    test_kit.create_block();
+
+    api.submit_pass_codes().await;
+    test_kit.create_block();
+
+    api.set_participant_status(
+        &key_alice.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
+
+    let now = time_provider.time();
+    let hidden_address = hash(&KeyPair::random().secret_key()[..]);
+    let live_address = hash(&KeyPair::random().secret_key()[..]);
+
+    let tx_hidden = api
+        .issue_election_with_visibility(
+            hidden_address,
+            &now,
+            &(now + Duration::hours(1)),
+            ResultsVisibility::AfterFinish,
+            &key_administration,
+        )
+        .await;
+    let tx_live = api
+        .issue_election_with_visibility(
+            live_address,
+            &now,
+            &(now + Duration::hours(1)),
+            ResultsVisibility::Live,
+            &key_administration,
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_hidden.object_hash()).await;
+    api.assert_tx_successful(tx_live.object_hash()).await;
+
+    let tx_hidden_vote = api.vote(hidden_address, 1, &key_alice).await;
+    let tx_live_vote = api.vote(live_address, 2, &key_alice).await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_hidden_vote.object_hash()).await;
+    api.assert_tx_successful(tx_live_vote.object_hash()).await;
+
+    assert!(api.are_election_results_hidden(hidden_address).await);
+    assert!(
+        api.is_vote_receipt_hidden(hidden_address, &key_alice.public_key())
+            .await
+    );
+    assert!(api.is_election_history_hidden(hidden_address).await);
+    assert_eq!(api.get_election_result(live_address).await[&2], 1);
+    assert!(!api.is_election_history_hidden(live_address).await);
+
+    time_provider.add_time(Duration::minutes(90));
+    test_kit.create_blocks_until(test_kit.height().next().next());
+
+    assert!(!api.are_election_results_hidden(hidden_address).await);
+    assert!(
+        !api.is_vote_receipt_hidden(hidden_address, &key_alice.public_key())
+            .await
+    );
+    assert!(!api.is_election_history_hidden(hidden_address).await);
+    assert_eq!(api.get_election_result(hidden_address).await[&1], 1);
+}
+
+#[tokio::test]
+async fn encrypted_election_hides_ballot_count_until_finish() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
+
+    let (_, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant1::PASS_CODE,
+        )
+        .await;
+    test_kit.create_block();
+
+    api.submit_pass_codes().await;
+    test_kit.create_block();
+
+    api.set_participant_status(
+        &key_alice.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
+
+    let now = time_provider.time();
+    let election_address = hash(&KeyPair::random().secret_key()[..]);
+    let tx_election = key_administration.issue_election(
+        BLOCKCHAIN_SERVICE_ID,
+        IssueElection {
+            addr: election_address,
+            name: election1::NAME.to_owned(),
+            start_date: now,
+            finish_date: now + Duration::hours(1),
+            options: election1::OPTIONS.iter().map(ToString::to_string).collect(),
+            eligibility: Vec::new(),
+            ballot_mode: BallotMode::Encrypted,
+            reveal_finish_date: now + Duration::hours(1),
+            token_key: None.into(),
+            ballot_length: 0,
+            results_visibility: ResultsVisibility::AfterFinish,
+        },
+    );
+    api.assert_tx_hash(&tx_election).await;
+    test_kit.create_blocks_until(Height(test_kit.height().0 + 4));
+    api.assert_tx_successful(tx_election.object_hash()).await;
+
+    let tx_vote = api
+        .cast_encrypted_vote(election_address, 1, &key_alice)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_vote.object_hash()).await;
+
+    // Key and encrypted tally stay available to voters, the count does not.
+    let encryption = api.get_election_encryption(election_address).await;
+    assert!(encryption.is_key_ready);
+    assert_eq!(encryption.tally.len(), election1::OPTIONS.len());
+    assert_eq!(encryption.ballots_count, 0);
+
+    time_provider.add_time(Duration::minutes(90));
+    test_kit.create_blocks_until(test_kit.height().next().next());
+
+    let encryption = api.get_election_encryption(election_address).await;
+    assert_eq!(encryption.ballots_count, 1);
+}
+
+#[tokio::test]
+async fn participant_pii_is_kept_off_chain() {
+    let (mut test_kit, api, _) = create_test_kit();
+
+    let (tx, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &None,
+            participant1::PASS_CODE,
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx.object_hash()).await;
+
+    let alice = key_alice.public_key();
+    let openings = pii_openings(
+        &key_alice,
+        participant1::EMAIL,
+        participant1::PHONE_NUMBER,
+        participant1::PASS_CODE,
+    );
+
+    // Participant proves field value by disclosing the salt, wrong value is rejected.
+    assert!(api.check_pii(&alice, &openings[2]).await);
+    let forged = PiiOpening::new(
+        PiiField::PassCode,
+        participant2::PASS_CODE,
+        openings[2].salt,
+    );
+    assert!(!api.check_pii(&alice, &forged).await);
+
+    // Node keeps only data matching on-chain commitments.
+    assert!(!api.submit_pii(&alice, vec![forged]).await);
+    assert!(api.get_participant_pii(&alice).await.is_empty());
+
+    assert!(api.submit_pii(&alice, openings.clone()).await);
+    assert_eq!(api.get_participant_pii(&alice).await, openings);
+}
+
+#[tokio::test]
+async fn participant_confirms_contact_with_sent_code() {
+    let codes_path = std::env::temp_dir().join(format!("contact-codes-{}", rand::random::<u64>()));
+    let service = ElectionService::new(
+        PiiStore::temporary(),
+        ContactVerifier::file_sink(&codes_path),
+    );
+    let (mut test_kit, api, time_provider) = create_test_kit_with_service(service);
+
+    let (tx, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &None,
+            participant1::PASS_CODE,
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx.object_hash()).await;
+
+    // Code can be sent only to contact stored on the node.
+    let alice = key_alice.public_key();
+    let now = time_provider.time();
+    assert!(
+        !api.request_contact_verification(PiiField::Email, now, &key_alice)
+            .await
+    );
+    let openings = pii_openings(
+        &key_alice,
+        participant1::EMAIL,
+        participant1::PHONE_NUMBER,
+        participant1::PASS_CODE,
+    );
+    assert!(api.submit_pii(&alice, openings).await);
+    assert!(
+        !api.request_contact_verification(PiiField::PassCode, now, &key_alice)
+            .await
+    );
+
+    // Request is signed by participant recently.
+    let key_mallory = KeyPair::random();
+    let message = contact::request_message(&pub_key_address(alice), PiiField::Email, now);
+    assert!(api
+        .inner
+        .public(ApiKind::Service(BLOCKCHAIN_SERVICE_NAME))
+        .query(&ContactVerificationQuery {
+            participant: alice,
+            field: PiiField::Email,
+            requested_at: now,
+            signature: crypto::sign(message.as_ref(), key_mallory.secret_key()),
+        })
+        .post::<()>("v1/participants/contacts/verify")
+        .await
+        .is_err());
+    assert!(
+        !api.request_contact_verification(PiiField::Email, now - Duration::hours(1), &key_alice)
+            .await
+    );
+
+    assert!(
+        api.request_contact_verification(PiiField::Email, now, &key_alice)
+            .await
+    );
+    // Codes are sent to participant at most once a minute.
+    assert!(
+        !api.request_contact_verification(PiiField::PhoneNumber, now, &key_alice)
+            .await
+    );
+
+    // Validator publishes the challenge right away.
+    test_kit.create_block();
+
+    let message = std::fs::read_to_string(&codes_path).unwrap();
+    std::fs::remove_file(&codes_path).unwrap();
+    let mut parts = message.trim_end().splitn(2, '\t');
+    assert_eq!(parts.next(), Some(participant1::EMAIL));
+    let code = parts.next().unwrap().rsplit(' ').next().unwrap();
+
+    let tx_wrong = api
+        .confirm_contact(PiiField::PhoneNumber, code, &key_alice)
+        .await;
+    let tx_forged = api
+        .confirm_contact(PiiField::Email, "AAAAAAAAAAAA", &key_alice)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_wrong.object_hash()).await;
+    api.assert_tx_fail(tx_forged.object_hash()).await;
+
+    let tx = api
+        .confirm_contact(PiiField::Email, &code.to_lowercase(), &key_alice)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx.object_hash()).await;
 
-        Spec::new(service).with_instance(TIME_SERVICE_ID, TIME_SERVICE_NAME, ())
-    };
+    let participant = api.get_participant(&alice).await.unwrap();
+    assert!(participant.is_email_verified);
+    assert!(!participant.is_phone_number_verified);
+}
 
-    let election_service = {
-        let config = Config {
-            time_service_name: TIME_SERVICE_NAME.to_owned(),
-        };
+#[tokio::test]
+async fn participant_key_rotation_keeps_identity_and_votes() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
 
-        Spec::new(ElectionService).with_instance(
-            BLOCKCHAIN_SERVICE_ID,
-            BLOCKCHAIN_SERVICE_NAME,
-            config,
+    let (_, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant1::PASS_CODE,
         )
-    };
+        .await;
+    let (_, key_bob) = api
+        .create_participant_with_random_key(
+            participant2::NAME,
+            participant2::EMAIL,
+            participant2::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant2::PASS_CODE,
+        )
+        .await;
+    test_kit.create_block();
 
-    let mut test_kit = TestKitBuilder::validator()
-        .with(Spec::new(ExplorerFactory).with_default_instance())
-        .with(time_service)
-        .with(election_service)
-        .build();
+    api.submit_pass_codes().await;
+    test_kit.create_block();
 
-    let api = ElectionApi {
-        inner: test_kit.api(),
-    };
+    api.set_participant_status(
+        &key_alice.public_key(),
+        ParticipantStatus::Verified,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
 
-    test_kit.create_blocks_until(Height(2)); // Ensure that time is set
+    let now = time_provider.time();
+    let election_address = hash(&KeyPair::random().secret_key()[..]);
+    api.issue_election(
+        election_address,
+        election1::NAME,
+        &now,
+        &(now + Duration::hours(1)),
+        election1::OPTIONS,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
 
-    (test_kit, api, mock_provider)
-}
+    let tx = api.vote(election_address, 2, &key_alice).await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx.object_hash()).await;
+    let alice_before = api.get_participant(&key_alice.public_key()).await.unwrap();
+
+    // Both keys must sign the rotation, and the new key must be unused.
+    let key_new = KeyPair::random();
+    let tx_unsigned = key_alice.rotate_participant_key(
+        BLOCKCHAIN_SERVICE_ID,
+        RotateParticipantKey {
+            new_key: key_new.public_key(),
+            new_key_signature: key_rotation::sign_rotation(
+                &pub_key_address(key_alice.public_key()),
+                &KeyPair::random(),
+            ),
+        },
+    );
+    api.assert_tx_hash(&tx_unsigned).await;
+    let tx_taken = api.rotate_participant_key(&key_alice, &key_bob).await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_unsigned.object_hash()).await;
+    api.assert_tx_fail(tx_taken.object_hash()).await;
 
-fn empty_polygon() -> Polygon {
-    Polygon {
-        interiors: Vec::with_capacity(0),
-        exterior: Vec::<[f64; 2]>::with_capacity(0).into(),
-    }
+    let tx = api.rotate_participant_key(&key_alice, &key_new).await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx.object_hash()).await;
+
+    assert!(api.get_participant(&key_alice.public_key()).await.is_none());
+    let alice = api.get_participant(&key_new.public_key()).await.unwrap();
+    assert_eq!(alice.addr, pub_key_address(key_new.public_key()));
+    assert_eq!(alice.status, ParticipantStatus::Verified);
+    assert_eq!(alice.residence.0, alice_before.residence.0);
+    assert_eq!(alice.history_len, alice_before.history_len + 1);
+    assert_eq!(
+        api.get_current_address(&key_alice.public_key()).await,
+        Some(pub_key_address(key_new.public_key()))
+    );
+
+    // Ballot has moved to the new address, so it can not be cast again.
+    let validator_keys: Vec<PublicKey> = test_kit
+        .network()
+        .validators()
+        .iter()
+        .map(|validator| validator.public_keys().consensus_key)
+        .collect();
+    let receipt = api
+        .get_vote_receipt(election_address, &key_new.public_key())
+        .await;
+    assert_eq!(
+        receipt.verify(
+            BLOCKCHAIN_SERVICE_NAME,
+            &validator_keys,
+            &election_address,
+            &pub_key_address(key_new.public_key()),
+        ),
+        Ok(Some(2))
+    );
+
+    let tx_again = api.vote(election_address, 1, &key_new).await;
+    let tx_old = api.vote(election_address, 1, &key_alice).await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_again.object_hash()).await;
+    api.assert_tx_fail(tx_old.object_hash()).await;
+    assert_eq!(api.get_election_result(election_address).await[&2], 1);
 }
 
 #[tokio::test]
-async fn create_participant() {
-    let (mut test_kit, api, _) = create_test_kit();
+async fn administration_succession_keeps_references() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
 
-    let (tx, _) = api
+    let (_, key_root) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+    test_kit.create_block();
+    let (_, key_city) = api
+        .create_administration_with_random_key(
+            administration2::NAME,
+            Some(&key_root),
+            &default_area(),
+        )
+        .await;
+    test_kit.create_block();
+    let (_, key_district) = api
+        .create_administration_with_random_key(
+            administration2::NAME,
+            Some(&key_city),
+            &default_area(),
+        )
+        .await;
+    let (_, key_alice) = api
         .create_participant_with_random_key(
             participant1::NAME,
             participant1::EMAIL,
             participant1::PHONE_NUMBER,
-            &None,
+            &Some(key_city.public_key()),
             participant1::PASS_CODE,
         )
         .await;
+    test_kit.create_block();
+
+    api.submit_pass_codes().await;
+    test_kit.create_block();
+
+    // Principals of the residence administration do not verify its residents.
+    let tx_root_verify = api
+        .set_participant_status(
+            &key_alice.public_key(),
+            ParticipantStatus::Verified,
+            &key_root,
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_root_verify.object_hash()).await;
+
+    api.set_participant_status(
+        &key_alice.public_key(),
+        ParticipantStatus::Verified,
+        &key_city,
+    )
+    .await;
+    let now = time_provider.time();
+    let city_election = hash(&KeyPair::random().secret_key()[..]);
+    api.issue_election(
+        city_election,
+        election1::NAME,
+        &now,
+        &(now + Duration::hours(1)),
+        election1::OPTIONS,
+        &key_city,
+    )
+    .await;
+    let root_election = hash(&KeyPair::random().secret_key()[..]);
+    api.issue_restricted_election(
+        root_election,
+        election1::NAME,
+        &now,
+        &(now + Duration::hours(1)),
+        election1::OPTIONS,
+        vec![EligibilityTerm::resides_within(&pub_key_address(
+            key_city.public_key(),
+        ))],
+        &key_root,
+    )
+    .await;
+    test_kit.create_block();
 
+    // New key must agree to take over and must not belong to an administration.
+    let key_successor = KeyPair::random();
+    let tx_taken = api.succeed_administration(&key_city, &key_root).await;
     test_kit.create_block();
+    api.assert_tx_fail(tx_taken.object_hash()).await;
 
+    let tx = api.succeed_administration(&key_city, &key_successor).await;
+    test_kit.create_block();
     api.assert_tx_successful(tx.object_hash()).await;
 
-    let participant = api.get_participant(&tx.author()).await.unwrap();
+    let successor = pub_key_address(key_successor.public_key());
+    assert!(api
+        .get_administration(&key_city.public_key())
+        .await
+        .is_none());
+    assert_eq!(
+        api.get_current_administration_address(&key_city.public_key())
+            .await,
+        Some(successor)
+    );
+    let administration = api
+        .get_administration(&key_successor.public_key())
+        .await
+        .unwrap();
+    assert_eq!(
+        administration.principal_key.0,
+        Some(pub_key_address(key_root.public_key()))
+    );
+    let district = api
+        .get_administration(&key_district.public_key())
+        .await
+        .unwrap();
+    assert_eq!(district.principal_key.0, Some(successor));
+
+    // Former key can not register a new administration.
+    let tx_former = api
+        .create_administration(
+            administration2::NAME,
+            None,
+            &square_area(20.0, 20.0, 1.0),
+            &key_city,
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_former.object_hash()).await;
+    let (tx_fresh, _) = api
+        .create_administration_with_random_key(
+            administration2::NAME,
+            None,
+            &square_area(20.0, 20.0, 1.0),
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_fresh.object_hash()).await;
+
+    // Elections of the former key belong to the successor.
+    let elections = api.get_active_elections(&successor).await;
+    assert!(elections
+        .iter()
+        .any(|election| election.addr == city_election));
+    let election = api.get_election(&city_election).await.unwrap();
+    assert_eq!(election.issuer, successor);
+
+    // Residence and eligibility referring to the former key are resolved to the successor.
+    let tx_old = api
+        .set_participant_attribute(&key_alice.public_key(), "age", "30", &key_city)
+        .await;
+    let tx_successor = api
+        .set_participant_attribute(&key_alice.public_key(), "age", "30", &key_successor)
+        .await;
+    let tx_vote = api.vote(root_election, 1, &key_alice).await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_old.object_hash()).await;
+    api.assert_tx_successful(tx_successor.object_hash()).await;
+    api.assert_tx_successful(tx_vote.object_hash()).await;
 
-    assert_eq!(participant.addr, author_address(&tx));
-    assert_eq!(participant.name, participant1::NAME);
-    assert_eq!(participant.email, participant1::EMAIL);
-    assert_eq!(participant.phone_number, participant1::PHONE_NUMBER);
-    assert_eq!(participant.pass_code, participant1::PASS_CODE);
+    // Location lookup descends through the successor.
+    let tx_location = api.submit_location([1.0, 1.0], &key_alice).await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_location.object_hash()).await;
+    let locations = api.get_locations(&key_alice.public_key()).await;
+    assert_eq!(
+        locations[0].administrations,
+        vec![
+            pub_key_address(key_district.public_key()),
+            successor,
+            pub_key_address(key_root.public_key())
+        ]
+    );
 }
 
 #[tokio::test]
-async fn create_administration() {
-    let (mut test_kit, api, _) = create_test_kit();
+async fn multisig_administration_requires_approved_proposals() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
 
-    let (tx, _) = api
-        .create_administration_with_random_key(administration1::NAME, &None, &empty_polygon())
+    let (_, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
         .await;
+    test_kit.create_block();
 
+    let signer_keys: Vec<_> = (0..3).map(|_| KeyPair::random()).collect();
+    let signers: Vec<_> = signer_keys.iter().map(KeyPair::public_key).collect();
+    let tx_invalid = api.set_signers(&signers, 4, &key_administration).await;
     test_kit.create_block();
+    api.assert_tx_fail(tx_invalid.object_hash()).await;
 
+    let tx = api.set_signers(&signers, 2, &key_administration).await;
+    test_kit.create_block();
     api.assert_tx_successful(tx.object_hash()).await;
 
-    let administration = api.get_administration(&tx.author()).await.unwrap();
+    // Administration key alone can no longer issue elections.
+    let now = time_provider.time();
+    let election_address = hash(&KeyPair::random().secret_key()[..]);
+    let tx_direct = api
+        .issue_election(
+            election_address,
+            election1::NAME,
+            &now,
+            &(now + Duration::hours(1)),
+            election1::OPTIONS,
+            &key_administration,
+        )
+        .await;
+    let tx_outsider = api
+        .propose_action(
+            &key_administration.public_key(),
+            ProposalAction::CancelElection(election_address),
+            &KeyPair::random(),
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_direct.object_hash()).await;
+    api.assert_tx_fail(tx_outsider.object_hash()).await;
+    assert!(api.get_election(&election_address).await.is_none());
+
+    let issue = IssueElection {
+        addr: election_address,
+        name: election1::NAME.to_owned(),
+        start_date: now,
+        finish_date: now + Duration::hours(1),
+        options: election1::OPTIONS.iter().map(ToString::to_string).collect(),
+        eligibility: Vec::new(),
+        ballot_mode: BallotMode::Open,
+        reveal_finish_date: now + Duration::hours(1),
+        token_key: None.into(),
+        ballot_length: 0,
+        results_visibility: ResultsVisibility::Live,
+    };
+    let tx_proposal = api
+        .propose_action(
+            &key_administration.public_key(),
+            ProposalAction::IssueElection(issue),
+            &signer_keys[0],
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_proposal.object_hash()).await;
+    assert!(api.get_election(&election_address).await.is_none());
 
-    assert_eq!(administration.name, administration1::NAME);
-}
+    let proposals = api.get_proposals(&key_administration.public_key()).await;
+    assert_eq!(proposals.signers.unwrap().threshold, 2);
+    assert_eq!(proposals.pending.len(), 1);
+    assert_eq!(proposals.pending[0].proposal_id, tx_proposal.object_hash());
 
-#[tokio::test]
-async fn reject_create_different_participants_with_same_keys() {
-    let (mut test_kit, api, _) = create_test_kit();
+    // Proposer can not approve twice.
+    let tx_twice = api
+        .approve_proposal(tx_proposal.object_hash(), &signer_keys[0])
+        .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_twice.object_hash()).await;
 
-    let (tx, key) = api
-        .create_participant_with_random_key(
-            "John Doe",
-            "john_doe@example.com",
-            "1212",
-            &None,
-            "111",
+    let tx_approval = api
+        .approve_proposal(tx_proposal.object_hash(), &signer_keys[1])
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_approval.object_hash()).await;
+    let election = api.get_election(&election_address).await.unwrap();
+    assert_eq!(
+        election.issuer,
+        pub_key_address(key_administration.public_key())
+    );
+    assert!(api
+        .get_proposals(&key_administration.public_key())
+        .await
+        .pending
+        .is_empty());
+
+    // Executed proposal can not be approved anymore.
+    let tx_late = api
+        .approve_proposal(tx_proposal.object_hash(), &signer_keys[2])
+        .await;
+    let tx_cancel_direct = api
+        .cancel_election(election_address, &key_administration)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_late.object_hash()).await;
+    api.assert_tx_fail(tx_cancel_direct.object_hash()).await;
+
+    let tx_cancel = api
+        .propose_action(
+            &key_administration.public_key(),
+            ProposalAction::CancelElection(election_address),
+            &signer_keys[2],
         )
         .await;
-
     test_kit.create_block();
+    let tx_approval = api
+        .approve_proposal(tx_cancel.object_hash(), &signer_keys[0])
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_approval.object_hash()).await;
+    assert!(
+        api.get_election(&election_address)
+            .await
+            .unwrap()
+            .is_cancelled
+    );
 
-    api.assert_tx_successful(tx.object_hash()).await;
+    // Invitations are issued by proposals as well.
+    let codes = ["letter-1"];
+    let tx_invitations_direct = api
+        .issue_invitations(&codes, now + Duration::hours(1), &key_administration)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_invitations_direct.object_hash())
+        .await;
 
-    // using same key
-    let tx = api
-        .create_participant(
-            "John Smith",
-            "john_smith@example.com",
-            "2121",
-            &None,
-            "222",
-            &key,
+    let issuer = pub_key_address(key_administration.public_key());
+    let tx_invitations = api
+        .propose_action(
+            &key_administration.public_key(),
+            ProposalAction::IssueInvitations(IssueInvitations {
+                keys: vec![invitation::invitation_keys(&issuer, codes[0]).public_key()],
+                expires_at: now + Duration::hours(1),
+            }),
+            &signer_keys[1],
         )
         .await;
+    test_kit.create_block();
+    let tx_approval = api
+        .approve_proposal(tx_invitations.object_hash(), &signer_keys[2])
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_approval.object_hash()).await;
 
+    let tx_invited = api
+        .create_invited_participant(
+            participant1::NAME,
+            participant1::PASS_CODE,
+            &key_administration.public_key(),
+            codes[0],
+            &KeyPair::random(),
+        )
+        .await;
     test_kit.create_block();
+    api.assert_tx_successful(tx_invited.object_hash()).await;
 
-    api.assert_tx_fail(tx.object_hash()).await;
+    // Attributes deciding eligibility and succession need proposals too.
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant2::NAME,
+            participant2::EMAIL,
+            participant2::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant2::PASS_CODE,
+        )
+        .await;
+    let key_successor = KeyPair::random();
+    test_kit.create_block();
+    let tx_attribute_direct = api
+        .set_participant_attribute(&key_alice.public_key(), "age", "30", &key_administration)
+        .await;
+    let tx_succession_direct = api
+        .succeed_administration(&key_administration, &key_successor)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_attribute_direct.object_hash()).await;
+    api.assert_tx_fail(tx_succession_direct.object_hash()).await;
+
+    let tx_attribute = api
+        .propose_action(
+            &key_administration.public_key(),
+            ProposalAction::SetParticipantAttributes(SetParticipantAttributes {
+                participant: pub_key_address(key_alice.public_key()),
+                attributes: vec![AttributeValue {
+                    key: "age".to_owned(),
+                    value: "30".to_owned(),
+                }],
+                seed: rand::random(),
+            }),
+            &signer_keys[0],
+        )
+        .await;
+    test_kit.create_block();
+    let tx_approval = api
+        .approve_proposal(tx_attribute.object_hash(), &signer_keys[1])
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_approval.object_hash()).await;
+    let alice = api.get_participant(&key_alice.public_key()).await.unwrap();
+    assert_eq!(alice.attributes.len(), 1);
+    assert_eq!(alice.attributes[0].issuer, issuer);
+
+    let tx_succession = api
+        .propose_action(
+            &key_administration.public_key(),
+            ProposalAction::SucceedAdministration(SucceedAdministration {
+                new_key: key_successor.public_key(),
+                new_key_signature: key_rotation::sign_rotation(&issuer, &key_successor),
+            }),
+            &signer_keys[2],
+        )
+        .await;
+    test_kit.create_block();
+    let tx_approval = api
+        .approve_proposal(tx_succession.object_hash(), &signer_keys[0])
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_approval.object_hash()).await;
+    assert_eq!(
+        api.get_current_administration_address(&key_administration.public_key())
+            .await,
+        Some(pub_key_address(key_successor.public_key()))
+    );
+    let proposals = api.get_proposals(&key_successor.public_key()).await;
+    assert_eq!(proposals.signers.unwrap().threshold, 2);
 }
 
 #[tokio::test]
-async fn reject_create_different_administrations_with_same_keys() {
-    let (mut test_kit, api, _) = create_test_kit();
+async fn guardians_recover_lost_participant_key() {
+    let (mut test_kit, api, time_provider) = create_test_kit();
 
-    let (tx, key) = api
-        .create_administration_with_random_key(administration1::NAME, &None, &empty_polygon())
+    let (_, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant1::PASS_CODE,
+        )
+        .await;
+    let (_, key_bob) = api
+        .create_participant_with_random_key(
+            participant2::NAME,
+            participant2::EMAIL,
+            participant2::PHONE_NUMBER,
+            &None,
+            participant2::PASS_CODE,
+        )
         .await;
+    test_kit.create_block();
 
+    let alice = key_alice.public_key();
+    let guardians = [key_bob.public_key(), key_administration.public_key()];
+    let tx_unknown = api
+        .set_guardians(&[KeyPair::random().public_key()], 1, &key_alice)
+        .await;
+    let tx_threshold = api.set_guardians(&guardians, 3, &key_alice).await;
     test_kit.create_block();
+    api.assert_tx_fail(tx_unknown.object_hash()).await;
+    api.assert_tx_fail(tx_threshold.object_hash()).await;
 
+    let tx = api.set_guardians(&guardians, 2, &key_alice).await;
+    test_kit.create_block();
     api.assert_tx_successful(tx.object_hash()).await;
 
-    // using same key
+    // Approved recovery is locked for the challenge period, and the old key cancels it.
+    let key_new = KeyPair::random();
+    let tx_stranger = api
+        .approve_recovery(&alice, &key_new, &KeyPair::random())
+        .await;
+    let tx_bob = api.approve_recovery(&alice, &key_new, &key_bob).await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_stranger.object_hash()).await;
+    api.assert_tx_successful(tx_bob.object_hash()).await;
+
+    // Guardian proposing another key does not block the recovery.
+    let tx_other_key = api
+        .approve_recovery(&alice, &KeyPair::random(), &key_administration)
+        .await;
+    let tx_again = api.approve_recovery(&alice, &key_new, &key_bob).await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_other_key.object_hash()).await;
+    api.assert_tx_fail(tx_again.object_hash()).await;
+    let recovery = api.get_recovery(&alice).await.recovery.unwrap();
+    assert!(!recovery.is_approved());
+    assert_eq!(recovery.candidates.len(), 2);
+
     let tx = api
-        .create_administration(
-            administration2::NAME,
-            &Some(key.public_key()),
-            &empty_polygon(),
-            &key,
-        )
+        .approve_recovery(&alice, &key_new, &key_administration)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx.object_hash()).await;
+    let recovery = api.get_recovery(&alice).await.recovery.unwrap();
+    assert_eq!(recovery.approved_key(), Some(key_new.public_key()));
+    assert_eq!(recovery.candidates.len(), 1);
+
+    let tx_early = api.complete_recovery(&alice, &key_new).await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_early.object_hash()).await;
+
+    let tx = api.cancel_recovery(&key_alice).await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx.object_hash()).await;
+    assert!(api.get_recovery(&alice).await.recovery.is_none());
+
+    // Recovery completes by the new key once the challenge period is over.
+    let tx_bob = api.approve_recovery(&alice, &key_new, &key_bob).await;
+    test_kit.create_block();
+    let tx_administration = api
+        .approve_recovery(&alice, &key_new, &key_administration)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_bob.object_hash()).await;
+    api.assert_tx_successful(tx_administration.object_hash())
         .await;
 
+    time_provider.add_time(recovery::challenge_period() + Duration::minutes(1));
+    test_kit.create_blocks_until(test_kit.height().next().next());
+
+    let tx_stolen = api.complete_recovery(&alice, &KeyPair::random()).await;
+    let tx = api.complete_recovery(&alice, &key_new).await;
     test_kit.create_block();
+    api.assert_tx_fail(tx_stolen.object_hash()).await;
+    api.assert_tx_successful(tx.object_hash()).await;
 
-    api.assert_tx_fail(tx.object_hash()).await;
+    assert!(api.get_participant(&alice).await.is_none());
+    let recovered = api.get_participant(&key_new.public_key()).await.unwrap();
+    assert_eq!(recovered.name, participant1::NAME);
+    let info = api.get_recovery(&key_new.public_key()).await;
+    assert_eq!(info.guardians.unwrap().threshold, 2);
+    assert!(info.recovery.is_none());
 }
 
 #[tokio::test]
-#[ignore = "not implemented yet"]
-async fn select_administration_principals() {
+async fn participant_pass_code_is_unique() {
     let (mut test_kit, api, _) = create_test_kit();
 
-    let (tx_a1, _) = api
-        .create_administration_with_random_key(administration1::NAME, &None, &empty_polygon())
+    let (tx_alice, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &None,
+            participant1::PASS_CODE,
+        )
         .await;
-    let (tx_a2, _) = api
-        .create_administration_with_random_key(
-            administration2::NAME,
-            &Some(tx_a1.author()),
-            &empty_polygon(),
+    // Pass code is normalised before fingerprinting.
+    let (tx_twin, key_twin) = api
+        .create_participant_with_random_key(
+            participant2::NAME,
+            participant2::EMAIL,
+            participant2::PHONE_NUMBER,
+            &None,
+            "aa 000-000",
         )
         .await;
-
     test_kit.create_block();
-
-    api.assert_tx_successful(tx_a1.object_hash()).await;
-    api.assert_tx_successful(tx_a2.object_hash()).await;
-
-    // FixMe: adapt this test for new version
-
-    //let snapshot = test_kit.snapshot();
-    //
-    //let schema = ElectionSchema::new(&snapshot);
-    //
-    //let a1_principals = schema
-    //    .iter_principals(&tx_a1.author())
-    //    .unwrap()
-    //    .collect::<Box<[_]>>();
-    //let a2_principals = schema
-    //    .iter_principals(&tx_a2.author())
-    //    .unwrap()
-    //    .collect::<Box<[_]>>();
-    //
-    //assert_eq!(a1_principals.len(), 0);
-    //assert_eq!(a2_principals.len(), 1);
-    //assert_eq!(a2_principals[0].pub_key, tx_a1.author());
-}
-
-#[tokio::test]
-#[ignore = "not implemented yet"]
-async fn select_principals_elections() {
-    //ToDo: Add participants selection
+    api.assert_tx_successful(tx_alice.object_hash()).await;
+    api.assert_tx_successful(tx_twin.object_hash()).await;
+
+    let report = api.get_dedup_report().await;
+    assert!(report.duplicates.is_empty());
+    assert_eq!(report.unverified.len(), 2);
+
+    let alice_openings = pii_openings(
+        &key_alice,
+        participant1::EMAIL,
+        participant1::PHONE_NUMBER,
+        participant1::PASS_CODE,
+    );
+    assert!(
+        api.submit_pii(&key_alice.public_key(), alice_openings)
+            .await
+    );
+    test_kit.create_block();
+    let alice = api.get_participant(&key_alice.public_key()).await.unwrap();
+    assert!(alice.is_pass_code_attested());
+
+    // Fingerprint of the same pass code is owned by the first participant.
+    let twin_openings = pii_openings(
+        &key_twin,
+        participant2::EMAIL,
+        participant2::PHONE_NUMBER,
+        "aa 000-000",
+    );
+    assert!(api.submit_pii(&key_twin.public_key(), twin_openings).await);
+    test_kit.create_block();
+    let twin = api.get_participant(&key_twin.public_key()).await.unwrap();
+    assert!(!twin.is_pass_code_attested());
+
+    let report = api.get_dedup_report().await;
+    assert_eq!(report.duplicates.len(), 1);
+    assert_eq!(report.duplicates[0].len(), 2);
+    assert!(report.duplicates[0].contains(&pub_key_address(key_twin.public_key())));
+    assert!(report.mismatched.is_empty());
+    assert!(report.unverified.is_empty());
 }
 
 #[tokio::test]
-async fn issue_election() {
+async fn only_verified_participants_vote() {
     let (mut test_kit, api, time_provider) = create_test_kit();
 
-    let (tx_administration, key_administration) = api
-        .create_administration_with_random_key(administration1::NAME, &None, &empty_polygon())
+    let (_, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
         .await;
-
-    test_kit.create_block();
-
-    let elections_before = api
-        .get_active_elections(&author_address(&tx_administration))
+    let (_, key_other_administration) = api
+        .create_administration_with_random_key(
+            administration2::NAME,
+            None,
+            &square_area(10.0, 0.0, 10.0),
+        )
         .await;
 
-    assert!(elections_before.is_empty());
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &Some(key_administration.public_key()),
+            participant1::PASS_CODE,
+        )
+        .await;
+    test_kit.create_block();
 
-    let start_date = time_provider.time();
-    let finish_date = start_date + Duration::hours(1);
+    let alice = key_alice.public_key();
+    let participant = api.get_participant(&alice).await.unwrap();
+    assert_eq!(participant.status, ParticipantStatus::Pending);
 
+    let now = time_provider.time();
     let election_address = hash(&KeyPair::random().secret_key()[..]);
+    api.issue_election(
+        election_address,
+        election1::NAME,
+        &now,
+        &(now + Duration::hours(1)),
+        election1::OPTIONS,
+        &key_administration,
+    )
+    .await;
+    test_kit.create_block();
 
-    let create_election_tx = api
-        .issue_election(
-            election_address,
-            election1::NAME,
-            &start_date,
-            &finish_date,
-            election1::OPTIONS,
-            &key_administration,
+    let tx_pending_vote = api.vote(election_address, 1, &key_alice).await;
+    let tx_foreign_verify = api
+        .set_participant_status(
+            &alice,
+            ParticipantStatus::Verified,
+            &key_other_administration,
         )
         .await;
-
+    let tx_early_suspend = api
+        .set_participant_status(&alice, ParticipantStatus::Suspended, &key_administration)
+        .await;
+    let tx_unattested_verify = api
+        .set_participant_status(&alice, ParticipantStatus::Verified, &key_administration)
+        .await;
     test_kit.create_block();
+    api.assert_tx_fail(tx_pending_vote.object_hash()).await;
+    api.assert_tx_fail(tx_foreign_verify.object_hash()).await;
+    api.assert_tx_fail(tx_early_suspend.object_hash()).await;
+    api.assert_tx_fail(tx_unattested_verify.object_hash()).await;
 
-    api.assert_tx_successful(create_election_tx.object_hash())
+    // Participant is verified once validator attests its pass code.
+    api.submit_pass_codes().await;
+    test_kit.create_block();
+    assert!(api
+        .get_participant(&alice)
+        .await
+        .unwrap()
+        .is_pass_code_attested());
+
+    let tx_verify = api
+        .set_participant_status(&alice, ParticipantStatus::Verified, &key_administration)
         .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_verify.object_hash()).await;
 
-    let elections_after = api
-        .get_active_elections(&author_address(&tx_administration))
+    let tx_suspend = api
+        .set_participant_status(&alice, ParticipantStatus::Suspended, &key_administration)
         .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_suspend.object_hash()).await;
 
-    assert_eq!(elections_after.len(), 1);
+    let tx_suspended_vote = api.vote(election_address, 1, &key_alice).await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_suspended_vote.object_hash()).await;
 
-    let election = api.get_election(&election_address).await.unwrap();
+    let tx_reinstate = api
+        .set_participant_status(&alice, ParticipantStatus::Verified, &key_administration)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_reinstate.object_hash()).await;
 
-    assert_eq!(election1::NAME, election.name);
-    assert_eq!(start_date, election.start_date);
-    assert_eq!(finish_date, election.finish_date);
-    assert_eq!(
-        pub_key_address(key_administration.public_key()),
-        election.issuer
-    )
+    let tx_vote = api.vote(election_address, 1, &key_alice).await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_vote.object_hash()).await;
 }
 
 #[tokio::test]
-async fn reject_issuing_election_by_unknown_administration() {
+async fn invited_participant_is_verified_in_issuing_administration() {
     let (mut test_kit, api, time_provider) = create_test_kit();
 
-    let start_date = time_provider.time();
-    let finish_date = start_date + Duration::hours(1);
-
-    // Case 1: Issuing election with random key
-    let create_election_tx = api
-        .issue_election(
-            hash(&KeyPair::random().secret_key()[..]),
-            election1::NAME,
-            &start_date,
-            &finish_date,
-            election1::OPTIONS,
-            &KeyPair::random(),
-        )
+    let (_, key_administration) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
         .await;
+    test_kit.create_block();
 
+    let now = time_provider.time();
+    let codes = ["letter-1", "letter-2", "letter-3"];
+    let tx_issue = api
+        .issue_invitations(&codes, now + Duration::hours(1), &key_administration)
+        .await;
     test_kit.create_block();
+    api.assert_tx_successful(tx_issue.object_hash()).await;
 
-    api.assert_tx_fail(create_election_tx.object_hash()).await;
+    let tx_issue_again = api
+        .issue_invitations(&codes[..1], now + Duration::hours(1), &key_administration)
+        .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_issue_again.object_hash()).await;
 
-    // Case 2: Issuing election with participant key
-    let (tx, participant_key) = api
-        .create_participant_with_random_key(
+    let key_alice = KeyPair::random();
+    let key_bob = KeyPair::random();
+    let key_carol = KeyPair::random();
+    let tx_alice = api
+        .create_invited_participant(
             participant1::NAME,
-            participant1::EMAIL,
-            participant1::PHONE_NUMBER,
-            &None,
             participant1::PASS_CODE,
+            &key_administration.public_key(),
+            codes[0],
+            &key_alice,
         )
         .await;
-
-    test_kit.create_block();
-
-    api.assert_tx_successful(tx.object_hash()).await;
-
-    let create_election_tx = api
-        .issue_election(
-            hash(&KeyPair::random().secret_key()[..]),
-            election1::NAME,
-            &start_date,
-            &finish_date,
-            election1::OPTIONS,
-            &participant_key,
+    let tx_unknown = api
+        .create_invited_participant(
+            participant2::NAME,
+            participant2::PASS_CODE,
+            &key_administration.public_key(),
+            "forged letter",
+            &key_bob,
         )
         .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_alice.object_hash()).await;
+    api.assert_tx_fail(tx_unknown.object_hash()).await;
 
+    // Invitation verifies participant once its pass code is attested as unique.
+    let alice = api.get_participant(&key_alice.public_key()).await.unwrap();
+    assert_eq!(alice.status, ParticipantStatus::Pending);
+    api.submit_pass_codes().await;
     test_kit.create_block();
 
-    api.assert_tx_fail(create_election_tx.object_hash()).await;
-}
+    let alice = api.get_participant(&key_alice.public_key()).await.unwrap();
+    assert_eq!(alice.status, ParticipantStatus::Verified);
+    assert_eq!(
+        alice.residence.0,
+        Some(pub_key_address(key_administration.public_key()))
+    );
 
-#[tokio::test]
-async fn election_results_counting() {
-    let (mut test_kit, api, time_provider) = create_test_kit();
-    let (_, key_alice) = api
-        .create_participant_with_random_key(
-            participant1::NAME,
-            participant1::EMAIL,
-            participant1::PHONE_NUMBER,
-            &None,
-            participant1::PASS_CODE,
+    let tx_reused = api
+        .create_invited_participant(
+            participant2::NAME,
+            participant2::PASS_CODE,
+            &key_administration.public_key(),
+            codes[0],
+            &key_bob,
         )
         .await;
-
-    let (_, key_bob) = api
-        .create_participant_with_random_key(
+    // Redemption copied from a pending registration does not register another key.
+    let redemption = invitation::redeem(
+        &pub_key_address(key_administration.public_key()),
+        codes[2],
+        &pub_key_address(key_alice.public_key()),
+    );
+    let tx_replayed = api
+        .create_participant_with_invitation(
             participant2::NAME,
             participant2::EMAIL,
             participant2::PHONE_NUMBER,
             &None,
             participant2::PASS_CODE,
+            Some(redemption),
+            &key_bob,
         )
         .await;
+    test_kit.create_block();
+    api.assert_tx_fail(tx_reused.object_hash()).await;
+    api.assert_tx_fail(tx_replayed.object_hash()).await;
 
-    let (tx_administration, key_administration) = api
-        .create_administration_with_random_key(administration1::NAME, &None, &empty_polygon())
+    let report = api
+        .get_invitation_report(&key_administration.public_key())
         .await;
+    assert_eq!((report.used, report.expired, report.unused), (1, 0, 2));
 
+    // Invitation follows participant to the new key.
+    let key_alice_new = KeyPair::random();
+    let tx_rotate = api.rotate_participant_key(&key_alice, &key_alice_new).await;
     test_kit.create_block();
-
-    let now = time_provider.time();
-
-    let election_address = hash(&KeyPair::random().secret_key()[..]);
-
-    let create_election_tx = api
-        .issue_election(
-            election_address,
-            election1::NAME,
-            &now,
-            &(now + Duration::hours(1)),
-            election1::OPTIONS,
-            &key_administration,
+    api.assert_tx_successful(tx_rotate.object_hash()).await;
+    let report = api
+        .get_invitation_report(&key_administration.public_key())
+        .await;
+    assert!(report.invitations.iter().any(
+        |invitation| invitation.used_by.0 == Some(pub_key_address(key_alice_new.public_key()))
+    ));
+
+    time_provider.add_time(Duration::hours(2));
+    test_kit.create_blocks_until(test_kit.height().next().next());
+
+    let tx_expired = api
+        .create_invited_participant(
+            participant3::NAME,
+            participant3::PASS_CODE,
+            &key_administration.public_key(),
+            codes[1],
+            &key_carol,
         )
         .await;
-
     test_kit.create_block();
+    api.assert_tx_fail(tx_expired.object_hash()).await;
 
-    api.assert_tx_successful(create_election_tx.object_hash())
-        .await;
-
-    let elections = api
-        .get_active_elections(&author_address(&tx_administration))
+    let report = api
+        .get_invitation_report(&key_administration.public_key())
         .await;
+    assert_eq!((report.used, report.expired, report.unused), (1, 2, 0));
+    assert_eq!(report.invitations.len(), codes.len());
+}
 
-    assert_eq!(elections.len(), 1);
-
-    let election = &elections[0];
-
-    let options = &election.options;
-
-    assert_eq!(options.len(), 3);
-
-    let mut expected: HashMap<i32, u32> = options.iter().map(|op| (op.id, 0)).collect();
+#[tokio::test]
+async fn administration_areas_are_exported_as_geojson() {
+    let (mut test_kit, api, _) = create_test_kit();
 
-    let results = api.get_election_result(election.addr).await;
+    let country_geometry: Geometry = serde_json::from_value(serde_json::json!({
+        "type": "MultiPolygon",
+        "coordinates": [[
+            [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0], [0.0, 0.0]],
+            [[2.0, 2.0], [2.0, 3.0], [3.0, 3.0], [3.0, 2.0], [2.0, 2.0]]
+        ]]
+    }))
+    .unwrap();
+    let country_area = MultiPolygon::try_from(&country_geometry).unwrap();
+    assert_eq!(country_area.polygons.len(), 1);
+    assert_eq!(country_area.polygons[0].interiors.len(), 1);
+
+    let (_, key_country) = api
+        .create_administration_with_random_key(administration1::NAME, None, &country_area)
+        .await;
+    test_kit.create_block();
+    let (_, key_region) = api
+        .create_administration_with_random_key(
+            administration2::NAME,
+            Some(&key_country),
+            &square_area(5.0, 5.0, 5.0),
+        )
+        .await;
+    test_kit.create_block();
 
-    assert_eq!(results, expected);
+    let features = api.get_administrations_geojson().await.features;
+    assert_eq!(features.len(), 2);
+
+    let address = |keys: &KeyPair| serde_json::json!(pub_key_address(keys.public_key()));
+    let property =
+        |index: usize, name: &str| features[index].properties.as_ref().unwrap()[name].clone();
+    assert_eq!(property(0, "address"), address(&key_country));
+    assert_eq!(property(0, "name"), administration1::NAME);
+    assert_eq!(property(0, "level"), 0);
+    assert_eq!(property(0, "principal"), serde_json::Value::Null);
+    assert_eq!(property(1, "address"), address(&key_region));
+    assert_eq!(property(1, "name"), administration2::NAME);
+    assert_eq!(property(1, "level"), 1);
+    assert_eq!(property(1, "principal"), address(&key_country));
+
+    let exported = MultiPolygon::try_from(features[0].geometry.as_ref().unwrap()).unwrap();
+    assert_eq!(exported.object_hash(), country_area.object_hash());
+
+    let two_polygons: Geometry = serde_json::from_value(serde_json::json!({
+        "type": "MultiPolygon",
+        "coordinates": [
+            [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]]],
+            [[[2.0, 0.0], [3.0, 0.0], [3.0, 1.0], [2.0, 0.0]]]
+        ]
+    }))
+    .unwrap();
+    assert_eq!(
+        Polygon::try_from(&two_polygons).unwrap_err(),
+        GeoJsonError::MultiplePolygons
+    );
+}
 
-    let alice_position = options[0].id;
-    let bob_position = options[2].id;
+#[tokio::test]
+async fn multi_polygon_area_covers_exclaves() {
+    let (mut test_kit, api, _) = create_test_kit();
 
-    let tx_vote_alice = api.vote(election.addr, alice_position, &key_alice).await;
-    let tx_vote_bob = api.vote(election.addr, bob_position, &key_bob).await;
+    let (_, key_country) = api
+        .create_administration_with_random_key(administration1::NAME, None, &default_area())
+        .await;
     test_kit.create_block();
+    let district_area = MultiPolygon {
+        polygons: vec![square(0.0, 0.0, 2.0), square(6.0, 6.0, 2.0)],
+    };
+    let (tx_district, key_district) = api
+        .create_administration_with_random_key(
+            administration2::NAME,
+            Some(&key_country),
+            &district_area,
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_district.object_hash()).await;
 
-    api.assert_tx_successful(tx_vote_alice.object_hash()).await;
-    api.assert_tx_successful(tx_vote_bob.object_hash()).await;
-
-    *expected.get_mut(&bob_position).unwrap() += 1;
-    *expected.get_mut(&alice_position).unwrap() += 1;
-
-    let results = api.get_election_result(election.addr).await;
+    // Sibling may lie between parts of the district, but not overlap the exclave.
+    let (tx_between, _) = api
+        .create_administration_with_random_key(
+            administration2::NAME,
+            Some(&key_country),
+            &square_area(3.0, 3.0, 2.0),
+        )
+        .await;
+    let (tx_overlapping, _) = api
+        .create_administration_with_random_key(
+            administration2::NAME,
+            Some(&key_country),
+            &square_area(7.0, 7.0, 2.0),
+        )
+        .await;
+    let (_, key_alice) = api
+        .create_participant_with_random_key(
+            participant1::NAME,
+            participant1::EMAIL,
+            participant1::PHONE_NUMBER,
+            &None,
+            participant1::PASS_CODE,
+        )
+        .await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_between.object_hash()).await;
+    api.assert_tx_fail(tx_overlapping.object_hash()).await;
 
-    assert_eq!(results, expected);
+    let tx_exclave = api.submit_location([7.0, 7.0], &key_alice).await;
+    test_kit.create_block();
+    let tx_gap = api.submit_location([5.5, 1.0], &key_alice).await;
+    test_kit.create_block();
+    api.assert_tx_successful(tx_exclave.object_hash()).await;
+    api.assert_tx_successful(tx_gap.object_hash()).await;
+
+    let chains: Vec<_> = api
+        .get_locations(&key_alice.public_key())
+        .await
+        .into_iter()
+        .map(|location| location.administrations)
+        .collect();
+    let address = |keys: &KeyPair| pub_key_address(keys.public_key());
+    assert_eq!(
+        chains,
+        vec![
+            vec![address(&key_district), address(&key_country)],
+            vec![address(&key_country)],
+        ]
+    );
+
+    let features = api.get_administrations_geojson().await.features;
+    let district = features
+        .iter()
+        .find(|feature| {
+            feature.properties.as_ref().unwrap()["address"]
+                == serde_json::json!(address(&key_district))
+        })
+        .unwrap();
+    let exported = MultiPolygon::try_from(district.geometry.as_ref().unwrap()).unwrap();
+    assert_eq!(exported.object_hash(), district_area.object_hash());
 }
@@ -14,6 +14,13 @@ pub mod participant2 {
     pub const PASS_CODE: &str = "AA000001";
 }
 
+pub mod participant3 {
+    pub const NAME: &str = "Carol";
+    pub const EMAIL: &str = "carol@example.com";
+    pub const PHONE_NUMBER: &str = "+380710000002";
+    pub const PASS_CODE: &str = "AA000002";
+}
+
 pub mod administration1 {
     pub const NAME: &str = "Administration1";
 }
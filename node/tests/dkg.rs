@@ -0,0 +1,166 @@
+use exonum::crypto::{hash, KeyPair, PublicKey};
+
+use crypto_election_node::{
+    crypto::{
+        ballot::{self, PartialDecryption},
+        dkg::{Dealing, SharedKey},
+        elgamal::{Ciphertext, EncryptionKey},
+        Scalar,
+    },
+    model::{
+        encryption::{self, TransportKey},
+        ElectionAddress,
+    },
+};
+
+const TRUSTEES: usize = 4;
+
+struct Trustee {
+    key: PublicKey,
+    secret: Scalar,
+    transport_key: TransportKey,
+}
+
+fn trustees(election: &ElectionAddress) -> Vec<Trustee> {
+    let mut rng = rand::thread_rng();
+    (0..TRUSTEES)
+        .map(|_| {
+            let keypair = KeyPair::random();
+            let secret = encryption::transport_secret(keypair.secret_key(), election);
+            let transport_key =
+                TransportKey::create(election, &keypair.public_key(), &secret, &mut rng);
+            Trustee {
+                key: keypair.public_key(),
+                secret,
+                transport_key,
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn tally_is_decrypted_by_threshold_of_qualified_trustees() {
+    let mut rng = rand::thread_rng();
+    let election = hash(b"election");
+    let trustees = trustees(&election);
+    let threshold = encryption::decryption_threshold(TRUSTEES);
+    assert_eq!(threshold, 2);
+
+    // The last trustee is offline and publishes no transport key.
+    let transport_keys: Vec<Option<EncryptionKey>> = trustees
+        .iter()
+        .take(TRUSTEES - 1)
+        .map(|trustee| Some(trustee.transport_key.key))
+        .chain(Some(None))
+        .collect();
+    let mut dealings: Vec<Dealing> = trustees
+        .iter()
+        .take(TRUSTEES - 1)
+        .map(|trustee| {
+            let context = encryption::trustee_context(&election, &trustee.key);
+            let dealing = Dealing::deal(
+                &context,
+                threshold,
+                &trustee.secret,
+                &transport_keys,
+                &mut rng,
+            );
+            assert!(dealing.verify(&context, threshold, TRUSTEES));
+            dealing
+        })
+        .collect();
+    assert_eq!(dealings[0].shares[TRUSTEES - 1], None);
+
+    // Dealer 2 corrupts the share of trustee 0, which complains about it.
+    let corrupted = dealings[2].shares[0].unwrap() + Scalar::one();
+    dealings[2].shares[0] = Some(corrupted);
+    let (complainer, dealer) = (&trustees[0], &trustees[2]);
+    let context = encryption::trustee_context(&election, &complainer.key);
+    let shared_key = SharedKey::reveal(
+        &context,
+        &complainer.secret,
+        &dealer.transport_key.key,
+        &mut rng,
+    );
+    assert!(shared_key.verify(
+        &context,
+        &complainer.transport_key.key,
+        &dealer.transport_key.key
+    ));
+    let dealer_context = encryption::trustee_context(&election, &dealer.key);
+    assert_eq!(
+        dealings[2].open_share(&dealer_context, 0, &shared_key.key),
+        None
+    );
+    assert!(dealings[1]
+        .open_share(
+            &encryption::trustee_context(&election, &trustees[1].key),
+            0,
+            &(complainer.secret * trustees[1].transport_key.key.0),
+        )
+        .is_some());
+
+    // Dealer 2 is disqualified.
+    let qualified = &dealings[..2];
+    let joint_key =
+        EncryptionKey::combine(&qualified.iter().map(Dealing::key_share).collect::<Vec<_>>());
+    let tally = vec![
+        Ciphertext::encrypt(&joint_key, 3, &Scalar::random(&mut rng)),
+        Ciphertext::encrypt(&joint_key, 5, &Scalar::random(&mut rng)),
+    ];
+
+    // Any two trustees with valid shares decrypt the tally.
+    for positions in &[[0, 1], [1, 2], [0, 2]] {
+        let decryptions: Vec<(usize, PartialDecryption)> = positions
+            .iter()
+            .map(|&position| {
+                let trustee = &trustees[position];
+                let share = encryption::secret_share(
+                    &election,
+                    position,
+                    &trustee.secret,
+                    trustees
+                        .iter()
+                        .zip(qualified)
+                        .map(|(dealer, dealing)| (&dealer.key, &dealer.transport_key, dealing)),
+                )
+                .unwrap();
+                let share_key: EncryptionKey = EncryptionKey(
+                    qualified
+                        .iter()
+                        .map(|dealing| dealing.share_key(position))
+                        .sum(),
+                );
+                assert_eq!(EncryptionKey::from_secret(&share), share_key);
+
+                let context = encryption::trustee_context(&election, &trustee.key);
+                let decryption = PartialDecryption::create(&context, &share, &tally, &mut rng);
+                assert!(decryption.verify(&context, &share_key, &tally));
+                (position, decryption)
+            })
+            .collect();
+
+        assert_eq!(
+            ballot::decrypt_tally(&tally, &decryptions, 10),
+            Some(vec![3, 5])
+        );
+    }
+
+    // One trustee alone can not decrypt.
+    let share = encryption::secret_share(
+        &election,
+        0,
+        &trustees[0].secret,
+        trustees
+            .iter()
+            .zip(qualified)
+            .map(|(dealer, dealing)| (&dealer.key, &dealer.transport_key, dealing)),
+    )
+    .unwrap();
+    let context = encryption::trustee_context(&election, &trustees[0].key);
+    let decryption = PartialDecryption::create(&context, &share, &tally, &mut rng);
+    assert_ne!(
+        ballot::decrypt_tally(&tally, &[(0, decryption)], 10),
+        Some(vec![3, 5])
+    );
+}
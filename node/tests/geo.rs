@@ -0,0 +1,126 @@
+use std::convert::TryFrom;
+
+use exonum_merkledb::BinaryValue;
+use geojson::Geometry;
+use proptest::prelude::*;
+
+use crypto_election_node::model::geo::{Coordinate, LineString, PointLocation, Polygon};
+
+const RANGE: i64 = 1_000;
+
+fn coordinate() -> impl Strategy<Value = Coordinate> {
+    (-RANGE..=RANGE, -RANGE..=RANGE).prop_map(|(x, y)| Coordinate { x, y })
+}
+
+fn offset() -> impl Strategy<Value = Coordinate> {
+    (-170_000_000..=170_000_000i64, -80_000_000..=80_000_000i64)
+        .prop_map(|(x, y)| Coordinate { x, y })
+}
+
+fn polygon() -> impl Strategy<Value = Polygon> {
+    prop::collection::vec(coordinate(), 3..10).prop_map(|mut items| {
+        items.push(items[0].clone());
+        Polygon {
+            exterior: LineString { items },
+            interiors: vec![],
+        }
+    })
+}
+
+fn rectangle(min_x: i64, min_y: i64, max_x: i64, max_y: i64) -> Polygon {
+    Polygon {
+        exterior: LineString {
+            items: vec![
+                Coordinate { x: min_x, y: min_y },
+                Coordinate { x: max_x, y: min_y },
+                Coordinate { x: max_x, y: max_y },
+                Coordinate { x: min_x, y: max_y },
+                Coordinate { x: min_x, y: min_y },
+            ],
+        },
+        interiors: vec![],
+    }
+}
+
+fn translate(polygon: &Polygon, offset: &Coordinate) -> Polygon {
+    let translate_ring = |ring: &LineString| LineString {
+        items: ring
+            .items
+            .iter()
+            .map(|c| translate_point(c, offset))
+            .collect(),
+    };
+    Polygon {
+        exterior: translate_ring(&polygon.exterior),
+        interiors: polygon.interiors.iter().map(translate_ring).collect(),
+    }
+}
+
+fn translate_point(point: &Coordinate, offset: &Coordinate) -> Coordinate {
+    Coordinate {
+        x: point.x + offset.x,
+        y: point.y + offset.y,
+    }
+}
+
+proptest! {
+    #[test]
+    fn serialization_round_trip_is_bit_identical(polygon in polygon(), point in coordinate()) {
+        let bytes = polygon.to_bytes();
+        let decoded = Polygon::from_bytes(bytes.clone().into()).unwrap();
+
+        prop_assert_eq!(decoded.to_bytes(), bytes);
+        prop_assert_eq!(decoded.locate(&point), polygon.locate(&point));
+        prop_assert_eq!(decoded.is_valid(), polygon.is_valid());
+    }
+
+    #[test]
+    fn geojson_round_trip_is_bit_identical(polygon in polygon(), offset in offset()) {
+        let polygon = translate(&polygon, &offset);
+        let decoded = Polygon::try_from(&Geometry::from(&polygon)).unwrap();
+
+        prop_assert_eq!(decoded.to_bytes(), polygon.to_bytes());
+    }
+
+    #[test]
+    fn predicates_are_translation_invariant(
+        first in polygon(),
+        second in polygon(),
+        point in coordinate(),
+        offset in offset(),
+    ) {
+        let moved_first = translate(&first, &offset);
+        let moved_second = translate(&second, &offset);
+        let moved_point = translate_point(&point, &offset);
+
+        prop_assert_eq!(moved_first.locate(&moved_point), first.locate(&point));
+        prop_assert_eq!(moved_first.is_valid(), first.is_valid());
+        prop_assert_eq!(moved_first.overlaps(&moved_second), first.overlaps(&second));
+        prop_assert_eq!(
+            moved_first.contains_polygon(&moved_second),
+            first.contains_polygon(&second)
+        );
+    }
+
+    #[test]
+    fn rectangle_locations_match_reference(
+        (min_x, max_x) in (-RANGE..RANGE).prop_flat_map(|min| (Just(min), min + 1..=RANGE)),
+        (min_y, max_y) in (-RANGE..RANGE).prop_flat_map(|min| (Just(min), min + 1..=RANGE)),
+        point in coordinate(),
+    ) {
+        let polygon = rectangle(min_x, min_y, max_x, max_y);
+        let within_x = point.x >= min_x && point.x <= max_x;
+        let within_y = point.y >= min_y && point.y <= max_y;
+        let on_edge = point.x == min_x || point.x == max_x || point.y == min_y || point.y == max_y;
+        let expected = if !(within_x && within_y) {
+            PointLocation::Outside
+        } else if on_edge {
+            PointLocation::Boundary
+        } else {
+            PointLocation::Inside
+        };
+
+        prop_assert!(polygon.is_valid());
+        prop_assert_eq!(polygon.locate(&point), expected);
+    }
+}
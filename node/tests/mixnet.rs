@@ -0,0 +1,53 @@
+use crypto_election_node::crypto::{
+    elgamal::{Ciphertext, EncryptionKey},
+    mixnet::{BallotList, ShuffleProof},
+    Scalar,
+};
+
+const CONTEXT: &[u8] = b"mixer";
+
+fn ballots(key: &EncryptionKey, count: u64, width: u64) -> BallotList {
+    let mut rng = rand::thread_rng();
+    BallotList {
+        width: width as usize,
+        ballots: (0..count)
+            .map(|ballot| {
+                (0..width)
+                    .map(|value| {
+                        Ciphertext::encrypt(key, ballot * width + value, &Scalar::random(&mut rng))
+                    })
+                    .collect()
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn shuffle_proof_is_verified_and_bound_to_shuffle() {
+    let mut rng = rand::thread_rng();
+    let key = EncryptionKey::from_secret(&Scalar::random(&mut rng));
+    let input = ballots(&key, 5, 3);
+
+    let (output, opening) = input.random_shuffle(&key, &mut rng);
+    let proof = ShuffleProof::prove(CONTEXT, &key, &input, &output, &opening, &mut rng);
+    assert!(proof.verify(CONTEXT, &key, &input, &output));
+    assert!(!proof.verify(b"another mixer", &key, &input, &output));
+
+    let bytes = proof.to_bytes();
+    assert_eq!(bytes.len(), 8 + (5 * 4 + 3 + 4) * 32);
+    assert_eq!(ShuffleProof::from_bytes(&bytes), Some(proof.clone()));
+    assert_eq!(ShuffleProof::from_bytes(&bytes[1..]), None);
+
+    // Output with a replaced ballot is not a shuffle of the input.
+    let mut forged = output.clone();
+    forged.ballots[0] = ballots(&key, 1, 3).ballots.remove(0);
+    assert!(!proof.verify(CONTEXT, &key, &input, &forged));
+    let proof = ShuffleProof::prove(CONTEXT, &key, &input, &forged, &opening, &mut rng);
+    assert!(!proof.verify(CONTEXT, &key, &input, &forged));
+
+    // Empty list is shuffled as well.
+    let empty = ballots(&key, 0, 3);
+    let (output, opening) = empty.random_shuffle(&key, &mut rng);
+    let proof = ShuffleProof::prove(CONTEXT, &key, &empty, &output, &opening, &mut rng);
+    assert!(proof.verify(CONTEXT, &key, &empty, &output));
+}